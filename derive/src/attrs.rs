@@ -0,0 +1,217 @@
+use crate::TokenStream2;
+use quote::quote;
+
+/// Collects the `///` comments, the leading space of each line is removed like rustdoc does
+pub(crate) fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    let mut lines = Vec::new();
+
+    for attr in attrs {
+        if let syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit),
+                    ..
+                }),
+            ..
+        }) = &attr.meta
+        {
+            if path.is_ident("doc") {
+                let line = lit.value();
+                lines.push(
+                    line.strip_prefix(' ')
+                        .unwrap_or(&line)
+                        .trim_end()
+                        .to_owned(),
+                );
+            }
+        }
+    }
+
+    lines.join("\n").trim().to_owned()
+}
+
+/// How `rename_all` changes the names of fields or variants
+#[derive(Clone, Copy)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    const ALL: &'static [(&'static str, Self)] = &[
+        ("lowercase", Self::Lower),
+        ("UPPERCASE", Self::Upper),
+        ("PascalCase", Self::Pascal),
+        ("camelCase", Self::Camel),
+        ("snake_case", Self::Snake),
+        ("SCREAMING_SNAKE_CASE", Self::ScreamingSnake),
+        ("kebab-case", Self::Kebab),
+        ("SCREAMING-KEBAB-CASE", Self::ScreamingKebab),
+    ];
+
+    fn parse(lit: &syn::LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        Self::ALL
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, rule)| *rule)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|(name, _)| *name).collect();
+                syn::Error::new_spanned(
+                    lit,
+                    format!("unknown rename rule, expected one of {}", names.join(", ")),
+                )
+            })
+    }
+
+    /// Apply the rule to a field name, which is expected to be in snake_case
+    pub(crate) fn apply_to_field(self, name: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => name.to_owned(),
+            Self::Upper | Self::ScreamingSnake => name.to_ascii_uppercase(),
+            Self::Pascal => name
+                .split('_')
+                .map(|part| {
+                    let mut it = part.chars();
+                    it.next()
+                        .map(|c| c.to_ascii_uppercase().to_string() + it.as_str())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            Self::Camel => {
+                let pascal = Self::Pascal.apply_to_field(name);
+                let mut it = pascal.chars();
+                it.next()
+                    .map(|c| c.to_ascii_lowercase().to_string() + it.as_str())
+                    .unwrap_or_default()
+            }
+            Self::Kebab => name.replace('_', "-"),
+            Self::ScreamingKebab => name.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Apply the rule to a variant name, which is expected to be in PascalCase
+    pub(crate) fn apply_to_variant(self, name: &str) -> String {
+        match self {
+            Self::Pascal => name.to_owned(),
+            Self::Lower => name.to_ascii_lowercase(),
+            Self::Upper => name.to_ascii_uppercase(),
+            Self::Camel => {
+                let mut it = name.chars();
+                it.next()
+                    .map(|c| c.to_ascii_lowercase().to_string() + it.as_str())
+                    .unwrap_or_default()
+            }
+            Self::Snake => {
+                let mut ret = String::new();
+                for (i, c) in name.char_indices() {
+                    if i != 0 && c.is_uppercase() {
+                        ret.push('_');
+                    }
+                    ret.push(c.to_ascii_lowercase());
+                }
+                ret
+            }
+            Self::ScreamingSnake => Self::Snake.apply_to_variant(name).to_ascii_uppercase(),
+            Self::Kebab => Self::Snake.apply_to_variant(name).replace('_', "-"),
+            Self::ScreamingKebab => Self::ScreamingSnake
+                .apply_to_variant(name)
+                .replace('_', "-"),
+        }
+    }
+}
+
+/// The `#[logix(...)]` attributes of a struct, an enum or an enum variant
+#[derive(Default)]
+pub(crate) struct TypeAttrs {
+    /// The name used in the logix file, set by `rename = "name"`
+    pub rename: Option<String>,
+    /// Changes the names of the fields, or variants for an enum, set by `rename_all = "rule"`
+    pub rename_all: Option<RenameRule>,
+    /// Additional names accepted when parsing a variant, set by `alias = "name"`
+    pub aliases: Vec<String>,
+}
+
+impl TypeAttrs {
+    pub(crate) fn parse(attrs: &[syn::Attribute], allow_alias: bool) -> syn::Result<Self> {
+        let mut ret = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("logix")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    ret.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("rename_all") {
+                    ret.rename_all = Some(RenameRule::parse(&meta.value()?.parse()?)?);
+                    Ok(())
+                } else if allow_alias && meta.path.is_ident("alias") {
+                    ret.aliases
+                        .push(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported logix attribute"))
+                }
+            })?;
+        }
+
+        Ok(ret)
+    }
+}
+
+/// The `#[logix(...)]` attributes of a struct field
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    /// The expression used when the member is left out, set by `default` or `default = expr`
+    pub default: Option<TokenStream2>,
+    /// The field is never parsed or written, set by `skip`
+    pub skip: bool,
+    /// The name used in the logix file, set by `rename = "name"`
+    pub rename: Option<String>,
+    /// Additional names accepted when parsing, set by `alias = "name"`
+    pub aliases: Vec<String>,
+}
+
+impl FieldAttrs {
+    pub(crate) fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut ret = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("logix")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    ret.default = Some(if meta.input.peek(syn::Token![=]) {
+                        let expr: syn::Expr = meta.value()?.parse()?;
+                        quote!(#expr)
+                    } else {
+                        quote!(::std::default::Default::default())
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    ret.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    ret.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("alias") {
+                    ret.aliases
+                        .push(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported logix attribute"))
+                }
+            })?;
+        }
+
+        Ok(ret)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.default.is_none() && !self.skip && self.rename.is_none() && self.aliases.is_empty()
+    }
+}
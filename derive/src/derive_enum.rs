@@ -1,4 +1,7 @@
-use crate::{Shared, TokenStream2, Types};
+use crate::{
+    attrs::{doc_comment, TypeAttrs},
+    Shared, TokenStream2, Types,
+};
 use quote::quote;
 
 pub(crate) fn do_any(
@@ -16,41 +19,60 @@ pub(crate) fn do_any(
                 ..
             },
         impl_gen,
+        ty_gen,
+        gen_marker,
+        rename_all,
     }: &Shared,
     variants: impl IntoIterator<Item = syn::Variant>,
-) -> (TokenStream2, TokenStream2) {
+) -> syn::Result<(TokenStream2, TokenStream2, TokenStream2)> {
     let mut variants_desc = Vec::new();
     let mut variant_parsers = Vec::new();
+    let mut variant_writers = Vec::new();
     let mut variant_names_str = Vec::new();
+    let mut variant_patterns = Vec::new();
+    let mut variant_docs = Vec::new();
 
     for variant in variants {
+        let attrs = TypeAttrs::parse(&variant.attrs, true)?;
+        variant_docs.push(doc_comment(&variant.attrs));
         let variant_name = variant.ident;
         let shared = Shared {
             prefix: quote!(#prefix #type_name::),
             types: types.clone(),
-            type_name_str: variant_name.to_string(),
+            type_name_str: match (attrs.rename, rename_all) {
+                (Some(name), _) => name,
+                (None, Some(rule)) => rule.apply_to_variant(&variant_name.to_string()),
+                (None, None) => variant_name.to_string(),
+            },
             type_name: variant_name,
             impl_gen: impl_gen.clone(),
+            ty_gen: ty_gen.clone(),
+            gen_marker: gen_marker.clone(),
+            rename_all: attrs.rename_all,
         };
-        let (value_desc, parse) = crate::derive_struct::do_any(&shared, variant.fields, true);
+        let (value_desc, parse, write) =
+            crate::derive_struct::do_any(&shared, variant.fields, true)?;
         variants_desc.push(value_desc);
         variant_parsers.push(parse);
+        variant_writers.push(write);
+        let (variant_name_str, aliases) = (&shared.type_name_str, attrs.aliases);
+        variant_patterns.push(quote!(#variant_name_str #(| #aliases)*));
         variant_names_str.push(shared.type_name_str);
     }
 
-    (
+    Ok((
         quote!(
             #LogixValueDescriptor::Enum {
                 variants: vec![#(#LogixTypeDescriptor {
                     name: #variant_names_str,
-                    doc: "",
+                    doc: #variant_docs,
                     value: #variants_desc
                  },)*],
             }
         ),
         quote!(
             match p.next_token()? {
-                #((type_name_span, #Token::Ident(#variant_names_str)) => {
+                #((type_name_span, #Token::Ident(#variant_patterns)) => {
                     #variant_parsers
                 })*
                 (span, token) => {
@@ -63,5 +85,10 @@ pub(crate) fn do_any(
                 }
             }
         ),
-    )
+        if variant_writers.is_empty() {
+            quote!(match *self {})
+        } else {
+            quote!(match self { #(#variant_writers)* })
+        },
+    ))
 }
@@ -1,15 +1,19 @@
-use crate::{Shared, TokenStream2, Types};
-use quote::quote;
+use crate::{
+    attrs::{doc_comment, FieldAttrs},
+    Shared, TokenStream2, Types,
+};
+use quote::{format_ident, quote};
 
+/// Returns the value descriptor, the parser and a match arm that writes the value
 pub(crate) fn do_any(
     shared: &Shared,
     fields: syn::Fields,
     skip_struct_ident: bool,
-) -> (TokenStream2, TokenStream2) {
+) -> syn::Result<(TokenStream2, TokenStream2, TokenStream2)> {
     match fields {
         syn::Fields::Named(fields) => do_named(shared, fields, skip_struct_ident),
         syn::Fields::Unnamed(fields) => do_unnamed(shared, fields, skip_struct_ident),
-        syn::Fields::Unit => do_unit(shared, skip_struct_ident),
+        syn::Fields::Unit => Ok(do_unit(shared, skip_struct_ident)),
     }
 }
 
@@ -26,9 +30,12 @@ pub(crate) fn do_unit(
                 ..
             },
         impl_gen: _,
+        ty_gen: _,
+        gen_marker: _,
+        rename_all: _,
     }: &Shared,
     skip_struct_ident: bool,
-) -> (TokenStream2, TokenStream2) {
+) -> (TokenStream2, TokenStream2, TokenStream2) {
     let req_struct_ident = if skip_struct_ident {
         quote!()
     } else {
@@ -48,6 +55,9 @@ pub(crate) fn do_unit(
                 span: type_name_span,
             })
         ),
+        quote!(
+            #prefix #type_name => w.write_ident(#type_name_str),
+        ),
     )
 }
 
@@ -59,7 +69,9 @@ pub(crate) fn do_named(
         types:
             Types {
                 LogixValueDescriptor,
+                LogixMemberDescriptor,
                 LogixType,
+                ParseResult,
                 ParseError,
                 Value,
                 Token,
@@ -69,17 +81,24 @@ pub(crate) fn do_named(
                 ..
             },
         impl_gen,
+        ty_gen,
+        gen_marker,
+        rename_all,
     }: &Shared,
     fields: syn::FieldsNamed,
     skip_struct_ident: bool,
-) -> (TokenStream2, TokenStream2) {
+) -> syn::Result<(TokenStream2, TokenStream2, TokenStream2)> {
     let mut members_desc = Vec::new();
     let mut member_names = Vec::new();
+    let mut skipped_names = Vec::new();
+    let mut skipped_values = Vec::new();
+    let mut member_bindings = Vec::new();
     let mut member_str_names = Vec::new();
+    let mut member_patterns = Vec::new();
     let mut member_tmp_types = Vec::new();
     let mut member_tmp_init = Vec::new();
     let mut member_tmp_parse = Vec::new();
-    let mut member_tmp_assign = Vec::new();
+    let mut member_tmp_check = Vec::new();
 
     let req_struct_ident = if skip_struct_ident {
         quote!()
@@ -87,11 +106,42 @@ pub(crate) fn do_named(
         quote!(let type_name_span = p.req_token(#type_name_str, #Token::Ident(#type_name_str))?;)
     };
 
-    for field in fields.named {
+    for (i, field) in fields.named.into_iter().enumerate() {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        let doc = doc_comment(&field.attrs);
         let fname = field.ident.unwrap();
-        let fname_str = fname.to_string();
+        let fname_str = match (attrs.rename, rename_all) {
+            (Some(name), _) => name,
+            (None, Some(rule)) => rule.apply_to_field(&fname.to_string()),
+            (None, None) => fname.to_string(),
+        };
         let ty = field.ty;
-        members_desc.push(quote!((#fname_str, <#ty as #LogixType>::descriptor())));
+        let aliases = attrs.aliases;
+
+        if attrs.skip {
+            skipped_values.push(
+                attrs
+                    .default
+                    .unwrap_or_else(|| quote!(::std::default::Default::default())),
+            );
+            skipped_names.push(fname);
+            continue;
+        }
+
+        let default_value = match &attrs.default {
+            Some(expr) => quote!(Some(#expr)),
+            None => quote!(<#ty as #LogixType>::default_value()),
+        };
+        let optional = match attrs.default {
+            Some(_) => quote!(true),
+            None => quote!(<#ty as #LogixType>::default_value().is_some()),
+        };
+        members_desc.push(quote!(#LogixMemberDescriptor {
+            name: #fname_str,
+            doc: #doc,
+            optional: #optional,
+            ty: <#ty as #LogixType>::descriptor(),
+        }));
         member_tmp_init.push(quote!(None));
         member_tmp_parse.push(quote!(
             if tmp.#fname.is_some() {
@@ -103,21 +153,30 @@ pub(crate) fn do_named(
             }
             tmp.#fname = Some(<#ty as #LogixType>::logix_parse(p)?.value)
         ));
-        member_tmp_assign.push(quote!(
-            tmp.#fname
-                .or_else(|| <#ty as #LogixType>::default_value())
-                .ok_or_else(|| #ParseError::MissingStructMember {
-                    span: curly_span.clone(),
+        let binding = format_ident!("m_{i}");
+        member_tmp_check.push(quote!(
+            let #binding = tmp.#fname.or_else(|| #default_value);
+            if #binding.is_none() && !failed.contains(&#fname_str) {
+                let error = #ParseError::MissingStructMember {
+                    span: curly_span,
                     type_name: #type_name_str,
                     member: #fname_str,
-                })?
+                };
+                if p.is_recovering() {
+                    errors.push(error);
+                } else {
+                    return Err(error);
+                }
+            }
         ));
         member_tmp_types.push(ty);
         member_names.push(fname);
+        member_patterns.push(quote!(#fname_str #(| #aliases)*));
         member_str_names.push(fname_str);
+        member_bindings.push(binding);
     }
 
-    (
+    Ok((
         quote!(
             #LogixValueDescriptor::Struct {
                 members: vec![#(#members_desc,)*],
@@ -126,46 +185,80 @@ pub(crate) fn do_named(
         quote!(
             #req_struct_ident
             {
+                // NOTE(2024.04): Skipped fields are left out, so the marker uses the generics they might need
                 struct Tmp #impl_gen {
                     #(#member_names: Option<#member_tmp_types>,)*
+                    __marker: #gen_marker,
                 }
-                let mut tmp = Tmp {
+                let mut tmp: Tmp #ty_gen = Tmp {
                     #(#member_names: #member_tmp_init,)*
+                    __marker: ::std::marker::PhantomData,
                 };
 
+                let mut errors = ::std::vec::Vec::new();
+                let mut failed: ::std::vec::Vec<&'static str> = ::std::vec::Vec::new();
+
                 let mut curly_span = p.req_token(#type_name_str, #Token::Brace { start: true, brace: #Brace::Curly })?;
                 p.req_token(#type_name_str, #Token::Newline(false))?;
+                let depth = p.depth();
                 'parse_members: loop {
-                    match p.next_token()? {
-                        #((span, #Token::Ident(#member_str_names)) => {
-                            p.req_token(#type_name_str, #Token::Delim(#Delim::Colon))?;
-                            #member_tmp_parse;
-                            p.req_token(#type_name_str, #Token::Newline(false))?;
-                        })*
-                        (span, #Token::Brace { start: false, brace: #Brace::Curly }) => {
+                    let res = (|| -> #ParseResult<Option<_>> {
+                        match p.next_token()? {
+                            #((span, #Token::Ident(#member_patterns)) => {
+                                let res = (|| -> #ParseResult<()> {
+                                    p.req_token(#type_name_str, #Token::Delim(#Delim::Colon))?;
+                                    #member_tmp_parse;
+                                    p.req_token(#type_name_str, #Token::Newline(false))?;
+                                    Ok(())
+                                })();
+                                if res.is_err() {
+                                    failed.push(#member_str_names);
+                                }
+                                res.map(|()| None)
+                            })*
+                            (span, #Token::Brace { start: false, brace: #Brace::Curly }) => Ok(Some(span)),
+                            (span, token) => Err(#ParseError::UnexpectedToken {
+                                span,
+                                while_parsing: #type_name_str,
+                                wanted: #Wanted::Tokens(&[
+                                    #Token::Brace { start: false, brace: #Brace::Curly },
+                                    #(#Token::Ident(#member_str_names),)*
+                                ]),
+                                got_token: token.token_type_name(),
+                            }),
+                        }
+                    })();
+                    match res {
+                        Ok(Some(span)) => {
                             curly_span = span;
                             break 'parse_members;
                         }
-                        (span, token) => return Err(#ParseError::UnexpectedToken {
-                            span,
-                            while_parsing: #type_name_str,
-                            wanted: #Wanted::Tokens(&[
-                                #Token::Brace { start: false, brace: #Brace::Curly },
-                                #(#Token::Ident(#member_str_names),)*
-                            ]),
-                            got_token: token.token_type_name(),
-                        }),
+                        Ok(None) => {}
+                        Err(e) => p.recover(&mut errors, depth, #Brace::Curly, e)?,
                     }
                 }
+
+                #(#member_tmp_check)*
+                p.finish_recovery(errors)?;
+
                 Ok(#Value {
                     value: #prefix #type_name {
-                        #(#member_names: #member_tmp_assign,)*
+                        #(#member_names: #member_bindings.unwrap(),)*
+                        #(#skipped_names: #skipped_values,)*
                     },
                     span: type_name_span,
+                }.join_with_span(curly_span))
+            }
+        ),
+        quote!(
+            #prefix #type_name { #(#member_names: #member_bindings,)* .. } => {
+                w.write_struct(#type_name_str, |w| {
+                    #(w.write_member(#member_str_names, #member_bindings)?;)*
+                    Ok(())
                 })
             }
         ),
-    )
+    ))
 }
 
 pub(crate) fn do_unnamed(
@@ -184,12 +277,15 @@ pub(crate) fn do_unnamed(
                 ..
             },
         impl_gen: _,
+        ty_gen: _,
+        gen_marker: _,
+        rename_all: _,
     }: &Shared,
     fields: syn::FieldsUnnamed,
     skip_struct_ident: bool,
-) -> (TokenStream2, TokenStream2) {
+) -> syn::Result<(TokenStream2, TokenStream2, TokenStream2)> {
     let mut members_desc = Vec::new();
-    let mut member_indices = Vec::new();
+    let mut member_bindings = Vec::new();
     let mut member_str_names = Vec::new();
     let mut member_parse = Vec::new();
 
@@ -200,17 +296,26 @@ pub(crate) fn do_unnamed(
     };
 
     for (i, field) in fields.unnamed.into_iter().enumerate() {
+        if !FieldAttrs::parse(&field.attrs)?.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &field,
+                "logix attributes are only supported on named fields",
+            ));
+        }
         let ty = field.ty;
         let fname_str = format!("#{i}");
         members_desc.push(quote!(<#ty as #LogixType>::descriptor()));
-        member_parse.push(quote!(<#ty as #LogixType>::logix_parse(p)?.value));
-        member_indices.push(i);
+        let comma =
+            (i != 0).then(|| quote!(p.req_token(#type_name_str, #Token::Delim(#Delim::Comma))?;));
+        member_parse.push(quote!({
+            #comma
+            Ok(<#ty as #LogixType>::logix_parse(p)?.value)
+        }));
+        member_bindings.push(format_ident!("m_{i}"));
         member_str_names.push(fname_str);
     }
 
-    let last_member_parse = member_parse.pop().into_iter();
-
-    (
+    Ok((
         quote!(
             #LogixValueDescriptor::Tuple {
                 members: vec![#(#members_desc,)*],
@@ -219,25 +324,26 @@ pub(crate) fn do_unnamed(
         quote!(
             #req_struct_ident
             p.req_token(#type_name_str, #Token::Brace { start: true, brace: #Brace::Paren })?;
+            let depth = p.depth();
+            #[allow(unused_mut)]
+            let mut errors = ::std::vec::Vec::new();
+            #(let #member_bindings = p.recover_item(&mut errors, depth, |p| #member_parse)?;)*
+            p.finish_recovery(errors)?;
+            // NOTE(2024.04): Every member is parsed if no errors were collected
+            let value = #prefix #type_name ( #(#member_bindings.unwrap(),)* );
+            let paren_span = p.req_token(#type_name_str, #Token::Brace { start: false, brace: #Brace::Paren })?;
             Ok(#Value {
-                value: #prefix #type_name (
-                    #({
-                        let value = #member_parse;
-
-                        p.req_token(#type_name_str, #Token::Delim(#Delim::Comma))?;
-
-                        value
-                    },)*
-                    #({
-                        let value = #last_member_parse;
-
-                        p.req_token(#type_name_str, #Token::Brace { start: false, brace: #Brace::Paren })?;
-
-                        value
-                    },)*
-                ),
+                value,
                 span: type_name_span,
-            })
+            }.join_with_span(paren_span))
         ),
-    )
+        quote!(
+            #prefix #type_name ( #(#member_bindings,)* ) => {
+                w.write_tuple(#type_name_str, |w| {
+                    #(w.write_item(#member_bindings)?;)*
+                    Ok(())
+                })
+            }
+        ),
+    ))
 }
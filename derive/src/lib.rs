@@ -1,5 +1,6 @@
 #![deny(warnings, clippy::all)]
 #![allow(non_snake_case)] // NOTE(2024.03.29): There appear to be a bug triggering this even when set on the Types struct
+mod attrs;
 mod derive_enum;
 mod derive_struct;
 
@@ -12,6 +13,7 @@ use syn::{parse_macro_input, DeriveInput};
 struct Types {
     LogixTypeDescriptor: TokenStream2,
     LogixValueDescriptor: TokenStream2,
+    LogixMemberDescriptor: TokenStream2,
     LogixType: TokenStream2,
     LogixVfs: TokenStream2,
     LogixParser: TokenStream2,
@@ -22,6 +24,7 @@ struct Types {
     Token: TokenStream2,
     Brace: TokenStream2,
     Delim: TokenStream2,
+    LogixWriter: TokenStream2,
 }
 
 struct Shared<'a> {
@@ -30,22 +33,54 @@ struct Shared<'a> {
     type_name: syn::Ident,
     types: Types,
     impl_gen: syn::ImplGenerics<'a>,
+    ty_gen: syn::TypeGenerics<'a>,
+    /// A `PhantomData` that uses every generic parameter
+    gen_marker: TokenStream2,
+    /// Applies to the fields of a struct or the variants of an enum
+    rename_all: Option<attrs::RenameRule>,
 }
 
 /// Derives the LogixType trait
-#[proc_macro_derive(LogixType)]
+///
+/// Fields of named structs accept these attributes:
+/// * `#[logix(default)]` the member is optional and defaults to `Default::default()`
+/// * `#[logix(default = expr)]` the member is optional and defaults to `expr`
+/// * `#[logix(skip)]` the field is neither parsed nor written, it is set to the default value
+/// * `#[logix(rename = "name")]` the member is called `name` in the logix file
+/// * `#[logix(alias = "name")]` the member can also be called `name` when parsing
+///
+/// Structs, enums and enum variants accept `#[logix(rename = "name")]` and
+/// `#[logix(rename_all = "rule")]`, where the rule is one of `lowercase`, `UPPERCASE`,
+/// `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case` or
+/// `SCREAMING-KEBAB-CASE`. On an enum it renames the variants, otherwise the fields. Enum
+/// variants also accept `#[logix(alias = "name")]`.
+#[proc_macro_derive(LogixType, attributes(logix))]
 pub fn impl_logix_type(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let (impl_gen, ty_gen, where_gen) = input.generics.split_for_impl();
+    let gen_marker = {
+        let lifetimes = input.generics.lifetimes().map(|l| &l.lifetime);
+        let types = input.generics.type_params().map(|t| &t.ident);
+        quote!(::std::marker::PhantomData<(#(&#lifetimes (),)* #(fn() -> #types,)*)>)
+    };
+
+    let attrs = match attrs::TypeAttrs::parse(&input.attrs, false) {
+        Ok(attrs) => attrs,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let doc = attrs::doc_comment(&input.attrs);
 
     let shared = Shared {
         prefix: quote!(),
-        type_name_str: input.ident.to_string(),
+        type_name_str: attrs.rename.unwrap_or_else(|| input.ident.to_string()),
         type_name: input.ident,
+        rename_all: attrs.rename_all,
         types: Types {
             LogixTypeDescriptor: quote!(::logix_type::type_trait::LogixTypeDescriptor),
             LogixValueDescriptor: quote!(::logix_type::type_trait::LogixValueDescriptor),
+            LogixMemberDescriptor: quote!(::logix_type::type_trait::LogixMemberDescriptor),
             LogixType: quote!(::logix_type::LogixType),
             LogixVfs: quote!(::logix_vfs::LogixVfs),
             LogixParser: quote!(::logix_type::LogixParser),
@@ -56,8 +91,11 @@ pub fn impl_logix_type(input: TokenStream) -> TokenStream {
             Token: quote!(::logix_type::token::Token),
             Brace: quote!(::logix_type::token::Brace),
             Delim: quote!(::logix_type::token::Delim),
+            LogixWriter: quote!(::logix_type::LogixWriter),
         },
         impl_gen,
+        ty_gen,
+        gen_marker,
     };
     let Shared {
         prefix: _,
@@ -71,31 +109,45 @@ pub fn impl_logix_type(input: TokenStream) -> TokenStream {
                 LogixParser,
                 ParseResult,
                 Value,
+                LogixWriter,
                 ..
             },
         impl_gen,
+        ty_gen,
+        gen_marker: _,
+        rename_all: _,
     } = &shared;
 
-    let (value_desc, parse) = match input.data {
-        syn::Data::Struct(data) => derive_struct::do_any(&shared, data.fields, false),
+    let res = match input.data {
+        syn::Data::Struct(data) => derive_struct::do_any(&shared, data.fields, false)
+            .map(|(value_desc, parse, write)| (value_desc, parse, quote!(match self { #write }))),
         syn::Data::Enum(data) => derive_enum::do_any(&shared, data.variants),
         syn::Data::Union(..) => return quote!(compile_error!("Union is not supported")).into(),
     };
+    let (value_desc, parse, write) = match res {
+        Ok(ret) => ret,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     let descriptor = quote!(
         #LogixTypeDescriptor {
             name: #type_name_str,
-            doc: "",
+            doc: #doc,
             value: #value_desc,
         }
     );
 
+    // NOTE(2024.04): A static inside a generic impl is shared by all instantiations, and a
+    // `OnceLock` deadlocks when a recursive type asks for its own descriptor, so every type
+    // looks up its descriptor by type instead
+    let descriptor = quote!(
+        ::logix_type::type_trait::type_descriptor::<Self>(#type_name_str, || #descriptor)
+    );
+
     let tokens = quote! {
         impl #impl_gen #LogixType for #type_name #ty_gen #where_gen {
             fn descriptor() -> &'static #LogixTypeDescriptor{
-                // NOTE(2023.11): Currently generics can't be used to make statics, so I need this work-around
-                static RET: std::sync::OnceLock<#LogixTypeDescriptor> = std::sync::OnceLock::new();
-                RET.get_or_init(|| #descriptor)
+                #descriptor
             }
 
             fn default_value() -> Option<Self> {
@@ -105,6 +157,10 @@ pub fn impl_logix_type(input: TokenStream) -> TokenStream {
             fn logix_parse<FS: #LogixVfs>(p: &mut #LogixParser<FS>) -> #ParseResult<#Value<Self>> {
                 #parse
             }
+
+            fn logix_write(&self, w: &mut #LogixWriter) -> ::logix_type::error::WriteResult {
+                #write
+            }
         }
     };
 
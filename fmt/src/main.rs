@@ -0,0 +1,114 @@
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use logix_type::format::format;
+
+const USAGE: &str = "\
+Usage: logix-fmt [--check] [FILE]...
+
+Formats .logix files in place, or stdin to stdout if no files are given.
+
+Options:
+  --check  Do not write anything, list the files that are not formatted and exit with 1
+  --help   Print this help
+";
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut files = Vec::new();
+    let mut only_files = false;
+
+    for arg in std::env::args_os().skip(1) {
+        match arg.to_str() {
+            Some("--") if !only_files => only_files = true,
+            Some("--check") if !only_files => check = true,
+            Some("-h" | "--help") if !only_files => {
+                print!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            Some(s) if !only_files && s.starts_with('-') && s != "-" => {
+                eprint!("error: unknown option {s:?}\n\n{USAGE}");
+                return ExitCode::from(2);
+            }
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+
+    let res = if files.is_empty() {
+        run_stdin(check)
+    } else {
+        run_files(&files, check)
+    };
+
+    match res {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Returns false if `--check` found differences
+fn run_stdin(check: bool) -> Result<bool, String> {
+    let mut src = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut src)
+        .map_err(|e| format!("failed to read stdin: {e}"))?;
+    let formatted = format(&src).map_err(|e| format!("<stdin>: {e}"))?;
+
+    if check {
+        if formatted.as_bytes() != src {
+            println!("<stdin>");
+            return Ok(false);
+        }
+    } else {
+        std::io::stdout()
+            .write_all(formatted.as_bytes())
+            .map_err(|e| format!("failed to write stdout: {e}"))?;
+    }
+    Ok(true)
+}
+
+/// Returns false if `--check` found differences, errors are reported but do not stop the run
+fn run_files(files: &[PathBuf], check: bool) -> Result<bool, String> {
+    let mut clean = true;
+    let mut failed = 0;
+
+    for path in files {
+        let res = std::fs::read(path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))
+            .and_then(|src| {
+                let formatted = format(&src).map_err(|e| format!("{}: {e}", path.display()))?;
+                Ok((src, formatted))
+            });
+
+        match res {
+            Ok((src, formatted)) if formatted.as_bytes() == src => {}
+            Ok(_) if check => {
+                println!("{}", path.display());
+                clean = false;
+            }
+            Ok((_, formatted)) => {
+                if let Err(e) = std::fs::write(path, formatted) {
+                    eprintln!("error: failed to write {}: {e}", path.display());
+                    failed += 1;
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                failed += 1;
+            }
+        }
+    }
+
+    if failed != 0 {
+        Err(format!("{failed} of {} files failed", files.len()))
+    } else {
+        Ok(clean)
+    }
+}
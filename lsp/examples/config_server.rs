@@ -0,0 +1,27 @@
+//! A language server for the config files of an application
+//!
+//! The `logix-lsp` binary doesn't know what the files contain, so it only reports syntax errors.
+//! An application builds a binary like this one around its own root type to get diagnostics,
+//! completion and hover for its config files.
+
+use logix_lsp::LanguageServer;
+use logix_type::LogixType;
+use lsp_server::Connection;
+
+/// The settings of the application
+#[derive(LogixType)]
+#[allow(dead_code)]
+struct Config {
+    /// The name shown in the title bar
+    name: String,
+    /// How many worker threads to start
+    workers: u32,
+}
+
+fn main() -> Result<(), logix_lsp::Error> {
+    let (conn, io_threads) = Connection::stdio();
+    LanguageServer::with_root::<Config>().run(&conn)?;
+    drop(conn);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,312 @@
+use std::ops::Range;
+
+use logix_type::{
+    cst::{CstToken, Element, Node, NodeKind, TokenKind},
+    type_trait::{LogixMemberDescriptor, LogixTypeDescriptor, LogixValueDescriptor},
+};
+
+/// An identifier in the document
+pub(crate) struct Word<'a> {
+    pub range: Range<usize>,
+    pub text: &'a str,
+}
+
+/// What belongs at a position in the document according to the root type
+pub(crate) enum Found<'a> {
+    /// The name of a struct member
+    Member {
+        members: &'static [LogixMemberDescriptor],
+        word: Option<Word<'a>>,
+    },
+    /// A value of the type `ty`
+    Value {
+        ty: &'static LogixTypeDescriptor,
+        word: Option<Word<'a>>,
+    },
+    /// Nothing is known, for example inside comments and strings, or in values of unknown types
+    Nothing,
+}
+
+/// Returns what belongs at `offset` in `file`, which is a [`NodeKind::File`] of type `root`
+pub(crate) fn find<'a>(
+    file: &'a Node,
+    offset: usize,
+    root: &'static LogixTypeDescriptor,
+) -> Found<'a> {
+    let finder = Finder { offset };
+    for (start, child) in children(file, 0) {
+        if let Some(found) = finder.in_value(child, start, Some(root)) {
+            return found;
+        }
+    }
+    Found::Value {
+        ty: root,
+        word: None,
+    }
+}
+
+/// Follows back-references and optional values to the type that is written in the file
+pub(crate) fn written_type(mut ty: &'static LogixTypeDescriptor) -> &'static LogixTypeDescriptor {
+    loop {
+        ty = ty.resolve();
+        match ty.value {
+            LogixValueDescriptor::Optional { inner } => ty = inner,
+            _ => return ty,
+        }
+    }
+}
+
+/// What the items of a brace are
+#[derive(Clone, Copy)]
+enum Items {
+    Members(&'static [LogixMemberDescriptor]),
+    MapValues(&'static LogixTypeDescriptor),
+    Tuple(&'static [&'static LogixTypeDescriptor]),
+    List(&'static LogixTypeDescriptor),
+    Unknown,
+}
+
+struct Finder {
+    offset: usize,
+}
+
+impl Finder {
+    /// True if the offset is in or at the end of the text starting at `start`
+    fn touches(&self, start: usize, elem: &Element) -> bool {
+        (start..=start + elem.text_len()).contains(&self.offset)
+    }
+
+    /// True if the offset is inside the element, or at the end of a node that is not complete
+    fn inside(&self, start: usize, elem: &Element) -> bool {
+        let end = start + elem.text_len();
+        start < self.offset && (self.offset < end || (self.offset == end && !is_closed(elem)))
+    }
+
+    fn word<'a>(&self, start: usize, elem: &'a Element) -> Option<Word<'a>> {
+        match elem {
+            Element::Token(CstToken {
+                kind: TokenKind::Ident,
+                text,
+            }) if self.touches(start, elem) => Some(Word {
+                range: start..start + text.len(),
+                text: std::str::from_utf8(text).ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns `Some` if the offset is in `elem`, which is a value of the type `ty`
+    fn in_value<'a>(
+        &self,
+        elem: &'a Element,
+        start: usize,
+        ty: Option<&'static LogixTypeDescriptor>,
+    ) -> Option<Found<'a>> {
+        if let Some(word) = self.word(start, elem) {
+            return Some(match ty {
+                Some(ty) => Found::Value {
+                    ty,
+                    word: Some(word),
+                },
+                None => Found::Nothing,
+            });
+        }
+        if !self.inside(start, elem) {
+            return None;
+        }
+        let node = match elem {
+            Element::Node(node) => node,
+            Element::Token(token)
+                if matches!(token.kind, TokenKind::Whitespace | TokenKind::Newline) =>
+            {
+                return None
+            }
+            Element::Token(_) => return Some(Found::Nothing),
+        };
+
+        let mut it = children(node, start).peekable();
+        let ty = match node.kind {
+            NodeKind::Struct | NodeKind::Tuple => {
+                let name = match it.peek() {
+                    Some(&(start, elem @ Element::Token(token)))
+                        if token.kind == TokenKind::Ident =>
+                    {
+                        if let Some(word) = self.word(start, elem) {
+                            return Some(match ty {
+                                Some(ty) => Found::Value {
+                                    ty,
+                                    word: Some(word),
+                                },
+                                None => Found::Nothing,
+                            });
+                        }
+                        it.next();
+                        std::str::from_utf8(&token.text).ok()
+                    }
+                    _ => None,
+                };
+                ty.and_then(|ty| type_named(ty, name))
+            }
+            NodeKind::Map | NodeKind::List => ty.map(written_type),
+            _ => return Some(Found::Nothing),
+        };
+
+        let items = match ty.map(|ty| (node.kind, &ty.value)) {
+            Some((NodeKind::Struct, LogixValueDescriptor::Struct { members })) => {
+                Items::Members(members)
+            }
+            Some((NodeKind::Map, LogixValueDescriptor::Map { value, .. })) => {
+                Items::MapValues(value)
+            }
+            Some((NodeKind::Tuple, LogixValueDescriptor::Tuple { members })) => {
+                Items::Tuple(members)
+            }
+            Some((
+                NodeKind::List,
+                LogixValueDescriptor::List { item }
+                | LogixValueDescriptor::Array { item, .. }
+                | LogixValueDescriptor::Set { item },
+            )) => Items::List(item),
+            _ => Items::Unknown,
+        };
+
+        Some(self.in_items(it, items))
+    }
+
+    /// The offset is somewhere after the name of a struct or tuple, `it` starts at the brace
+    fn in_items<'a>(
+        &self,
+        mut it: impl Iterator<Item = (usize, &'a Element)>,
+        items: Items,
+    ) -> Found<'a> {
+        // Skip whitespace up to and including the opening brace
+        for (start, elem) in it.by_ref() {
+            let end = start + elem.text_len();
+            if self.offset < end {
+                return Found::Nothing;
+            }
+            if matches!(elem, Element::Token(t) if matches!(t.kind, TokenKind::Brace { .. })) {
+                break;
+            }
+        }
+
+        let mut index = 0;
+        for (start, elem) in it {
+            let item_ty = match items {
+                Items::MapValues(ty) | Items::List(ty) => Some(ty),
+                Items::Tuple(members) => members.get(index).copied(),
+                Items::Members(_) | Items::Unknown => None,
+            };
+            match elem {
+                Element::Token(token) if token.kind == TokenKind::Comma => index += 1,
+                Element::Node(member) if member.kind == NodeKind::Member => {
+                    if self.inside(start, elem) {
+                        return self.in_member(member, start, items);
+                    }
+                }
+                _ => {
+                    if let Some(found) = self.in_value(elem, start, item_ty) {
+                        return found;
+                    }
+                }
+            }
+            if self.offset <= start + elem.text_len() {
+                break;
+            }
+        }
+
+        // The offset is between items
+        match items {
+            Items::Members(members) => Found::Member {
+                members,
+                word: None,
+            },
+            Items::MapValues(_) | Items::Unknown => Found::Nothing,
+            Items::Tuple(members) => match members.get(index) {
+                Some(ty) => Found::Value { ty, word: None },
+                None => Found::Nothing,
+            },
+            Items::List(ty) => Found::Value { ty, word: None },
+        }
+    }
+
+    fn in_member<'a>(&self, member: &'a Node, start: usize, items: Items) -> Found<'a> {
+        let mut it = children(member, start);
+        let Some((start, key)) = it.next() else {
+            return Found::Nothing;
+        };
+        let name = self.word(start, key);
+        let ty = match items {
+            Items::Members(members) => {
+                if name.is_some() {
+                    return Found::Member {
+                        members,
+                        word: name,
+                    };
+                }
+                let Element::Token(key) = key else {
+                    return Found::Nothing;
+                };
+                members
+                    .iter()
+                    .find(|m| m.name.as_bytes() == key.text.as_slice())
+                    .map(|m| m.ty)
+            }
+            Items::MapValues(ty) => Some(ty),
+            _ => None,
+        };
+
+        let mut after_colon = false;
+        for (start, elem) in it {
+            if !after_colon {
+                after_colon = matches!(elem, Element::Token(t) if t.kind == TokenKind::Colon);
+                if self.offset < start + elem.text_len() {
+                    return Found::Nothing;
+                }
+            } else if let Some(found) = self.in_value(elem, start, ty) {
+                return found;
+            }
+        }
+        match ty {
+            Some(ty) if after_colon => Found::Value { ty, word: None },
+            _ => Found::Nothing,
+        }
+    }
+}
+
+/// Returns the type called `name` that can be written where `ty` is expected
+fn type_named(
+    ty: &'static LogixTypeDescriptor,
+    name: Option<&str>,
+) -> Option<&'static LogixTypeDescriptor> {
+    let ty = written_type(ty);
+    match (&ty.value, name) {
+        (LogixValueDescriptor::Enum { variants }, Some(name)) => {
+            variants.iter().find(|v| v.name == name)
+        }
+        (_, Some(name)) if ty.name != name => None,
+        _ => Some(ty),
+    }
+}
+
+/// Returns true if the element ends with a closing brace
+fn is_closed(elem: &Element) -> bool {
+    match elem {
+        Element::Token(token) => matches!(token.kind, TokenKind::Brace { start: false, .. }),
+        Element::Node(node) => node
+            .children
+            .iter()
+            .rev()
+            .find(|child| !matches!(child, Element::Token(t) if t.kind.is_trivia()))
+            .is_some_and(is_closed),
+    }
+}
+
+/// The children of `node` together with their start offset
+fn children(node: &Node, start: usize) -> impl Iterator<Item = (usize, &Element)> {
+    node.children.iter().scan(start, |pos, child| {
+        let start = *pos;
+        *pos += child.text_len();
+        Some((start, child))
+    })
+}
@@ -0,0 +1,77 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use logix_type::{
+    error::ParseError,
+    token::{LexemeKind, Lexer},
+    LogixLoader,
+};
+use lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+
+use crate::{position::LineIndex, vfs::OverlayFs, Root};
+
+/// Check the open document at `path`, using the root type if there is one
+pub(crate) fn check(
+    root: Option<&Root>,
+    path: &Path,
+    docs: &HashMap<std::path::PathBuf, Arc<str>>,
+) -> Vec<Diagnostic> {
+    let Some(text) = docs.get(path) else {
+        return Vec::new();
+    };
+    let index = LineIndex::new(text);
+
+    let (Some(root), Some(dir), Some(name)) = (root, path.parent(), path.file_name()) else {
+        return syntax_errors(&index, text);
+    };
+
+    let mut loader = LogixLoader::new(OverlayFs::new(dir, docs));
+    (root.check)(&mut loader, Path::new(name))
+        .iter()
+        .map(|error| from_parse_error(&index, &loader, Path::new(name), error))
+        .collect()
+}
+
+/// Without a type to parse as, only the errors found by the lexer can be reported
+fn syntax_errors(index: &LineIndex, text: &str) -> Vec<Diagnostic> {
+    Lexer::new(text.as_bytes())
+        .filter_map(|lexeme| match lexeme.kind {
+            LexemeKind::Error { error, at } => Some(diagnostic(
+                index.range(at.range()),
+                DiagnosticSeverity::ERROR,
+                error.to_string(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn from_parse_error(
+    index: &LineIndex,
+    loader: &LogixLoader<OverlayFs>,
+    name: &Path,
+    error: &ParseError,
+) -> Diagnostic {
+    let severity = match error {
+        ParseError::Warning(_) => DiagnosticSeverity::WARNING,
+        _ => DiagnosticSeverity::ERROR,
+    };
+
+    match error.span() {
+        // The location is given by the range, so it is left out of the message
+        Some(span) if loader.files().path(span.file_id()) == Some(name) => {
+            diagnostic(index.range(span.range()), severity, error.to_string())
+        }
+        // Errors in included files, or without a location, are shown at the start of the document
+        _ => diagnostic(Range::default(), severity, loader.report(error).to_string()),
+    }
+}
+
+fn diagnostic(range: Range, severity: DiagnosticSeverity, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        source: Some("logix".into()),
+        message,
+        ..Default::default()
+    }
+}
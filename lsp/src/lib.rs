@@ -0,0 +1,341 @@
+//! A language server for `.logix` files
+//!
+//! The server speaks JSON-RPC through an [`lsp_server::Connection`], `Connection::stdio()` is
+//! what editors use. Register the type of the files with [`LanguageServer::with_root`] to get
+//! diagnostics from the parser, completion of member names and enum variants, and documentation
+//! on hover. Without it only errors found by the lexer are reported.
+//!
+//! The `logix-lsp` binary has no root type, applications ship their own binary instead, see
+//! `examples/config_server.rs`.
+
+mod context;
+mod diagnostics;
+mod position;
+mod vfs;
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use logix_type::{
+    complete::{complete, CompletionKind, Completions},
+    cst::Node,
+    error::ParseError,
+    token::{Action, LexemeKind, Lexer, Literal, Token},
+    type_trait::{LogixTypeDescriptor, LogixValueDescriptor},
+    LogixLoader, LogixType,
+};
+use logix_vfs::{LogixVfs, RelFs};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, Request as _},
+    CompletionItem, CompletionItemKind, CompletionParams, CompletionResponse,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    Documentation, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams,
+    Range, ServerCapabilities, TextDocumentPositionParams, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Url,
+};
+
+use crate::{
+    context::{written_type, Found},
+    position::LineIndex,
+    vfs::OverlayFs,
+};
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// The type of the files and how to check them
+struct Root {
+    desc: &'static LogixTypeDescriptor,
+    check: fn(&mut LogixLoader<OverlayFs>, &Path) -> Vec<ParseError>,
+    complete: fn(&[u8], usize) -> Completions,
+}
+
+fn check_as<T: LogixType>(loader: &mut LogixLoader<OverlayFs>, path: &Path) -> Vec<ParseError> {
+    match loader.load_file_all_errors::<T>(path) {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors.into_vec(),
+    }
+}
+
+/// The state of the server, mainly the text of the open documents
+pub struct LanguageServer {
+    root: Option<Root>,
+    docs: HashMap<PathBuf, Arc<str>>,
+}
+
+impl Default for LanguageServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageServer {
+    /// A server that only knows the syntax, not what the files contain
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            docs: HashMap::new(),
+        }
+    }
+
+    /// A server for files containing a `T`
+    pub fn with_root<T: LogixType>() -> Self {
+        Self {
+            root: Some(Root {
+                desc: T::descriptor(),
+                check: check_as::<T>,
+                complete: complete::<T>,
+            }),
+            docs: HashMap::new(),
+        }
+    }
+
+    pub fn capabilities() -> ServerCapabilities {
+        ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            completion_provider: Some(Default::default()),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            definition_provider: Some(OneOf::Left(true)),
+            ..Default::default()
+        }
+    }
+
+    /// Handle the initialization and then messages until the client shuts the server down
+    pub fn run(mut self, conn: &Connection) -> Result<(), Error> {
+        conn.initialize(serde_json::to_value(Self::capabilities())?)?;
+
+        for msg in &conn.receiver {
+            match msg {
+                Message::Request(req) => {
+                    if conn.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    conn.sender.send(self.handle_request(req).into())?;
+                }
+                Message::Notification(not) => {
+                    if let Some(not) = self.handle_notification(not) {
+                        conn.sender.send(not.into())?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, req: Request) -> Response {
+        match req.method.as_str() {
+            Completion::METHOD => self.dispatch::<Completion>(req, Self::completion),
+            HoverRequest::METHOD => self.dispatch::<HoverRequest>(req, Self::hover),
+            GotoDefinition::METHOD => self.dispatch::<GotoDefinition>(req, Self::definition),
+            _ => Response::new_err(
+                req.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unknown request {:?}", req.method),
+            ),
+        }
+    }
+
+    fn dispatch<R: lsp_types::request::Request>(
+        &self,
+        req: Request,
+        f: impl FnOnce(&Self, R::Params) -> R::Result,
+    ) -> Response {
+        match serde_json::from_value(req.params) {
+            Ok(params) => Response::new_ok(req.id, f(self, params)),
+            Err(e) => Response::new_err(req.id, ErrorCode::InvalidParams as i32, e.to_string()),
+        }
+    }
+
+    /// Returns the diagnostics of the document if it changed
+    fn handle_notification(&mut self, not: Notification) -> Option<Notification> {
+        let (uri, version) = match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(not.params).ok()?;
+                let doc = params.text_document;
+                self.docs.insert(doc_path(&doc.uri), doc.text.into());
+                (doc.uri, Some(doc.version))
+            }
+            DidChangeTextDocument::METHOD => {
+                let mut params: DidChangeTextDocumentParams =
+                    serde_json::from_value(not.params).ok()?;
+                let doc = params.text_document;
+                // The sync kind is full, so the last change is the whole new text
+                let text = params.content_changes.pop()?.text;
+                self.docs.insert(doc_path(&doc.uri), text.into());
+                (doc.uri, Some(doc.version))
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(not.params).ok()?;
+                self.docs.remove(&doc_path(&params.text_document.uri));
+                // Clear the diagnostics of the closed document
+                return Some(Notification::new(
+                    PublishDiagnostics::METHOD.into(),
+                    PublishDiagnosticsParams::new(params.text_document.uri, Vec::new(), None),
+                ));
+            }
+            _ => return None,
+        };
+
+        let diagnostics = diagnostics::check(self.root.as_ref(), &doc_path(&uri), &self.docs);
+        Some(Notification::new(
+            PublishDiagnostics::METHOD.into(),
+            PublishDiagnosticsParams::new(uri, diagnostics, version),
+        ))
+    }
+
+    /// Returns the text of the document, its line index and the byte offset of the position
+    fn locate<'a>(
+        &'a self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<(&'a str, LineIndex<'a>, usize)> {
+        let text = self.docs.get(&doc_path(&params.text_document.uri))?;
+        let index = LineIndex::new(text);
+        let offset = index.offset(params.position);
+        Some((text, index, offset))
+    }
+
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let (text, index, offset) = self.locate(&params.text_document_position)?;
+        let root = self.root.as_ref()?;
+        let res = (root.complete)(text.as_bytes(), offset);
+        if res.items.is_empty() {
+            return None;
+        }
+
+        let range = index.range(res.range);
+        Some(CompletionResponse::Array(
+            res.items
+                .into_iter()
+                .map(|item| CompletionItem {
+                    text_edit: Some(lsp_types::CompletionTextEdit::Edit(TextEdit::new(
+                        range,
+                        item.text.into(),
+                    ))),
+                    ..completion_item(item)
+                })
+                .collect(),
+        ))
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let (text, index, offset) = self.locate(&params.text_document_position_params)?;
+        let root = self.root.as_ref()?;
+        let file = Node::parse(text.as_bytes());
+
+        let (word, title, doc) = match context::find(&file, offset, root.desc) {
+            Found::Member {
+                members,
+                word: Some(word),
+                ..
+            } => {
+                let member = members.iter().find(|m| m.name == word.text)?;
+                let title = format!("{}: {}", member.name, member.ty.name);
+                (word, title, member.doc)
+            }
+            Found::Value {
+                ty,
+                word: Some(word),
+            } => {
+                let ty = written_type(ty);
+                match &ty.value {
+                    LogixValueDescriptor::Enum { variants } => {
+                        let variant = variants.iter().find(|v| v.name == word.text)?;
+                        (word, format!("{}::{}", ty.name, variant.name), variant.doc)
+                    }
+                    _ if ty.name == word.text => (word, ty.name.to_owned(), ty.doc),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        let mut value = format!("```logix\n{title}\n```");
+        if !doc.is_empty() {
+            value.push_str("\n\n");
+            value.push_str(doc);
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(index.range(word.range)),
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let params = params.text_document_position_params;
+        let (text, _, offset) = self.locate(&params)?;
+        let path = include_at(text, offset)?;
+
+        let dir = doc_path(&params.text_document.uri).parent()?.to_path_buf();
+        let target = dir.join(RelFs::new(&dir).canonicalize_path(Path::new(&path)).ok()?);
+        Some(GotoDefinitionResponse::Scalar(Location::new(
+            Url::from_file_path(target).ok()?,
+            Range::default(),
+        )))
+    }
+}
+
+/// The path of a document, documents that are not files get a path that doesn't exist
+fn doc_path(uri: &Url) -> PathBuf {
+    uri.to_file_path()
+        .unwrap_or_else(|()| PathBuf::from("/").join(uri.path()))
+}
+
+fn completion_item(item: logix_type::complete::Completion) -> CompletionItem {
+    let kind = match item.kind {
+        CompletionKind::Member => CompletionItemKind::FIELD,
+        CompletionKind::Variant => CompletionItemKind::ENUM_MEMBER,
+        CompletionKind::Type => CompletionItemKind::STRUCT,
+        CompletionKind::Bool => CompletionItemKind::KEYWORD,
+        CompletionKind::StrTag => CompletionItemKind::TEXT,
+        CompletionKind::Action => CompletionItemKind::FUNCTION,
+    };
+    CompletionItem {
+        label: item.text.into(),
+        kind: Some(kind),
+        detail: (!item.detail.is_empty()).then(|| item.detail.into()),
+        documentation: (!item.doc.is_empty()).then(|| {
+            Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: item.doc.into(),
+            })
+        }),
+        ..Default::default()
+    }
+}
+
+/// Returns the path of the `@include` at `offset`, if any
+fn include_at(text: &str, offset: usize) -> Option<String> {
+    let mut start = None;
+    for lexeme in Lexer::new(text.as_bytes()) {
+        if lexeme.span.start > offset && start.is_none() {
+            return None;
+        }
+        match lexeme.kind {
+            LexemeKind::Token(Token::Action(Action::Include)) => start = Some(lexeme.span.start),
+            LexemeKind::Whitespace | LexemeKind::Token(Token::Brace { start: true, .. }) => {}
+            LexemeKind::Token(Token::Literal(Literal::Str(lit))) if start.is_some() => {
+                if offset <= lexeme.span.end {
+                    return lit
+                        .decode_str(&logix_type::error::SourceSpan::empty())
+                        .ok()
+                        .map(|path| path.into_owned());
+                }
+                start = None;
+            }
+            _ => start = None,
+        }
+    }
+    None
+}
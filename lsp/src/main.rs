@@ -0,0 +1,17 @@
+//! A language server for `.logix` files that only knows the syntax
+//!
+//! It has no root type, so it reports lexer errors but can't check, complete or document the
+//! contents of a file. To get those, build your own binary that runs
+//! [`LanguageServer::with_root`] with the root type of your files, see
+//! `examples/config_server.rs`.
+
+use logix_lsp::LanguageServer;
+use lsp_server::Connection;
+
+fn main() -> Result<(), logix_lsp::Error> {
+    let (conn, io_threads) = Connection::stdio();
+    LanguageServer::new().run(&conn)?;
+    drop(conn);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use lsp_types::{Position, Range};
+
+/// Converts between byte offsets and LSP positions, which count columns in UTF-16 code units
+pub(crate) struct LineIndex<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { text, starts }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.starts[line];
+        let col = self.text[start..]
+            .char_indices()
+            .take_while(|&(i, _)| start + i < offset)
+            .map(|(_, c)| c.len_utf16())
+            .sum::<usize>();
+        Position::new(line as u32, col as u32)
+    }
+
+    pub fn range(&self, range: std::ops::Range<usize>) -> Range {
+        Range::new(self.position(range.start), self.position(range.end))
+    }
+
+    /// Returns the byte offset of `pos`, positions past the end of a line are moved to the end
+    pub fn offset(&self, pos: Position) -> usize {
+        let Some(&start) = self.starts.get(pos.line as usize) else {
+            return self.text.len();
+        };
+        let line = self.text[start..].split('\n').next().unwrap();
+        let mut col = 0;
+        for (i, c) in line.char_indices() {
+            if col >= pos.character as usize {
+                return start + i;
+            }
+            col += c.len_utf16();
+        }
+        start + line.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf16() {
+        let text = "ab\næ😀x\n";
+        let index = LineIndex::new(text);
+        for (offset, line, col) in [
+            (0, 0, 0),
+            (2, 0, 2),
+            (3, 1, 0),
+            (5, 1, 1),
+            (9, 1, 3),
+            (11, 2, 0),
+        ] {
+            let pos = Position::new(line, col);
+            assert_eq!(index.position(offset), pos, "{offset}");
+            assert_eq!(index.offset(pos), offset, "{pos:?}");
+        }
+        assert_eq!(index.offset(Position::new(0, 10)), 2);
+        assert_eq!(index.offset(Position::new(5, 0)), text.len());
+    }
+}
@@ -0,0 +1,54 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use logix_vfs::{LogixVfs, RelFs};
+
+/// A file system rooted at the directory of a document, where open documents are read from the
+/// editor instead of the disk so that unsaved changes are checked
+#[derive(Debug)]
+pub(crate) struct OverlayFs<'a> {
+    dir: PathBuf,
+    fs: RelFs,
+    docs: &'a HashMap<PathBuf, Arc<str>>,
+}
+
+impl<'a> OverlayFs<'a> {
+    pub fn new(dir: &Path, docs: &'a HashMap<PathBuf, Arc<str>>) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            fs: RelFs::new(dir),
+            docs,
+        }
+    }
+}
+
+impl<'a> LogixVfs for OverlayFs<'a> {
+    type RoFile = Cursor<Arc<[u8]>>;
+    type DirEntry = <RelFs as LogixVfs>::DirEntry;
+    type ReadDir = <RelFs as LogixVfs>::ReadDir;
+
+    fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, logix_vfs::Error> {
+        self.fs.canonicalize_path(path)
+    }
+
+    fn open_file(&self, path: &Path) -> Result<Self::RoFile, logix_vfs::Error> {
+        let full_path = self.dir.join(self.fs.canonicalize_path(path)?);
+        if let Some(data) = self.docs.get(&full_path) {
+            return Ok(Cursor::new(data.clone().into()));
+        }
+        let mut data = Vec::new();
+        self.fs
+            .open_file(path)?
+            .read_to_end(&mut data)
+            .map_err(|e| logix_vfs::Error::from_io(path.to_path_buf(), e))?;
+        Ok(Cursor::new(data.into()))
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, logix_vfs::Error> {
+        self.fs.read_dir(path)
+    }
+}
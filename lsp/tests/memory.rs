@@ -0,0 +1,149 @@
+//! Kept apart from the other tests, the allocator below counts the memory used by the whole process
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use logix_lsp::LanguageServer;
+use logix_type::LogixType;
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{Completion, Initialize, Shutdown},
+    CompletionParams, DidChangeTextDocumentParams, DidOpenTextDocumentParams, InitializeParams,
+    Position, PublishDiagnosticsParams, TextDocumentContentChangeEvent, TextDocumentIdentifier,
+    TextDocumentItem, TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier,
+};
+
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: Counting = Counting;
+
+#[derive(LogixType)]
+struct Settings {
+    name: String,
+    size: u32,
+}
+
+fn notify<N: lsp_types::notification::Notification>(conn: &Connection, params: N::Params) {
+    let not = Notification::new(N::METHOD.into(), params);
+    conn.sender.send(not.into()).unwrap();
+}
+
+fn request<R: lsp_types::request::Request>(conn: &Connection, id: i32, params: R::Params) {
+    let req = Request::new(RequestId::from(id), R::METHOD.into(), params);
+    conn.sender.send(req.into()).unwrap();
+    match conn.receiver.recv().unwrap() {
+        Message::Response(resp) => assert!(resp.error.is_none(), "{:?}", resp.error),
+        msg => panic!("Expected a response, got {msg:?}"),
+    }
+}
+
+fn diagnostics(conn: &Connection) -> PublishDiagnosticsParams {
+    match conn.receiver.recv().unwrap() {
+        Message::Notification(not) if not.method == PublishDiagnostics::METHOD => {
+            serde_json::from_value(not.params).unwrap()
+        }
+        msg => panic!("Expected diagnostics, got {msg:?}"),
+    }
+}
+
+#[test]
+fn edits_keep_memory_flat() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = Url::from_file_path(dir.path().join("test.logix")).unwrap();
+
+    let (conn, server_conn) = Connection::memory();
+    let server = std::thread::spawn(move || {
+        LanguageServer::with_root::<Settings>()
+            .run(&server_conn)
+            .unwrap()
+    });
+    request::<Initialize>(&conn, 1, InitializeParams::default());
+    notify::<Initialized>(&conn, lsp_types::InitializedParams {});
+
+    // Every version is different and has an error, so each one is parsed and reported in full
+    let text = |version: i32| {
+        format!(
+            "Settings {{\n  name: \"{}{version}\"\n  size: x\n}}\n",
+            "a".repeat(20_000)
+        )
+    };
+    notify::<DidOpenTextDocument>(
+        &conn,
+        DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(uri.clone(), "logix".into(), 0, text(0)),
+        },
+    );
+    assert_eq!(diagnostics(&conn).diagnostics.len(), 1);
+
+    let edit = |version| {
+        notify::<DidChangeTextDocument>(
+            &conn,
+            DidChangeTextDocumentParams {
+                text_document: VersionedTextDocumentIdentifier::new(uri.clone(), version),
+                content_changes: vec![TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text: text(version),
+                }],
+            },
+        );
+        let diagnostics = diagnostics(&conn);
+        assert_eq!(diagnostics.version, Some(version));
+        assert_eq!(diagnostics.diagnostics.len(), 1);
+
+        // Completion parses the text before the cursor on its own
+        request::<Completion>(
+            &conn,
+            version + 10,
+            CompletionParams {
+                text_document_position: TextDocumentPositionParams::new(
+                    TextDocumentIdentifier::new(uri.clone()),
+                    Position::new(2, 8),
+                ),
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+                context: None,
+            },
+        );
+    };
+
+    for version in 1..=100 {
+        edit(version);
+    }
+    let before = LIVE.load(Ordering::Relaxed);
+    for version in 101..=2000 {
+        edit(version);
+    }
+    let after = LIVE.load(Ordering::Relaxed);
+
+    // Keeping a copy of every version would be about 38 MB
+    assert!(
+        after.saturating_sub(before) < 1_000_000,
+        "{before} bytes before, {after} bytes after"
+    );
+
+    request::<Shutdown>(&conn, 2, ());
+    notify::<Exit>(&conn, ());
+    server.join().unwrap();
+}
@@ -0,0 +1,320 @@
+use std::{path::Path, thread::JoinHandle};
+
+use logix_lsp::LanguageServer;
+use logix_type::LogixType;
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Exit, Initialized,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, Initialize, Shutdown},
+    CompletionParams, CompletionResponse, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, GotoDefinitionParams,
+    GotoDefinitionResponse, HoverContents, HoverParams, InitializeParams, Position,
+    PublishDiagnosticsParams, Range, TextDocumentContentChangeEvent, TextDocumentIdentifier,
+    TextDocumentItem, TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier,
+};
+
+/// The settings of the editor
+#[derive(LogixType)]
+struct Settings {
+    /// The name of the user
+    name: String,
+    /// The color theme
+    theme: Theme,
+    font: Option<Font>,
+    tags: Vec<Theme>,
+}
+
+#[derive(LogixType)]
+enum Theme {
+    /// A dark background
+    Dark,
+    Light,
+    Custom(String),
+}
+
+#[derive(LogixType)]
+struct Font {
+    family: String,
+    size: u32,
+}
+
+/// Talks to a server running in a thread
+struct Client {
+    conn: Connection,
+    server: Option<JoinHandle<()>>,
+    next_id: i32,
+}
+
+impl Client {
+    fn start(server: LanguageServer) -> Self {
+        let (conn, server_conn) = Connection::memory();
+        let server = std::thread::spawn(move || server.run(&server_conn).unwrap());
+        let mut ret = Self {
+            conn,
+            server: Some(server),
+            next_id: 0,
+        };
+        let init = ret.request::<Initialize>(InitializeParams::default());
+        assert!(init.capabilities.hover_provider.is_some());
+        ret.notify::<Initialized>(lsp_types::InitializedParams {});
+        ret
+    }
+
+    fn request<R: lsp_types::request::Request>(&mut self, params: R::Params) -> R::Result {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        let req = Request::new(id.clone(), R::METHOD.into(), params);
+        self.conn.sender.send(req.into()).unwrap();
+        match self.conn.receiver.recv().unwrap() {
+            Message::Response(resp) => {
+                assert_eq!(resp.id, id);
+                assert!(resp.error.is_none(), "{:?}", resp.error);
+                serde_json::from_value(resp.result.unwrap()).unwrap()
+            }
+            msg => panic!("Expected a response, got {msg:?}"),
+        }
+    }
+
+    fn notify<N: lsp_types::notification::Notification>(&self, params: N::Params) {
+        let not = Notification::new(N::METHOD.into(), params);
+        self.conn.sender.send(not.into()).unwrap();
+    }
+
+    fn diagnostics(&self) -> PublishDiagnosticsParams {
+        match self.conn.receiver.recv().unwrap() {
+            Message::Notification(not) if not.method == PublishDiagnostics::METHOD => {
+                serde_json::from_value(not.params).unwrap()
+            }
+            msg => panic!("Expected diagnostics, got {msg:?}"),
+        }
+    }
+
+    /// Open a document and return the diagnostics as `(range, message)`
+    fn open(&self, uri: &Url, text: &str) -> Vec<(Range, String)> {
+        self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(uri.clone(), "logix".into(), 1, text.into()),
+        });
+        let diagnostics = self.diagnostics();
+        assert_eq!(&diagnostics.uri, uri);
+        diagnostics
+            .diagnostics
+            .into_iter()
+            .map(|d| {
+                assert_eq!(d.severity, Some(DiagnosticSeverity::ERROR));
+                (d.range, d.message)
+            })
+            .collect()
+    }
+
+    fn complete(&mut self, uri: &Url, line: u32, col: u32) -> Vec<String> {
+        let res = self.request::<Completion>(CompletionParams {
+            text_document_position: position(uri, line, col),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        });
+        match res {
+            Some(CompletionResponse::Array(items)) => {
+                items.into_iter().map(|item| item.label).collect()
+            }
+            None => Vec::new(),
+            res => panic!("Unexpected response {res:?}"),
+        }
+    }
+
+    fn hover(&mut self, uri: &Url, line: u32, col: u32) -> Option<String> {
+        let res = self.request::<HoverRequest>(HoverParams {
+            text_document_position_params: position(uri, line, col),
+            work_done_progress_params: Default::default(),
+        })?;
+        match res.contents {
+            HoverContents::Markup(content) => Some(content.value),
+            contents => panic!("Unexpected contents {contents:?}"),
+        }
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        self.request::<Shutdown>(());
+        self.notify::<Exit>(());
+        self.server.take().unwrap().join().unwrap();
+    }
+}
+
+fn position(uri: &Url, line: u32, col: u32) -> TextDocumentPositionParams {
+    TextDocumentPositionParams::new(
+        TextDocumentIdentifier::new(uri.clone()),
+        Position::new(line, col),
+    )
+}
+
+fn range(line: u32, start: u32, end_line: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(end_line, end))
+}
+
+fn uri(dir: &Path, name: &str) -> Url {
+    Url::from_file_path(dir.join(name)).unwrap()
+}
+
+#[test]
+fn syntax_diagnostics() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "test.logix");
+    let client = Client::start(LanguageServer::new());
+
+    // The column counts UTF-16 code units, the emoji is two of them
+    assert_eq!(
+        client.open(&uri, "Settings {\n  name: \"😀\n}\n"),
+        [(
+            range(1, 11, 2, 0),
+            "unexpected end of the string, expected `\"`".into()
+        )]
+    );
+
+    client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 2),
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "Settings {\n  name: \"😀\"\n}\n".into(),
+        }],
+    });
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics.version, Some(2));
+    assert_eq!(diagnostics.diagnostics, []);
+
+    client.notify::<DidCloseTextDocument>(DidCloseTextDocumentParams {
+        text_document: TextDocumentIdentifier::new(uri),
+    });
+    assert_eq!(client.diagnostics().diagnostics, []);
+}
+
+#[test]
+fn typed_diagnostics() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "test.logix");
+    let client = Client::start(LanguageServer::with_root::<Settings>());
+
+    assert_eq!(
+        client.open(
+            &uri,
+            "Settings {\n  name: \"😀\" 12\n  theme: Drak\n  tags: []\n}\n"
+        ),
+        [
+            (
+                range(1, 13, 1, 15),
+                "Unexpected number while parsing `Settings`, expected newline".into()
+            ),
+            (
+                range(2, 9, 2, 13),
+                "Unexpected identifier while parsing `Theme`, expected one of `Dark`, `Light`, or `Custom`".into()
+            ),
+        ]
+    );
+
+    // Errors in other files are shown at the start of the document
+    let diagnostics = client.open(
+        &self::uri(dir.path(), "include.logix"),
+        "Settings {\n  name: @include(\"missing.txt\")\n  theme: Dark\n  tags: []\n}\n",
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].0, range(1, 8, 1, 31));
+}
+
+const SRC: &str = concat!(
+    "Settings {\n",
+    "  name: \"test\"\n",
+    "  theme: Da\n",
+    "  \n",
+    "  tags: [Light, Custom(\"x\")]\n",
+    "  font: Font {\n",
+    "    size: 12\n",
+    "  }\n",
+    "}\n",
+);
+
+#[test]
+fn completion() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "test.logix");
+    let mut client = Client::start(LanguageServer::with_root::<Settings>());
+    client.open(&uri, SRC);
+
+    // The root type
+    assert_eq!(client.complete(&uri, 0, 0), ["Settings"]);
+    assert_eq!(client.complete(&uri, 0, 3), ["Settings"]);
+    // Members that are not used yet
+    assert_eq!(client.complete(&uri, 3, 2), Vec::<String>::new());
+    assert_eq!(client.complete(&uri, 6, 4), ["family"]);
+    assert_eq!(client.complete(&uri, 6, 6), ["family", "size"]);
+    // Enum variants, in members and lists
+    assert_eq!(client.complete(&uri, 2, 11), ["Dark", "Light", "Custom"]);
+    assert_eq!(client.complete(&uri, 4, 10), ["Dark", "Light", "Custom"]);
+    assert_eq!(client.complete(&uri, 4, 16), ["Dark", "Light", "Custom"]);
+    // Nothing inside strings, or where a number goes
+    assert_eq!(client.complete(&uri, 1, 10), Vec::<String>::new());
+    assert_eq!(client.complete(&uri, 4, 24), Vec::<String>::new());
+    assert_eq!(client.complete(&uri, 6, 11), Vec::<String>::new());
+}
+
+#[test]
+fn hover() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "test.logix");
+    let mut client = Client::start(LanguageServer::with_root::<Settings>());
+    client.open(&uri, &SRC.replace("theme: Da", "theme: Dark"));
+
+    assert_eq!(
+        client.hover(&uri, 0, 2).as_deref(),
+        Some("```logix\nSettings\n```\n\nThe settings of the editor")
+    );
+    assert_eq!(
+        client.hover(&uri, 2, 4).as_deref(),
+        Some("```logix\ntheme: Theme\n```\n\nThe color theme")
+    );
+    assert_eq!(
+        client.hover(&uri, 2, 10).as_deref(),
+        Some("```logix\nTheme::Dark\n```\n\nA dark background")
+    );
+    assert_eq!(
+        client.hover(&uri, 4, 12).as_deref(),
+        Some("```logix\nTheme::Light\n```")
+    );
+    assert_eq!(client.hover(&uri, 1, 10), None);
+}
+
+#[test]
+fn definition() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "test.logix");
+    let mut client = Client::start(LanguageServer::with_root::<Settings>());
+    client.open(
+        &uri,
+        "Settings {\n  name: @include(\"sub/name.txt\")\n  theme: Dark\n  tags: []\n}\n",
+    );
+
+    let definition = |client: &mut Client, col| {
+        client.request::<GotoDefinition>(GotoDefinitionParams {
+            text_document_position_params: position(&uri, 1, col),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    };
+    for col in [8, 20, 31] {
+        match definition(&mut client, col) {
+            Some(GotoDefinitionResponse::Scalar(location)) => {
+                assert_eq!(location.uri, self::uri(dir.path(), "sub/name.txt"));
+                assert_eq!(location.range, Range::default());
+            }
+            res => panic!("Unexpected response {res:?}"),
+        }
+    }
+    assert_eq!(definition(&mut client, 4), None);
+}
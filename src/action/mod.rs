@@ -21,7 +21,7 @@ pub(crate) fn for_include<FS: LogixVfs>(
         .map(|v| v.join_with_span(span))?;
     let file = p.open_file(&path.value.value).map_err(|error| {
         dbg!(ParseError::IncludeError {
-            span: path.span.clone(),
+            span: path.span,
             while_parsing: "string",
             error: IncludeError::Open(error),
         })
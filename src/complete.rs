@@ -0,0 +1,300 @@
+//! Suggest what can be written at a position in a `.logix` file
+//!
+//! The parser is asked what it wants at the cursor, and the type descriptors add documentation
+//! and remove members that are already in the struct.
+
+use std::{cell::RefCell, ops::Range};
+
+use logix_vfs::MemFs;
+
+use crate::{
+    cst::{Element, Node, NodeKind, TokenKind},
+    error::{ParseError, Wanted},
+    token::Token,
+    type_trait::{LogixTypeDescriptor, LogixValueDescriptor},
+    LogixLoader, LogixType,
+};
+
+/// Written in place of the word at the cursor, so the parser reports what it wanted there
+const PLACEHOLDER: &str = "logix-complete-placeholder";
+
+thread_local! {
+    /// Reused by every call to `complete`, so the loader is only set up once per thread
+    static LOADER: RefCell<LogixLoader<MemFs>> = RefCell::new(LogixLoader::new(MemFs::default()));
+}
+
+/// The start of the string literals
+const STR_TAGS: [&str; 4] = ["\"", "#esc\"", "#raw\"", "#txt\""];
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum CompletionKind {
+    /// The name of a struct member, or of a struct enum variant
+    Member,
+    /// An enum variant
+    Variant,
+    /// The name of a struct or tuple
+    Type,
+    /// `true` or `false`
+    Bool,
+    /// The start of a string literal, such as `"` or `#raw"`
+    StrTag,
+    /// An action, such as `@include`
+    Action,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Completion {
+    pub kind: CompletionKind,
+    /// The text to write
+    pub text: &'static str,
+    /// The type of a member or the enum of a variant, empty if not known
+    pub detail: &'static str,
+    /// The documentation from the descriptor, empty if there is none
+    pub doc: &'static str,
+}
+
+/// The completions at a position, and the range of the partial word they replace
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Completions {
+    pub range: Range<usize>,
+    pub items: Vec<Completion>,
+}
+
+/// Returns what can be written at byte offset `offset` in `src`, which contains a `T`
+///
+/// The word before the cursor is left out when asking the parser, so it is replaced by the
+/// completion. No completions are returned inside comments and strings, or where a number goes.
+pub fn complete<T: LogixType>(src: &[u8], offset: usize) -> Completions {
+    let offset = offset.min(src.len());
+    let range = word_before(src, offset)..offset;
+
+    let mut text = src[..range.start].to_vec();
+    text.extend_from_slice(PLACEHOLDER.as_bytes());
+    let errors = LOADER.with_borrow_mut(|loader| {
+        // NOTE(2024.04): The file system only ever has this one file, so setting it can't fail
+        loader
+            .fs_mut()
+            .set_file("complete.logix", text, true)
+            .unwrap();
+        loader.forget_files();
+        match loader.load_file_all_errors::<T>("complete.logix") {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors.into_vec(),
+        }
+    });
+
+    let wanted = errors.iter().find_map(|error| match *error {
+        ParseError::UnexpectedToken {
+            span,
+            while_parsing,
+            wanted,
+            ..
+        } if span.range().start == range.start => Some((while_parsing, wanted)),
+        _ => None,
+    });
+
+    let items = match wanted {
+        Some((while_parsing, wanted)) => {
+            let mut types = Vec::new();
+            collect_types(T::descriptor(), &mut types);
+            let file = Node::parse(src);
+            let used = used_members(&file, 0, &range).unwrap_or_default();
+            from_wanted(&types, while_parsing, wanted, &used)
+        }
+        None => Vec::new(),
+    };
+
+    Completions { range, items }
+}
+
+fn from_wanted(
+    types: &[&'static LogixTypeDescriptor],
+    while_parsing: &'static str,
+    wanted: Wanted,
+    used: &[&[u8]],
+) -> Vec<Completion> {
+    let named = |name: &'static str| types.iter().copied().filter(move |ty| ty.name == name);
+
+    match wanted {
+        Wanted::Token(Token::Ident(name)) => vec![Completion {
+            kind: CompletionKind::Type,
+            text: name,
+            detail: "",
+            doc: named(name).next().map_or("", |ty| ty.doc),
+        }],
+        // Structs want the closing brace or a member, enums want a variant
+        Wanted::Tokens(tokens) => tokens
+            .iter()
+            .filter_map(|token| match *token {
+                Token::Ident(name) => Some(name),
+                _ => None,
+            })
+            .map(|name| {
+                named(while_parsing)
+                    .find_map(|ty| match &ty.value {
+                        LogixValueDescriptor::Struct { members } => {
+                            let member = members.iter().find(|m| m.name == name)?;
+                            Some(Completion {
+                                kind: CompletionKind::Member,
+                                text: name,
+                                detail: member.ty.name,
+                                doc: member.doc,
+                            })
+                        }
+                        LogixValueDescriptor::Enum { variants } => {
+                            let variant = variants.iter().find(|v| v.name == name)?;
+                            Some(Completion {
+                                kind: CompletionKind::Variant,
+                                text: name,
+                                detail: ty.name,
+                                doc: variant.doc,
+                            })
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or(Completion {
+                        kind: CompletionKind::Member,
+                        text: name,
+                        detail: "",
+                        doc: "",
+                    })
+            })
+            .filter(|c| c.kind != CompletionKind::Member || !used.contains(&c.text.as_bytes()))
+            .collect(),
+        Wanted::Bool => ["true", "false"]
+            .into_iter()
+            .map(|text| Completion {
+                kind: CompletionKind::Bool,
+                text,
+                detail: "bool",
+                doc: "",
+            })
+            .collect(),
+        Wanted::LitStr
+        | Wanted::FullPath
+        | Wanted::RelPath
+        | Wanted::ExecutablePath
+        | Wanted::NameOnlyPath
+        | Wanted::ValidPath => {
+            let mut ret: Vec<_> = STR_TAGS
+                .into_iter()
+                .map(|text| Completion {
+                    kind: CompletionKind::StrTag,
+                    text,
+                    detail: "",
+                    doc: "",
+                })
+                .collect();
+            // Only plain strings can be included from a file
+            if wanted == Wanted::LitStr {
+                ret.push(Completion {
+                    kind: CompletionKind::Action,
+                    text: "@include",
+                    detail: "",
+                    doc: "Use the content of a file as the string",
+                });
+            }
+            ret
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Collects every type and enum variant reachable from `ty`
+fn collect_types(ty: &'static LogixTypeDescriptor, out: &mut Vec<&'static LogixTypeDescriptor>) {
+    let ty = ty.resolve();
+    // NOTE(2024.04): This is what stops recursive types from looping forever
+    if out.iter().any(|t| std::ptr::eq(*t, ty)) {
+        return;
+    }
+    out.push(ty);
+
+    match &ty.value {
+        LogixValueDescriptor::Native | LogixValueDescriptor::BackRef { .. } => {}
+        LogixValueDescriptor::Tuple { members } => {
+            for member in members {
+                collect_types(member, out);
+            }
+        }
+        LogixValueDescriptor::Struct { members } => {
+            for member in members {
+                collect_types(member.ty, out);
+            }
+        }
+        LogixValueDescriptor::Enum { variants } => {
+            for variant in variants {
+                collect_types(variant, out);
+            }
+        }
+        LogixValueDescriptor::List { item }
+        | LogixValueDescriptor::Array { item, .. }
+        | LogixValueDescriptor::Set { item } => collect_types(item, out),
+        LogixValueDescriptor::Map { key, value } => {
+            collect_types(key, out);
+            collect_types(value, out);
+        }
+        LogixValueDescriptor::Optional { inner } => collect_types(inner, out),
+    }
+}
+
+/// Returns the start of the identifier, string tag or action that ends at `offset`
+fn word_before(src: &[u8], offset: usize) -> usize {
+    let is_ident = |c: &u8| c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-');
+    let mut start = offset
+        - src[..offset]
+            .iter()
+            .rev()
+            .take_while(|c| is_ident(c))
+            .count();
+    match src[..start].last() {
+        Some(b'@') => start -= 1,
+        Some(b'#') => {
+            start -= src[..start]
+                .iter()
+                .rev()
+                .take_while(|&&c| c == b'#')
+                .count()
+        }
+        _ => {}
+    }
+    start
+}
+
+/// Returns the names of the members in the innermost struct around `word`, except the member
+/// whose name is being written
+fn used_members<'a>(node: &'a Node, start: usize, word: &Range<usize>) -> Option<Vec<&'a [u8]>> {
+    let mut ret = Vec::new();
+    let (mut open_end, mut close_start) = (None, None);
+    let mut pos = start;
+
+    for child in &node.children {
+        let (start, end) = (pos, pos + child.text_len());
+        pos = end;
+        match child {
+            Element::Token(token) => match token.kind {
+                TokenKind::Brace { start: true, .. } if open_end.is_none() => open_end = Some(end),
+                TokenKind::Brace { start: false, .. } => close_start = Some(start),
+                _ => {}
+            },
+            Element::Node(child) => {
+                if start < word.end && word.start < end {
+                    if let Some(inner) = used_members(child, start, word) {
+                        return Some(inner);
+                    }
+                }
+                if child.kind == NodeKind::Member {
+                    if let Some(Element::Token(key)) = child.children.first() {
+                        let typing = start == word.start && !word.is_empty();
+                        if !typing {
+                            ret.push(key.text.as_slice());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let inside = open_end.is_some_and(|end| end <= word.start)
+        && close_start.is_none_or(|start| word.end <= start);
+    (node.kind == NodeKind::Struct && inside).then_some(ret)
+}
@@ -0,0 +1,398 @@
+//! A lossless concrete syntax tree of a `.logix` file
+//!
+//! Unlike `LogixParser`, which only produces typed values, this keeps every byte of the input,
+//! including whitespace, comments and text that failed to parse. Writing the tree back out
+//! reproduces the input exactly, which makes it the starting point for formatting and editing.
+
+use std::fmt;
+
+use bstr::{BString, ByteSlice};
+
+use crate::{
+    error::TokenError,
+    token::{Action, Brace, Delim, LexemeKind, Lexer, Literal, StrTag, Token},
+};
+
+/// Deeper nesting than this is kept as a flat [`NodeKind::Error`] node
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum NodeKind {
+    /// The whole file
+    File,
+    /// A named struct, `Name { key: value }`
+    Struct,
+    /// A map, or a struct without a name, `{ key: value }`
+    Map,
+    /// A tuple, with or without a name, `Name(a, b)` or `(a, b)`
+    Tuple,
+    /// A list or set, `[a, b]`
+    List,
+    /// A member of a struct or map, `key: value`
+    Member,
+    /// An include action, `@include("path")`
+    Include,
+    /// Text that is not valid in its position
+    Error,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum TokenKind {
+    /// A run of spaces and tabs
+    Whitespace,
+    Newline,
+    /// A line or block comment, including the `//` or `/* */`
+    Comment,
+    Ident,
+    Num,
+    /// A string literal, `hashes` is the number of `#` in front of the tag and after the closing
+    /// quote, zero for a plain `"..."` string
+    Str {
+        tag: StrTag,
+        hashes: usize,
+    },
+    /// The `@include` action
+    Include,
+    Colon,
+    Comma,
+    Brace {
+        start: bool,
+        brace: Brace,
+    },
+    /// Text that could not be tokenized
+    Error(TokenError),
+}
+
+impl TokenKind {
+    /// Returns true for whitespace, newlines and comments
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Self::Whitespace | Self::Newline | Self::Comment)
+    }
+}
+
+/// A token in the tree together with its exact text
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct CstToken {
+    pub kind: TokenKind,
+    pub text: BString,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum Element {
+    Node(Node),
+    Token(CstToken),
+}
+
+impl Element {
+    /// The length of the text in bytes
+    pub fn text_len(&self) -> usize {
+        match self {
+            Self::Node(node) => node.text_len(),
+            Self::Token(token) => token.text.len(),
+        }
+    }
+}
+
+/// A node in the tree, the children include all the tokens that make up the node
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub children: Vec<Element>,
+}
+
+impl Node {
+    /// Parse `src` into a [`NodeKind::File`] node, this never fails, problems end up in
+    /// [`NodeKind::Error`] nodes and [`TokenKind::Error`] tokens
+    pub fn parse(src: &[u8]) -> Node {
+        Builder::new(src).file()
+    }
+
+    /// All the tokens in the node, in the order they appear in the text
+    pub fn tokens(&self) -> impl Iterator<Item = &CstToken> {
+        let mut stack = vec![self.children.iter()];
+        std::iter::from_fn(move || loop {
+            match stack.last_mut()?.next() {
+                Some(Element::Token(token)) => return Some(token),
+                Some(Element::Node(node)) => stack.push(node.children.iter()),
+                None => {
+                    stack.pop();
+                }
+            }
+        })
+    }
+
+    /// The length of the text in bytes
+    pub fn text_len(&self) -> usize {
+        self.tokens().map(|token| token.text.len()).sum()
+    }
+
+    /// Returns the text of the node, for a [`NodeKind::File`] this is identical to the input
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(self.text_len());
+        for token in self.tokens() {
+            ret.extend_from_slice(&token.text);
+        }
+        ret
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for token in self.tokens() {
+            write!(f, "{}", token.text)?;
+        }
+        Ok(())
+    }
+}
+
+struct Builder {
+    tokens: Vec<CstToken>,
+    pos: usize,
+    open: Vec<Brace>,
+}
+
+impl Builder {
+    fn new(src: &[u8]) -> Self {
+        let tokens = Lexer::new(src)
+            .filter_map(|lexeme| {
+                let text = &src[lexeme.span.range()];
+                let kind = match lexeme.kind {
+                    LexemeKind::Whitespace => TokenKind::Whitespace,
+                    LexemeKind::Token(Token::Newline(false)) => TokenKind::Newline,
+                    LexemeKind::Token(Token::Newline(true)) => return None,
+                    LexemeKind::Token(Token::Comment(_)) => TokenKind::Comment,
+                    LexemeKind::Token(Token::Ident(_)) => TokenKind::Ident,
+                    LexemeKind::Token(Token::Action(Action::Include)) => TokenKind::Include,
+                    LexemeKind::Token(Token::Literal(Literal::Num(_))) => TokenKind::Num,
+                    LexemeKind::Token(Token::Literal(Literal::Str(lit))) => TokenKind::Str {
+                        tag: lit.tag(),
+                        hashes: text.len() - text.trim_start_with(|c| c == '#').len(),
+                    },
+                    LexemeKind::Token(Token::Delim(Delim::Colon)) => TokenKind::Colon,
+                    LexemeKind::Token(Token::Delim(Delim::Comma)) => TokenKind::Comma,
+                    LexemeKind::Token(Token::Brace { start, brace }) => {
+                        TokenKind::Brace { start, brace }
+                    }
+                    LexemeKind::Error { error, .. } => TokenKind::Error(error),
+                };
+                Some(CstToken {
+                    kind,
+                    text: text.into(),
+                })
+            })
+            .collect();
+        Self {
+            tokens,
+            pos: 0,
+            open: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    /// The first token that isn't whitespace, and how far ahead it is
+    fn peek_past_ws(&self) -> Option<(usize, &TokenKind)> {
+        self.tokens[self.pos..]
+            .iter()
+            .enumerate()
+            .find(|(_, token)| token.kind != TokenKind::Whitespace)
+            .map(|(off, token)| (off, &token.kind))
+    }
+
+    fn bump(&mut self) -> Element {
+        // NOTE(2024.04): Tokens are never looked at again once consumed, so leave a dummy behind
+        let token = std::mem::replace(
+            &mut self.tokens[self.pos],
+            CstToken {
+                kind: TokenKind::Whitespace,
+                text: BString::default(),
+            },
+        );
+        self.pos += 1;
+        Element::Token(token)
+    }
+
+    fn bump_n(&mut self, n: usize, out: &mut Vec<Element>) {
+        for _ in 0..n {
+            out.push(self.bump());
+        }
+    }
+
+    /// Wrap the next token in an error node
+    fn error(&mut self, out: &mut Vec<Element>) {
+        let children = vec![self.bump()];
+        out.push(Element::Node(Node {
+            kind: NodeKind::Error,
+            children,
+        }));
+    }
+
+    fn file(mut self) -> Node {
+        let mut children = Vec::new();
+        while let Some(kind) = self.peek() {
+            if kind.is_trivia() || matches!(kind, TokenKind::Error(_)) {
+                children.push(self.bump());
+            } else if starts_value(kind) {
+                self.value(&mut children);
+            } else {
+                self.error(&mut children);
+            }
+        }
+        Node {
+            kind: NodeKind::File,
+            children,
+        }
+    }
+
+    fn value(&mut self, out: &mut Vec<Element>) {
+        match self.peek() {
+            Some(TokenKind::Ident) => {
+                let mut children = vec![self.bump()];
+                let kind = match self.peek_past_ws() {
+                    Some((off, &TokenKind::Brace { start: true, brace })) => {
+                        self.bump_n(off, &mut children);
+                        match brace {
+                            Brace::Curly => Some((NodeKind::Struct, brace)),
+                            Brace::Paren => Some((NodeKind::Tuple, brace)),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                if let Some((kind, brace)) = kind {
+                    out.push(Element::Node(self.group(kind, brace, children)));
+                } else {
+                    out.append(&mut children);
+                }
+            }
+            Some(&TokenKind::Brace { start: true, brace }) => {
+                let kind = match brace {
+                    Brace::Curly => NodeKind::Map,
+                    Brace::Paren => NodeKind::Tuple,
+                    Brace::Square => NodeKind::List,
+                    Brace::Angle => NodeKind::Error,
+                };
+                out.push(Element::Node(self.group(kind, brace, Vec::new())));
+            }
+            Some(TokenKind::Include) => {
+                let mut children = vec![self.bump()];
+                let node = match self.peek_past_ws() {
+                    Some((off, TokenKind::Brace { start: true, .. })) => {
+                        self.bump_n(off, &mut children);
+                        self.group(NodeKind::Include, Brace::Paren, children)
+                    }
+                    _ => Node {
+                        kind: NodeKind::Include,
+                        children,
+                    },
+                };
+                out.push(Element::Node(node));
+            }
+            Some(_) => out.push(self.bump()),
+            None => {}
+        }
+    }
+
+    /// Parse the contents of a brace, starting at the opening brace
+    fn group(&mut self, kind: NodeKind, brace: Brace, mut children: Vec<Element>) -> Node {
+        children.push(self.bump());
+
+        if self.open.len() >= MAX_DEPTH {
+            self.skip_group(&mut children);
+            return Node {
+                kind: NodeKind::Error,
+                children,
+            };
+        }
+
+        let members = matches!(kind, NodeKind::Struct | NodeKind::Map);
+        self.open.push(brace);
+        while let Some(cur) = self.peek() {
+            match *cur {
+                TokenKind::Brace {
+                    start: false,
+                    brace: end,
+                } => {
+                    if end == brace {
+                        children.push(self.bump());
+                        break;
+                    } else if self.open.contains(&end) {
+                        // NOTE(2024.04): Leave the brace to the node it belongs to
+                        break;
+                    } else {
+                        self.error(&mut children);
+                    }
+                }
+                ref cur if cur.is_trivia() => children.push(self.bump()),
+                TokenKind::Comma | TokenKind::Error(_) => children.push(self.bump()),
+                ref cur if starts_value(cur) => {
+                    if members {
+                        self.member(&mut children);
+                    } else {
+                        self.value(&mut children);
+                    }
+                }
+                _ => self.error(&mut children),
+            }
+        }
+        self.open.pop();
+
+        Node { kind, children }
+    }
+
+    /// Skip to the end of the current brace without creating any nodes
+    fn skip_group(&mut self, children: &mut Vec<Element>) {
+        let mut level = 1;
+        while let Some(kind) = self.peek() {
+            match kind {
+                TokenKind::Brace { start: true, .. } => level += 1,
+                TokenKind::Brace { start: false, .. } => level -= 1,
+                _ => {}
+            }
+            children.push(self.bump());
+            if level == 0 {
+                break;
+            }
+        }
+    }
+
+    fn member(&mut self, out: &mut Vec<Element>) {
+        if self.peek() != Some(&TokenKind::Ident) {
+            let mut children = Vec::new();
+            self.value(&mut children);
+            out.push(Element::Node(Node {
+                kind: NodeKind::Error,
+                children,
+            }));
+            return;
+        }
+
+        let mut children = vec![self.bump()];
+        if let Some((off, TokenKind::Colon)) = self.peek_past_ws() {
+            self.bump_n(off + 1, &mut children);
+            if let Some((off, kind)) = self.peek_past_ws() {
+                if starts_value(kind) {
+                    self.bump_n(off, &mut children);
+                    self.value(&mut children);
+                }
+            }
+        }
+        out.push(Element::Node(Node {
+            kind: NodeKind::Member,
+            children,
+        }));
+    }
+}
+
+fn starts_value(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Ident
+            | TokenKind::Num
+            | TokenKind::Str { .. }
+            | TokenKind::Include
+            | TokenKind::Brace { start: true, .. }
+    )
+}
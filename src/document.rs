@@ -0,0 +1,594 @@
+//! Editing `.logix` files while keeping comments and layout intact
+//!
+//! A [`Document`] is built on the lossless [`cst`](crate::cst), so everything that isn't edited is
+//! written back exactly as it was read. Values are addressed by a key path such as
+//! `editor.theme` where each part is a struct member, a map key or, if it is a number, the index
+//! of an item in a list or tuple.
+
+use std::fmt;
+
+use bstr::ByteSlice;
+use thiserror::Error;
+
+use crate::{
+    cst::{CstToken, Element, Node, NodeKind, TokenKind},
+    error::WriteError,
+    writer::{is_valid_ident, LogixWriter},
+    LogixType,
+};
+
+#[derive(Error, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
+pub enum EditError {
+    #[error("invalid key path `{0}`")]
+    InvalidPath(String),
+    #[error("no value at `{0}`")]
+    NotFound(String),
+    #[error("there is already a value at `{0}`")]
+    AlreadyExists(String),
+    #[error("the value at `{0}` is not a struct, map or list")]
+    NotAContainer(String),
+    #[error("failed to write the value for `{0}`: {1}")]
+    WriteFailed(String, WriteError),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Seg<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn parse_path(path: &str) -> Result<Vec<Seg<'_>>, EditError> {
+    if path.is_empty() {
+        return Ok(Vec::new());
+    }
+    path.split('.')
+        .map(|part| {
+            if !part.is_empty() && part.bytes().all(|c| c.is_ascii_digit()) {
+                part.parse()
+                    .map(Seg::Index)
+                    .map_err(|_| EditError::InvalidPath(path.into()))
+            } else if is_valid_ident(part) {
+                Ok(Seg::Key(part))
+            } else {
+                Err(EditError::InvalidPath(path.into()))
+            }
+        })
+        .collect()
+}
+
+/// A `.logix` file that can be edited without losing comments or layout
+#[derive(Debug, Clone)]
+pub struct Document {
+    root: Node,
+    /// The indentation of one level, taken from the first indented line
+    indent_unit: String,
+}
+
+impl Document {
+    pub fn parse(src: &[u8]) -> Self {
+        let root = Node::parse(src);
+        let mut after_newline = false;
+        let indent_unit = root
+            .tokens()
+            .find_map(|token| {
+                let found = after_newline && token.kind == TokenKind::Whitespace;
+                after_newline = token.kind == TokenKind::Newline;
+                found.then(|| token.text.to_str_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "  ".into());
+        Self { root, indent_unit }
+    }
+
+    /// The syntax tree of the whole file
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+
+    /// Returns the text of the document, only the edited parts differ from the input
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.root.to_bytes()
+    }
+
+    /// Returns the value at `path`, an empty path refers to the root value
+    pub fn get(&self, path: &str) -> Result<&Element, EditError> {
+        let loc = self.locate(path, &parse_path(path)?)?;
+        Ok(self.element(&loc))
+    }
+
+    /// Replace the value at `path`, a missing struct member or map entry is inserted
+    pub fn set<T: LogixType>(&mut self, path: &str, value: &T) -> Result<(), EditError> {
+        let segs = parse_path(path)?;
+        let loc = match self.locate(path, &segs) {
+            Ok(loc) => loc,
+            Err(EditError::NotFound(_)) if matches!(segs.last(), Some(Seg::Key(_))) => {
+                return self.insert(path, value);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let indent = self.line_indent(&loc);
+        let elements = self.render(path, value, &indent)?;
+        let (&idx, parent) = loc.split_last().unwrap();
+        self.node_mut(parent).children.splice(idx..=idx, elements);
+        Ok(())
+    }
+
+    /// Insert a new struct member, map entry or list item at `path`
+    ///
+    /// A list item is inserted before the item currently at the index, or at the end if the index
+    /// is the length of the list
+    pub fn insert<T: LogixType>(&mut self, path: &str, value: &T) -> Result<(), EditError> {
+        let segs = parse_path(path)?;
+        let Some((last, parent_segs)) = segs.split_last() else {
+            return Err(EditError::AlreadyExists(path.into()));
+        };
+        let parent_path = path.rsplit_once('.').map_or("", |(parent, _)| parent);
+        let loc = self.locate(parent_path, parent_segs)?;
+        let container = match self.element(&loc) {
+            Element::Node(node) => node,
+            Element::Token(_) => return Err(EditError::NotAContainer(parent_path.into())),
+        };
+
+        match (*last, container.kind) {
+            (Seg::Key(key), NodeKind::Struct | NodeKind::Map) => {
+                if find_member(container, key).is_some() {
+                    return Err(EditError::AlreadyExists(path.into()));
+                }
+                self.insert_member(path, &loc, key, value)
+            }
+            (Seg::Index(index), NodeKind::List) => {
+                if index > item_indices(container).len() {
+                    return Err(EditError::NotFound(path.into()));
+                }
+                self.insert_item(path, &loc, index, value)
+            }
+            _ => Err(EditError::NotAContainer(parent_path.into())),
+        }
+    }
+
+    /// Remove the struct member, map entry or list item at `path`
+    pub fn remove(&mut self, path: &str) -> Result<(), EditError> {
+        let segs = parse_path(path)?;
+        let loc = self.locate(path, &segs)?;
+        match segs.last() {
+            Some(Seg::Key(_)) => {
+                // The value is inside the member, it is the member that is removed
+                let (&member, parent) = loc[..loc.len() - 1].split_last().unwrap();
+                remove_entry(&mut self.node_mut(parent).children, member);
+                Ok(())
+            }
+            Some(Seg::Index(_)) => {
+                let (&item, parent) = loc.split_last().unwrap();
+                let parent = self.node_mut(parent);
+                if parent.kind != NodeKind::List {
+                    return Err(EditError::NotAContainer(
+                        path.rsplit_once('.').map_or("", |(p, _)| p).into(),
+                    ));
+                }
+                remove_entry(&mut parent.children, item);
+                Ok(())
+            }
+            None => Err(EditError::InvalidPath(path.into())),
+        }
+    }
+
+    /// Find the child indices leading to the value at `segs`
+    fn locate(&self, path: &str, segs: &[Seg]) -> Result<Vec<usize>, EditError> {
+        let not_found = || EditError::NotFound(path.into());
+        let mut loc = vec![self
+            .root
+            .children
+            .iter()
+            .position(is_value)
+            .ok_or_else(not_found)?];
+
+        for (i, seg) in segs.iter().enumerate() {
+            let Element::Node(node) = self.element(&loc) else {
+                return Err(EditError::NotAContainer(join_path(&segs[..i])));
+            };
+            match (*seg, node.kind) {
+                (Seg::Key(key), NodeKind::Struct | NodeKind::Map) => {
+                    let member = find_member(node, key).ok_or_else(not_found)?;
+                    let Element::Node(member_node) = &node.children[member] else {
+                        unreachable!()
+                    };
+                    loc.push(member);
+                    loc.push(member_value(member_node).ok_or_else(not_found)?);
+                }
+                (Seg::Index(index), NodeKind::List | NodeKind::Tuple) => {
+                    loc.push(*item_indices(node).get(index).ok_or_else(not_found)?);
+                }
+                _ => return Err(EditError::NotAContainer(join_path(&segs[..i]))),
+            }
+        }
+        Ok(loc)
+    }
+
+    fn element(&self, loc: &[usize]) -> &Element {
+        let (&last, parent) = loc.split_last().unwrap();
+        let mut node = &self.root;
+        for &idx in parent {
+            let Element::Node(child) = &node.children[idx] else {
+                unreachable!()
+            };
+            node = child;
+        }
+        &node.children[last]
+    }
+
+    fn node_mut(&mut self, loc: &[usize]) -> &mut Node {
+        let mut node = &mut self.root;
+        for &idx in loc {
+            let Element::Node(child) = &mut node.children[idx] else {
+                unreachable!()
+            };
+            node = child;
+        }
+        node
+    }
+
+    /// The indentation of the line where the element at `loc` starts
+    fn line_indent(&self, loc: &[usize]) -> String {
+        let mut levels = Vec::with_capacity(loc.len());
+        let mut node = &self.root;
+        for &idx in loc {
+            levels.push((node, idx));
+            if let Element::Node(child) = &node.children[idx] {
+                node = child;
+            }
+        }
+
+        // NOTE(2024.04): Only the tokens in front of the element on the same line are visited
+        let mut parts = Vec::new();
+        for (node, idx) in levels.into_iter().rev() {
+            if line_start(&node.children[..idx], &mut parts) {
+                break;
+            }
+        }
+        parts
+            .iter()
+            .rev()
+            .flat_map(|part| part.iter())
+            .take_while(|&&b| b == b' ' || b == b'\t')
+            .map(|&b| char::from(b))
+            .collect()
+    }
+
+    /// Write `value` for a line indented by `indent` and turn it into syntax tree elements
+    fn render<T: LogixType>(
+        &self,
+        path: &str,
+        value: &T,
+        indent: &str,
+    ) -> Result<Vec<Element>, EditError> {
+        let mut text = String::new();
+        LogixWriter::with_indent(&mut text, indent, &self.indent_unit)
+            .write_value(value)
+            .map_err(|e| EditError::WriteFailed(path.into(), e))?;
+        Ok(Node::parse(text.as_bytes()).children)
+    }
+
+    fn insert_member<T: LogixType>(
+        &mut self,
+        path: &str,
+        loc: &[usize],
+        key: &str,
+        value: &T,
+    ) -> Result<(), EditError> {
+        let container_indent = self.line_indent(loc);
+        let member_indent = entry_indent(self.node_mut(loc), is_member)
+            .unwrap_or_else(|| container_indent.clone() + &self.indent_unit);
+
+        let mut children = vec![
+            token(TokenKind::Ident, key),
+            token(TokenKind::Colon, ":"),
+            token(TokenKind::Whitespace, " "),
+        ];
+        children.extend(self.render(path, value, &member_indent)?);
+        let member = Element::Node(Node {
+            kind: NodeKind::Member,
+            children,
+        });
+
+        let container = self.node_mut(loc);
+        let last_member = container.children.iter().rposition(is_member);
+        let after = last_member.unwrap_or(opening_brace(container));
+        let (pos, new) = match find_newline(container, after) {
+            Some(newline) => (newline + 1, line_of(&member_indent, vec![member])),
+            None => {
+                // NOTE(2024.04): There is no line to add the member after, so it gets its own
+                let mut new = vec![token(TokenKind::Newline, "\n")];
+                new.extend(line_of(&member_indent, vec![member]));
+                if last_member.is_none() {
+                    new.extend(whitespace(&container_indent));
+                }
+                (after + 1, new)
+            }
+        };
+        container.children.splice(pos..pos, new);
+        Ok(())
+    }
+
+    fn insert_item<T: LogixType>(
+        &mut self,
+        path: &str,
+        loc: &[usize],
+        index: usize,
+        value: &T,
+    ) -> Result<(), EditError> {
+        let container_indent = self.line_indent(loc);
+        let list = self.node_mut(loc);
+        let items = item_indices(list);
+        let multi_line = list
+            .children
+            .iter()
+            .any(|e| matches!(e, Element::Token(t) if t.kind == TokenKind::Newline));
+
+        if !multi_line {
+            let item = self.render(path, value, &container_indent)?;
+            let list = self.node_mut(loc);
+            let comma = || token(TokenKind::Comma, ",");
+            let space = || token(TokenKind::Whitespace, " ");
+            let (pos, new) = if let Some(&next) = items.get(index) {
+                (next, [item, vec![comma(), space()]].concat())
+            } else if let Some(&last) = items.last() {
+                (last + 1, [vec![comma(), space()], item].concat())
+            } else {
+                (opening_brace(list) + 1, item)
+            };
+            list.children.splice(pos..pos, new);
+            return Ok(());
+        }
+
+        let item_indent = entry_indent(list, is_value)
+            .unwrap_or_else(|| container_indent.clone() + &self.indent_unit);
+        let item = self.render(path, value, &item_indent)?;
+        let list = self.node_mut(loc);
+        let has_comma = |list: &Node, idx: usize| {
+            list.children[idx + 1..]
+                .iter()
+                .take_while(|e| !matches!(e, Element::Token(t) if t.kind == TokenKind::Newline))
+                .any(|e| matches!(e, Element::Token(t) if t.kind == TokenKind::Comma))
+        };
+        let uses_commas = items.iter().any(|&idx| has_comma(list, idx));
+
+        if let Some(&next) = items.get(index) {
+            // The indentation in front of `next` is reused for the new line
+            let mut new = item;
+            if uses_commas {
+                new.push(token(TokenKind::Comma, ","));
+            }
+            new.push(token(TokenKind::Newline, "\n"));
+            new.extend(whitespace(&item_indent));
+            list.children.splice(next..next, new);
+            return Ok(());
+        }
+
+        let mut trailing = Vec::new();
+        if let Some(&last) = items.last() {
+            if uses_commas {
+                if has_comma(list, last) {
+                    trailing.push(token(TokenKind::Comma, ","));
+                } else {
+                    list.children.insert(last + 1, token(TokenKind::Comma, ","));
+                }
+            }
+        }
+        let after = items.last().copied().unwrap_or(opening_brace(list));
+        let new_line = {
+            let mut children = item;
+            children.extend(trailing);
+            children
+        };
+        let (pos, new) = match find_newline(list, after) {
+            Some(newline) => (newline + 1, line_of(&item_indent, new_line)),
+            None => {
+                let mut new = vec![token(TokenKind::Newline, "\n")];
+                new.extend(line_of(&item_indent, new_line));
+                new.extend(whitespace(&container_indent));
+                (after + 1, new)
+            }
+        };
+        list.children.splice(pos..pos, new);
+        Ok(())
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.root, f)
+    }
+}
+
+fn join_path(segs: &[Seg]) -> String {
+    segs.iter()
+        .map(|seg| match seg {
+            Seg::Key(key) => key.to_string(),
+            Seg::Index(index) => index.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn token(kind: TokenKind, text: &str) -> Element {
+    Element::Token(CstToken {
+        kind,
+        text: text.into(),
+    })
+}
+
+fn whitespace(text: &str) -> Option<Element> {
+    (!text.is_empty()).then(|| token(TokenKind::Whitespace, text))
+}
+
+/// An indented line with `elements` followed by a newline
+fn line_of(indent: &str, elements: Vec<Element>) -> Vec<Element> {
+    let mut ret = Vec::from_iter(whitespace(indent));
+    ret.extend(elements);
+    ret.push(token(TokenKind::Newline, "\n"));
+    ret
+}
+
+fn is_token(element: &Element, kind: &TokenKind) -> bool {
+    matches!(element, Element::Token(t) if t.kind == *kind)
+}
+
+/// Returns true if the element is a value, as opposed to trivia, punctuation or an error
+fn is_value(element: &Element) -> bool {
+    match element {
+        Element::Node(node) => !matches!(node.kind, NodeKind::Member | NodeKind::Error),
+        Element::Token(token) => matches!(
+            token.kind,
+            TokenKind::Ident | TokenKind::Num | TokenKind::Str { .. }
+        ),
+    }
+}
+
+fn is_member(element: &Element) -> bool {
+    matches!(element, Element::Node(node) if node.kind == NodeKind::Member)
+}
+
+fn opening_brace(node: &Node) -> usize {
+    node.children
+        .iter()
+        .position(|e| {
+            matches!(
+                e,
+                Element::Token(CstToken {
+                    kind: TokenKind::Brace { start: true, .. },
+                    ..
+                })
+            )
+        })
+        .unwrap_or(0)
+}
+
+/// The child indices of the items of a list or tuple
+fn item_indices(node: &Node) -> Vec<usize> {
+    let open = opening_brace(node);
+    (open + 1..node.children.len())
+        .filter(|&idx| is_value(&node.children[idx]))
+        .collect()
+}
+
+fn find_member(node: &Node, key: &str) -> Option<usize> {
+    node.children.iter().position(|e| match e {
+        Element::Node(Node {
+            kind: NodeKind::Member,
+            children,
+        }) => matches!(children.first(), Some(Element::Token(t)) if t.text == key),
+        _ => false,
+    })
+}
+
+/// The index of the value inside a member
+fn member_value(member: &Node) -> Option<usize> {
+    let colon = member
+        .children
+        .iter()
+        .position(|e| is_token(e, &TokenKind::Colon))?;
+    (colon + 1..member.children.len()).find(|&idx| is_value(&member.children[idx]))
+}
+
+/// Collect the text of `elements` from the end until a newline, returns true if one was found
+fn line_start<'a>(elements: &'a [Element], parts: &mut Vec<&'a [u8]>) -> bool {
+    for element in elements.iter().rev() {
+        match element {
+            Element::Node(node) => {
+                if line_start(&node.children, parts) {
+                    return true;
+                }
+            }
+            Element::Token(token) => match token.text.rfind_byte(b'\n') {
+                Some(pos) => {
+                    parts.push(&token.text[pos + 1..]);
+                    return true;
+                }
+                None => parts.push(&token.text),
+            },
+        }
+    }
+    false
+}
+
+/// The indentation used by an entry that starts a line
+fn entry_indent(node: &Node, is_entry: impl Fn(&Element) -> bool) -> Option<String> {
+    node.children.windows(3).find_map(|w| match w {
+        [newline, Element::Token(ws), entry]
+            if is_token(newline, &TokenKind::Newline)
+                && ws.kind == TokenKind::Whitespace
+                && is_entry(entry) =>
+        {
+            Some(ws.text.to_str_lossy().into_owned())
+        }
+        _ => None,
+    })
+}
+
+/// The first newline after the child at `idx`, stopping at a closing brace
+fn find_newline(node: &Node, idx: usize) -> Option<usize> {
+    (idx + 1..node.children.len())
+        .take_while(|&i| {
+            !matches!(
+                node.children[i],
+                Element::Token(CstToken {
+                    kind: TokenKind::Brace { start: false, .. },
+                    ..
+                })
+            )
+        })
+        .find(|&i| is_token(&node.children[i], &TokenKind::Newline))
+}
+
+/// Remove the entry at `idx` together with its delimiter, and its line if it was alone on it
+fn remove_entry(children: &mut Vec<Element>, idx: usize) {
+    let kind_at = |i: usize| match children.get(i) {
+        Some(Element::Token(t)) => Some(&t.kind),
+        _ => None,
+    };
+
+    let start = if idx > 0 && kind_at(idx - 1) == Some(&TokenKind::Whitespace) {
+        idx - 1
+    } else {
+        idx
+    };
+    let mut end = idx + 1;
+    let mut comma = false;
+    loop {
+        match kind_at(end) {
+            Some(TokenKind::Whitespace) => end += 1,
+            Some(TokenKind::Comma) if !comma => {
+                comma = true;
+                end += 1;
+            }
+            _ => break,
+        }
+    }
+
+    if start == 0 || kind_at(start - 1) == Some(&TokenKind::Newline) {
+        // Alone on the line, so the whole line goes, including a comment at the end of it
+        let mut line_end = end;
+        if matches!(&children.get(line_end), Some(Element::Token(t)) if t.kind == TokenKind::Comment && !t.text.contains(&b'\n'))
+        {
+            line_end += 1;
+        }
+        if line_end == children.len() {
+            children.drain(start..line_end);
+            return;
+        } else if kind_at(line_end) == Some(&TokenKind::Newline) {
+            children.drain(start..=line_end);
+            return;
+        }
+    }
+
+    if comma {
+        children.drain(idx..end);
+    } else {
+        // The last item, so it is the delimiter in front of it that goes
+        let before = (0..idx)
+            .rev()
+            .find(|&i| kind_at(i) != Some(&TokenKind::Whitespace))
+            .filter(|&i| kind_at(i) == Some(&TokenKind::Comma));
+        children.drain(before.unwrap_or(idx)..=idx);
+    }
+}
@@ -1,6 +1,10 @@
-pub use crate::span::SourceSpan;
+pub use crate::{
+    loader::{FileId, SourceFiles},
+    span::{ResolvedSpan, SourceSpan},
+};
 use core::fmt;
 use owo_colors::OwoColorize;
+use std::path::PathBuf;
 
 use thiserror::Error;
 
@@ -10,8 +14,9 @@ use crate::{
 };
 
 pub type Result<T, E = ParseError> = std::result::Result<T, E>;
+pub type WriteResult<T = ()> = std::result::Result<T, WriteError>;
 
-#[derive(Error, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Error, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
 pub enum TokenError {
     #[error("invalid utf-8 sequence")]
     LitStrNotUtf8,
@@ -21,6 +26,8 @@ pub enum TokenError {
     MissingCommentTerminator,
     #[error("unknown string tag `{0}`")]
     UnknownStrTag(ShortStr),
+    #[error("unknown action `@{0}`")]
+    UnknownAction(ShortStr),
     #[error("unexpected end of the string, expected `\"`")]
     MissingStringTerminator,
     #[error("unexpected end of {tag} string, expected {suffix}")]
@@ -78,6 +85,27 @@ pub enum PathError {
     JoinAbsolute,
 }
 
+#[derive(Error, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum NumError {
+    #[error("expected at least one digit")]
+    Empty,
+
+    #[error("invalid digit {0:?}")]
+    InvalidDigit(char),
+
+    #[error("the number is negative, expected an unsigned integer")]
+    Negative,
+
+    #[error("the number is too large")]
+    TooLarge,
+
+    #[error("the number is too small")]
+    TooSmall,
+
+    #[error("expected a non-zero number")]
+    Zero,
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum Wanted {
     Token(Token<'static>),
@@ -89,6 +117,9 @@ pub enum Wanted {
     NameOnlyPath,
     ValidPath,
     LitNum(&'static str),
+    LitFloat,
+    LitChar,
+    Bool,
     Ident,
     ItemOrEnd,
     ItemDelim,
@@ -128,6 +159,9 @@ impl fmt::Display for Wanted {
             Self::NameOnlyPath => write!(f, "file or directory name"),
             Self::ValidPath => write!(f, "path"),
             Self::LitNum(name) => write!(f, "{name}"),
+            Self::LitFloat => write!(f, "floating point number"),
+            Self::LitChar => write!(f, "string with a single character"),
+            Self::Bool => write!(f, "either `true` or `false`"),
             Self::Ident => write!(f, "identifier"),
             Self::ItemOrEnd => write!(f, "item or end"),
             Self::ItemDelim => write!(f, "delimiter"),
@@ -137,7 +171,9 @@ impl fmt::Display for Wanted {
     }
 }
 
-#[derive(Error, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// An error from parsing, use [`LogixLoader::report`](crate::LogixLoader::report) to show where
+/// it happened
+#[derive(Error, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum ParseError {
     #[error(transparent)]
     FsError(#[from] logix_vfs::Error),
@@ -145,21 +181,21 @@ pub enum ParseError {
     #[error(transparent)]
     Warning(Warn),
 
-    #[error("Missing struct member `{member}` while parsing `{type_name}` in {span}")]
+    #[error("Missing struct member `{member}` while parsing `{type_name}`")]
     MissingStructMember {
         span: SourceSpan,
         type_name: &'static str,
         member: &'static str,
     },
 
-    #[error("Duplicate struct member `{member}` while parsing `{type_name}` in {span}")]
+    #[error("Duplicate struct member `{member}` while parsing `{type_name}`")]
     DuplicateStructMember {
         span: SourceSpan,
         type_name: &'static str,
         member: &'static str,
     },
 
-    #[error("Unexpected {got_token} while parsing `{while_parsing}`, expected {wanted} in {span}")]
+    #[error("Unexpected {got_token} while parsing `{while_parsing}`, expected {wanted}")]
     UnexpectedToken {
         span: SourceSpan,
         while_parsing: &'static str,
@@ -167,58 +203,169 @@ pub enum ParseError {
         wanted: Wanted,
     },
 
-    #[error("Failed to parse string, {error} in {span}")]
+    #[error("Failed to parse string, {error}")]
     StrEscError {
         span: SourceSpan,
         error: EscStrError,
     },
 
-    #[error("Failed to parse input, {error} in {span}")]
+    #[error("Failed to parse input, {error}")]
     TokenError { span: SourceSpan, error: TokenError },
 
-    #[error("Failed to include file as `{while_parsing}`, {error} in {span}")]
+    #[error("Failed to include file as `{while_parsing}`, {error}")]
     IncludeError {
         span: SourceSpan,
         while_parsing: &'static str,
         error: IncludeError,
     },
 
-    #[error("Failed to parse path, {error} in {span}")]
+    #[error("Failed to parse path, {error}")]
     PathError { span: SourceSpan, error: PathError },
+
+    #[error("Failed to parse `{while_parsing}`, {error}")]
+    NumError {
+        span: SourceSpan,
+        while_parsing: &'static str,
+        error: NumError,
+    },
+
+    #[error("Nesting is deeper than the limit of {max_depth} levels")]
+    NestingTooDeep { span: SourceSpan, max_depth: usize },
+
+    /// Reported by a type that is read through serde, the message comes from the type itself
+    #[error("Failed to deserialize, {message}")]
+    Custom { span: SourceSpan, message: String },
+
+    /// Several errors were found while parsing in recovering mode
+    #[error(transparent)]
+    Multiple(ParseErrors),
+}
+
+impl ParseError {
+    /// The location of the error, if any
+    pub fn span(&self) -> Option<&SourceSpan> {
+        match self {
+            Self::FsError(_) => None,
+            Self::Warning(warning) => Some(warning.span()),
+            Self::MissingStructMember { span, .. }
+            | Self::DuplicateStructMember { span, .. }
+            | Self::UnexpectedToken { span, .. }
+            | Self::StrEscError { span, .. }
+            | Self::TokenError { span, .. }
+            | Self::IncludeError { span, .. }
+            | Self::PathError { span, .. }
+            | Self::NumError { span, .. }
+            | Self::NestingTooDeep { span, .. }
+            | Self::Custom { span, .. } => Some(span),
+            Self::Multiple(errors) => errors.iter().find_map(Self::span),
+        }
+    }
 }
 
-impl fmt::Debug for ParseError {
+/// An error or list of errors together with the files they point at, returned by
+/// [`LogixLoader::report`](crate::LogixLoader::report)
+///
+/// `{}` gives one line per error ending with the location, `{:?}` shows the source around each
+/// error like rustc does
+pub struct Report<'a, E> {
+    error: &'a E,
+    files: &'a SourceFiles,
+}
+
+impl<'a, E> Report<'a, E> {
+    pub(crate) fn new(error: &'a E, files: &'a SourceFiles) -> Self {
+        Self { error, files }
+    }
+}
+
+impl fmt::Display for Report<'_, ParseError> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let ParseError::Multiple(errors) = self.error {
+            return fmt::Display::fmt(&Report::new(errors, self.files), f);
+        }
+
+        write!(f, "{}", self.error)?;
+        match self.error.span().and_then(|span| self.files.resolve(span)) {
+            Some(span) => write!(f, " in {span}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Report<'_, Warn> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        match self.files.resolve(self.error.span()) {
+            Some(span) => write!(f, " in {span}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Report<'_, ParseErrors> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, e) in self.error.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", Report::new(e, self.files))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Report<'_, ParseErrors> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for e in self.error.iter() {
+            write!(f, "{:?}", Report::new(e, self.files))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Report<'_, ParseError> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let ParseError::Multiple(errors) = self.error {
+            return fmt::Debug::fmt(&Report::new(errors, self.files), f);
+        }
+
+        let resolve = |span| self.files.resolve(span);
         writeln!(f)?;
-        match self {
-            Self::FsError(e) => writeln!(f, "{}{}", "error: ".bright_red().bold(), e.bold()),
-            Self::Warning(Warn::DuplicateMapEntry { span, key }) => write_error(
+        match self.error {
+            ParseError::FsError(e) => writeln!(f, "{}{}", "error: ".bright_red().bold(), e.bold()),
+            ParseError::Warning(Warn::DuplicateMapEntry { span, key }) => write_error(
                 f,
                 format_args!("Duplicate entry `{key}` while parsing `Map`"),
-                span,
+                resolve(span),
                 format_args!("overwrites the previous entry"),
             ),
-            Self::MissingStructMember {
+            ParseError::Warning(Warn::DuplicateSetEntry { span, type_name }) => write_error(
+                f,
+                format_args!("Duplicate entry while parsing `{type_name}`"),
+                resolve(span),
+                format_args!("the value is already in the set"),
+            ),
+            ParseError::MissingStructMember {
                 span,
                 type_name,
                 member,
             } => write_error(
                 f,
                 format_args!("Missing struct member while parsing `{type_name}`"),
-                span,
+                resolve(span),
                 format_args!("expected `{member}`"),
             ),
-            Self::DuplicateStructMember {
+            ParseError::DuplicateStructMember {
                 span,
                 type_name,
                 member,
             } => write_error(
                 f,
                 format_args!("Duplicate struct member while parsing `{type_name}`"),
-                span,
+                resolve(span),
                 format_args!("unexpected `{member}`"),
             ),
-            Self::UnexpectedToken {
+            ParseError::UnexpectedToken {
                 span,
                 while_parsing,
                 got_token,
@@ -226,36 +373,127 @@ impl fmt::Debug for ParseError {
             } => write_error(
                 f,
                 format_args!("Unexpected {got_token} while parsing `{while_parsing}`"),
-                span,
+                resolve(span),
                 format_args!("expected {wanted}"),
             ),
-            Self::StrEscError { span, error } => {
-                write_error(f, "Failed to parse escaped string", span, error)
+            ParseError::StrEscError { span, error } => {
+                write_error(f, "Failed to parse escaped string", resolve(span), error)
             }
-            Self::TokenError { span, error } => {
-                write_error(f, "Failed to parse input", span, error)
+            ParseError::TokenError { span, error } => {
+                write_error(f, "Failed to parse input", resolve(span), error)
             }
-            Self::IncludeError {
+            ParseError::IncludeError {
                 span,
                 while_parsing,
                 error,
             } => write_error(
                 f,
                 format_args!("Failed to include file as `{while_parsing}`"),
+                resolve(span),
+                error,
+            ),
+            ParseError::PathError { span, error } => {
+                write_error(f, "Failed to parse path", resolve(span), error)
+            }
+            ParseError::NumError {
                 span,
+                while_parsing,
+                error,
+            } => write_error(
+                f,
+                format_args!("Failed to parse `{while_parsing}`"),
+                resolve(span),
                 error,
             ),
-            Self::PathError { span, error } => write_error(f, "Failed to parse path", span, error),
+            ParseError::NestingTooDeep { span, max_depth } => write_error(
+                f,
+                "Nesting is too deep",
+                resolve(span),
+                format_args!("exceeds the limit of {max_depth} levels"),
+            ),
+            ParseError::Custom { span, message } => {
+                write_error(f, "Failed to deserialize", resolve(span), message)
+            }
+            ParseError::Multiple(..) => unreachable!(),
+        }
+    }
+}
+
+/// A list of errors sorted in source order, returned when parsing in recovering mode
+#[derive(Error, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ParseErrors {
+    errors: Vec<ParseError>,
+}
+
+impl ParseErrors {
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, ParseError> {
+        self.errors.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<ParseError> {
+        self.errors
+    }
+}
+
+impl From<Vec<ParseError>> for ParseErrors {
+    fn from(mut errors: Vec<ParseError>) -> Self {
+        // NOTE: The sort is stable, so errors without a span keep their relative order at the end
+        errors.sort_by(|a, b| match (a.span(), b.span()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+        Self { errors }
+    }
+}
+
+impl From<ParseError> for ParseErrors {
+    fn from(error: ParseError) -> Self {
+        match error {
+            ParseError::Multiple(errors) => errors,
+            error => Self {
+                errors: vec![error],
+            },
         }
     }
 }
 
+impl IntoIterator for ParseErrors {
+    type Item = ParseError;
+    type IntoIter = std::vec::IntoIter<ParseError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, e) in self.errors.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{e}")?;
+        }
+        Ok(())
+    }
+}
+
 fn write_error(
     f: &mut impl fmt::Write,
     message: impl fmt::Display,
-    span: &SourceSpan,
+    span: Option<ResolvedSpan>,
     expected: impl fmt::Display,
 ) -> fmt::Result {
+    let Some(span) = span else {
+        // NOTE(2024.04): The file is gone or the span is empty, so there is no source to show
+        writeln!(f, "{}{}", "error: ".bright_red().bold(), message.bold())?;
+        return writeln!(f, "    = {}", expected.bright_red().bold());
+    };
+
     let context = 1;
     let ln_width = span.calc_ln_width(context);
     writeln!(f, "{}{}", "error: ".bright_red().bold(), message.bold())?;
@@ -270,6 +508,10 @@ fn write_error(
     )?;
     writeln!(f, "{:>ln_width$} {}", "", "|".bright_blue().bold(),)?;
 
+    if span.line() != span.last_line() {
+        return write_multi_line(f, &span, context, ln_width, expected);
+    }
+
     for (ln, span, line) in span.lines(context) {
         writeln!(
             f,
@@ -303,12 +545,170 @@ fn write_error(
     Ok(())
 }
 
+/// Render a span covering several lines the way rustc does, the spanned lines are marked by a
+/// `|` gutter that starts and ends with a marker pointing at the first and last character
+fn write_multi_line(
+    f: &mut impl fmt::Write,
+    span: &ResolvedSpan,
+    context: usize,
+    ln_width: usize,
+    expected: impl fmt::Display,
+) -> fmt::Result {
+    // Lines in the middle of long spans are left out, like rustc does
+    let (first, last) = (span.line(), span.last_line());
+    let elided = first + 4..last.saturating_sub(1);
+
+    for (ln, range, line) in span.lines(context) {
+        let line = line.trim_end();
+        let chars_before = |col: usize| line.get(..col).map_or(col, |s| s.chars().count());
+
+        if elided.contains(&ln) {
+            if ln == elided.start {
+                writeln!(
+                    f,
+                    "{:>ln_width$} {}",
+                    "...".bright_blue().bold(),
+                    "|".bright_red().bold()
+                )?;
+            }
+            continue;
+        }
+
+        write!(
+            f,
+            "{:>ln_width$} {} ",
+            ln.bright_blue().bold(),
+            "|".bright_blue().bold()
+        )?;
+        if ln > first && range.is_some() {
+            write!(f, "{}", "|".bright_red().bold())?;
+        } else {
+            f.write_char(' ')?;
+        }
+        if line.is_empty() {
+            writeln!(f)?;
+        } else {
+            writeln!(f, " {line}")?;
+        }
+
+        match range {
+            Some(range) if ln == first => writeln!(
+                f,
+                "{:>ln_width$} {}  {}",
+                "",
+                "|".bright_blue().bold(),
+                format_args!("{}^", "_".repeat(chars_before(range.start) + 1))
+                    .bright_red()
+                    .bold(),
+            )?,
+            Some(range) if ln == last => writeln!(
+                f,
+                "{:>ln_width$} {} {} {}",
+                "",
+                "|".bright_blue().bold(),
+                format_args!("|{}^", "_".repeat(chars_before(range.end).max(1)))
+                    .bright_red()
+                    .bold(),
+                expected.bright_red().bold(),
+            )?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Error, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub enum Warn {
-    #[error(
-        "Duplicate entry `{key}` while parsing `Map`, overwrites the previous entry in {span}"
-    )]
+    #[error("Duplicate entry `{key}` while parsing `Map`, overwrites the previous entry")]
     DuplicateMapEntry { span: SourceSpan, key: ShortStr },
+
+    #[error("Duplicate entry while parsing `{type_name}`, the value is already in the set")]
+    DuplicateSetEntry {
+        span: SourceSpan,
+        type_name: &'static str,
+    },
+}
+
+impl Warn {
+    pub fn kind(&self) -> WarnKind {
+        match self {
+            Self::DuplicateMapEntry { .. } => WarnKind::DuplicateMapEntry,
+            Self::DuplicateSetEntry { .. } => WarnKind::DuplicateSetEntry,
+        }
+    }
+
+    /// The location of the duplicate entry
+    pub fn span(&self) -> &SourceSpan {
+        match self {
+            Self::DuplicateMapEntry { span, .. } | Self::DuplicateSetEntry { span, .. } => span,
+        }
+    }
+}
+
+/// The kind of a `Warn`, used to configure how it is handled
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum WarnKind {
+    DuplicateMapEntry,
+    DuplicateSetEntry,
+}
+
+/// Decides what happens when a warning is encountered while parsing
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum WarningPolicy {
+    /// Fail with `ParseError::Warning`
+    #[default]
+    Deny,
+    /// Ignore the warning
+    Allow,
+    /// Keep going, the warning is returned by `LogixLoader::load_file_with_warnings`
+    Collect,
+}
+
+/// An error from writing a value with [`LogixWriter`](crate::LogixWriter)
+#[derive(Error, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum WriteError {
+    #[error("Failed to write the value")]
+    Fmt(#[from] fmt::Error),
+
+    #[error("Failed to write, `None` can only be written by leaving out a member")]
+    None,
+
+    #[error("Failed to write, `{0}` is not a valid identifier")]
+    InvalidIdent(String),
+
+    #[error("Failed to write, the path {0:?} is not valid utf-8")]
+    PathNotUtf8(PathBuf),
+}
+
+/// An error from writing a value through serde, see [`crate::serde::Serializer`]
+#[cfg(feature = "serde")]
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum SerializeError {
+    #[error("Failed to write the value")]
+    Fmt(#[from] fmt::Error),
+
+    /// Reported by the type that is written, the message comes from the type itself
+    #[error("Failed to serialize, {0}")]
+    Custom(String),
+
+    #[error("Failed to serialize, `None` can only be written by leaving out a member")]
+    None,
+
+    #[error("Failed to serialize, `{0}` is not a valid identifier")]
+    InvalidIdent(String),
+}
+
+#[cfg(feature = "serde")]
+impl From<WriteError> for SerializeError {
+    fn from(e: WriteError) -> Self {
+        match e {
+            WriteError::Fmt(e) => Self::Fmt(e),
+            WriteError::None => Self::None,
+            WriteError::InvalidIdent(ident) => Self::InvalidIdent(ident),
+            WriteError::PathNotUtf8(_) => Self::Custom(e.to_string()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -333,7 +733,25 @@ mod tests {
                     key: "test".into()
                 }
             ),
-            "DuplicateMapEntry { span: SourceSpan { file: CachedFile(\"\"), pos: 0, range: SingleLine { line: 0, col: 0..0 } }, key: \"test\" }",
+            format!(
+                "DuplicateMapEntry {{ span: SourceSpan {{ file: FileId({}), start: 0, end: 0 }}, key: \"test\" }}",
+                usize::MAX
+            ),
+        );
+
+        // Without the file only the message is shown
+        let e = ParseError::NestingTooDeep {
+            span: SourceSpan::empty(),
+            max_depth: 1,
+        };
+        let files = SourceFiles::default();
+        assert_eq!(
+            Report::new(&e, &files).to_string(),
+            "Nesting is deeper than the limit of 1 levels"
+        );
+        assert_eq!(
+            strip_ansi_escapes::strip_str(format!("{:?}", Report::new(&e, &files))),
+            "\nerror: Nesting is too deep\n    = exceeds the limit of 1 levels\n"
         );
         assert_eq!(
             ParseError::FsError(logix_vfs::Error::NotFound {
@@ -0,0 +1,176 @@
+//! Formatting `.logix` files to the canonical layout
+//!
+//! The layout is the one produced by `LogixWriter`, two spaces of indentation per level of
+//! braces, one space after `:` and `,` and no space in front of them. Line breaks are kept as
+//! written, except that runs of blank lines are reduced to one and blank lines at the start or
+//! end of the file or a brace are removed. Comments are kept as they are.
+
+use bstr::ByteSlice;
+use thiserror::Error;
+
+use crate::{
+    error::TokenError,
+    span::SourceSpan,
+    token::{Brace, LexemeKind, Lexer, Literal, StrTag, Token},
+};
+
+const INDENT: &str = "  ";
+
+/// The file could not be formatted since it contains text the tokenizer rejects
+#[derive(Error, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
+#[error("{error} at {line}:{col}")]
+pub struct FormatError {
+    pub line: usize,
+    pub col: usize,
+    pub error: TokenError,
+}
+
+/// Returns `src` in the canonical layout
+pub fn format(src: &[u8]) -> Result<String, FormatError> {
+    // Split into lines of tokens, a multi-line token belongs to the line where it starts
+    let mut lines = vec![Vec::new()];
+    for lexeme in Lexer::new(src) {
+        match lexeme.kind {
+            LexemeKind::Whitespace | LexemeKind::Token(Token::Newline(true)) => {}
+            LexemeKind::Token(Token::Newline(false)) => lines.push(Vec::new()),
+            LexemeKind::Token(token) => {
+                // NOTE(2024.04): Tokens are always valid utf-8, or they would be errors
+                let text = src[lexeme.span.range()].to_str().unwrap();
+                lines.last_mut().unwrap().push((token, text));
+            }
+            LexemeKind::Error { error, at } => {
+                return Err(FormatError {
+                    line: at.line,
+                    col: at.col,
+                    error,
+                })
+            }
+        }
+    }
+
+    let mut ret = String::with_capacity(src.len());
+    // The indentation level of the line where each open brace is
+    let mut open = Vec::new();
+    let mut blank = false;
+    let mut prev_line_opens = true;
+
+    for line in lines {
+        let (Some(&(first, _)), Some(&(last, _))) = (line.first(), line.last()) else {
+            blank = true;
+            continue;
+        };
+
+        let starts_with_close = matches!(first, Token::Brace { start: false, .. });
+        let level = match open.last() {
+            Some(&level) if starts_with_close => level,
+            Some(&level) => level + 1,
+            None => 0,
+        };
+        if std::mem::take(&mut blank) && !starts_with_close && !prev_line_opens {
+            ret.push('\n');
+        }
+        prev_line_opens = matches!(last, Token::Brace { start: true, .. });
+
+        let prefix = INDENT.repeat(level);
+        ret.push_str(&prefix);
+        let mut prev = None;
+        for (token, text) in line {
+            if let Some(prev) = prev {
+                if space_between(prev, token) {
+                    ret.push(' ');
+                }
+            }
+            match token {
+                Token::Brace { start: true, .. } => open.push(level),
+                Token::Brace { start: false, .. } => {
+                    open.pop();
+                }
+                _ => {}
+            }
+            match token {
+                Token::Literal(Literal::Str(lit)) if lit.tag() == StrTag::Txt => {
+                    ret.push_str(&reindent_txt(text, &format!("{prefix}{INDENT}")))
+                }
+                _ => ret.push_str(text),
+            }
+            prev = Some(token);
+        }
+        ret.push('\n');
+    }
+
+    Ok(ret)
+}
+
+fn space_between(prev: Token, next: Token) -> bool {
+    match (prev, next) {
+        (_, Token::Comment(_)) | (Token::Comment(_), _) => true,
+        (_, Token::Brace { start: false, .. }) => false,
+        (Token::Delim(_), _) => true,
+        (_, Token::Delim(_)) => false,
+        (Token::Brace { start: true, .. }, _) => false,
+        (_, Token::Brace { start: true, brace }) => brace == Brace::Curly,
+        _ => true,
+    }
+}
+
+/// Move the lines of a `#txt` string to `prefix`, unless that changes the value
+fn reindent_txt(text: &str, prefix: &str) -> String {
+    let mut lines = text.split('\n');
+    let first = lines.next().unwrap();
+    let rest = lines.collect::<Vec<_>>();
+    let Some((_, text_lines)) = rest.split_last() else {
+        // NOTE(2024.04): A string on one line has no lines to move
+        return text.into();
+    };
+    let Some(common) = text_lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+    else {
+        return text.into();
+    };
+
+    let mut ret = String::from(first);
+    for (i, line) in rest.iter().enumerate() {
+        ret.push('\n');
+        if i + 1 == rest.len() {
+            // The closing quote goes where `LogixWriter` puts it, at the same indent as the text
+            ret.push_str(prefix);
+            ret.push_str(line.trim_start());
+        } else if !line.trim().is_empty() {
+            ret.push_str(prefix);
+            ret.push_str(&line[common..]);
+        }
+    }
+
+    if decode(&ret).is_some() && decode(&ret) == decode(text) {
+        ret
+    } else {
+        text.into()
+    }
+}
+
+fn decode(text: &str) -> Option<String> {
+    match Lexer::new(text.as_bytes()).next()?.kind {
+        LexemeKind::Token(Token::Literal(Literal::Str(lit))) => lit
+            .decode_str(&SourceSpan::empty())
+            .ok()
+            .map(|s| s.into_owned()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spacing() {
+        let src = b"Root{a :1 , b:( 1,2, )\nc:Size ( 1 ,2)//x\nd:[1,2]}";
+        assert_eq!(
+            format(src).unwrap(),
+            "Root {a: 1, b: (1, 2,)\n  c: Size(1, 2) //x\n  d: [1, 2]}\n"
+        );
+    }
+}
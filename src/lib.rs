@@ -2,15 +2,23 @@
 #![allow(clippy::len_without_is_empty)]
 
 mod action;
+pub mod complete;
+pub mod cst;
+pub mod document;
 pub mod error;
+pub mod format;
 mod loader;
 mod parser;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod serde;
 mod span;
 pub mod token;
 pub mod type_trait;
 pub mod types;
+mod writer;
 
-pub use crate::{loader::LogixLoader, parser::LogixParser};
+pub use crate::{loader::LogixLoader, parser::LogixParser, writer::LogixWriter};
 pub use logix_type_derive::LogixType;
 pub use type_trait::LogixType;
 
@@ -1,53 +1,100 @@
-use std::{fmt, io::Read, path::Path, sync::Arc};
+use std::{cell::RefCell, fmt, io::Read, path::Path, sync::Arc};
 
 use bstr::ByteSlice;
 use indexmap::IndexMap;
 use logix_vfs::LogixVfs;
 
-use crate::{error::ParseError, parser::LogixParser, token::Token, type_trait::LogixType};
+use crate::{
+    error::{ParseError, ParseErrors, Report, Result, Warn, WarnKind, WarningPolicy},
+    parser::LogixParser,
+    span::{ResolvedSpan, SourceSpan},
+    token::Token,
+    type_trait::LogixType,
+};
+
+/// Identifies a file loaded by a [`LogixLoader`], this is what a `SourceSpan` refers to
+///
+/// The file itself is owned by the loader, use [`LogixLoader::files`] to look it up. The ids are
+/// only unique within one loader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(usize);
+
+impl FileId {
+    /// Used by spans that don't belong to any file
+    pub(crate) const NONE: Self = Self(usize::MAX);
+}
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct InnerCachedFile {
+    id: FileId,
     path: Arc<Path>,
-    data: Arc<[u8]>,
+    data: Box<[u8]>,
+    /// The position where each line starts, the first line always starts at 0
+    line_starts: Box<[usize]>,
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone)]
 pub(crate) struct CachedFile {
-    inner: Box<InnerCachedFile>,
+    inner: Arc<InnerCachedFile>,
 }
 
 impl CachedFile {
-    pub(crate) fn empty() -> CachedFile {
+    fn new(id: FileId, path: Arc<Path>, data: &[u8]) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(data.find_iter(b"\n").map(|pos| pos + 1))
+            .collect();
         Self {
-            inner: Box::new(InnerCachedFile {
-                path: Arc::from(Path::new("")),
-                data: Arc::from(b"".as_slice()),
+            inner: Arc::new(InnerCachedFile {
+                id,
+                path,
+                data: data.into(),
+                line_starts,
             }),
         }
     }
 
     #[cfg(test)]
     pub(crate) fn from_slice(path: impl AsRef<Path>, data: &[u8]) -> CachedFile {
-        Self {
-            inner: Box::new(InnerCachedFile {
-                path: Arc::from(Path::new(path.as_ref())),
-                data: Arc::from(data),
-            }),
-        }
+        Self::new(FileId(0), Arc::from(path.as_ref()), data)
     }
 
-    pub fn path(&self) -> &Path {
-        &self.inner.path
+    pub fn id(&self) -> FileId {
+        self.inner.id
     }
 
-    pub fn lines(&self) -> bstr::Lines {
-        self.inner.data.lines()
+    pub fn path(&self) -> &Arc<Path> {
+        &self.inner.path
     }
 
     pub fn data(&self) -> &[u8] {
         &self.inner.data
     }
+
+    /// The 1-based number of the line that contains `pos`
+    pub fn line_of(&self, pos: usize) -> usize {
+        self.inner
+            .line_starts
+            .partition_point(|&start| start <= pos)
+    }
+
+    /// The position where the 1-based line `ln` starts, or the end of the file if there is no such line
+    pub fn line_start(&self, ln: usize) -> usize {
+        ln.checked_sub(1)
+            .and_then(|i| self.inner.line_starts.get(i).copied())
+            .unwrap_or(self.inner.data.len())
+    }
+
+    /// The 1-based line `ln` without the line terminator, empty if there is no such line
+    pub fn line(&self, ln: usize) -> &[u8] {
+        let line = &self.inner.data[self.line_start(ln)..self.line_start(ln + 1)];
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+
+    /// The number of lines in the file, an empty line after the last newline is not counted
+    pub fn line_count(&self) -> usize {
+        let starts = &self.inner.line_starts;
+        starts.len() - usize::from(starts.last() == Some(&self.inner.data.len()))
+    }
 }
 
 impl fmt::Debug for CachedFile {
@@ -56,12 +103,52 @@ impl fmt::Debug for CachedFile {
     }
 }
 
+impl PartialEq for CachedFile {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for CachedFile {}
+
+/// The files read by a [`LogixLoader`], used to find what a `SourceSpan` points at
+#[derive(Debug, Default)]
+pub struct SourceFiles {
+    files: IndexMap<Arc<Path>, CachedFile>,
+    /// The id of the first file in `files`, ids are not reused after the files are forgotten
+    first_id: usize,
+}
+
+impl SourceFiles {
+    /// The path of the file, if it is still loaded
+    pub fn path(&self, id: FileId) -> Option<&Path> {
+        self.get(id).map(|file| &**file.path())
+    }
+
+    /// Look up the file of `span`, returns `None` if the file is no longer loaded
+    pub fn resolve(&self, span: &SourceSpan) -> Option<ResolvedSpan<'_>> {
+        self.get(span.file_id())
+            .map(|file| ResolvedSpan::new(*span, file))
+    }
+
+    fn get(&self, id: FileId) -> Option<&CachedFile> {
+        let (_, file) = self.files.get_index(id.0.checked_sub(self.first_id)?)?;
+        Some(file)
+    }
+}
+
 /// Load a logix configuration
 #[derive(Debug)]
 pub struct LogixLoader<FS: LogixVfs> {
     fs: FS,
-    files: IndexMap<Arc<Path>, Arc<[u8]>>,
+    files: SourceFiles,
     tmp: Vec<u8>,
+    default_warning_policy: WarningPolicy,
+    warning_policy: IndexMap<WarnKind, WarningPolicy>,
+    // NOTE(2024.04): A `RefCell` so `LogixParser::warning` can keep taking `&self`
+    warnings: RefCell<Vec<Warn>>,
+    pub(crate) recovering: bool,
+    pub(crate) max_depth: usize,
 }
 
 impl<FS: LogixVfs> LogixLoader<FS> {
@@ -69,20 +156,80 @@ impl<FS: LogixVfs> LogixLoader<FS> {
     pub fn new(fs: FS) -> Self {
         Self {
             fs,
-            files: IndexMap::new(),
+            files: SourceFiles::default(),
             tmp: Vec::with_capacity(0x10000),
+            default_warning_policy: WarningPolicy::Deny,
+            warning_policy: IndexMap::new(),
+            warnings: RefCell::new(Vec::new()),
+            recovering: false,
+            max_depth: 128,
         }
     }
 
+    /// Set how all kinds of warnings are handled, this resets any policy set by `set_warning_policy`
+    pub fn set_default_warning_policy(&mut self, policy: WarningPolicy) {
+        self.default_warning_policy = policy;
+        self.warning_policy.clear();
+    }
+
+    /// Set how warnings of the specified kind are handled
+    pub fn set_warning_policy(&mut self, kind: WarnKind, policy: WarningPolicy) {
+        self.warning_policy.insert(kind, policy);
+    }
+
+    /// Set how deeply braces may be nested before parsing fails, the default is 128
+    ///
+    /// This keeps recursive types from overflowing the stack on malicious or broken input
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Returns how warnings of the specified kind are handled
+    pub fn warning_policy(&self, kind: WarnKind) -> WarningPolicy {
+        self.warning_policy
+            .get(&kind)
+            .copied()
+            .unwrap_or(self.default_warning_policy)
+    }
+
+    pub(crate) fn warning(&self, warning: Warn) -> Result<()> {
+        match self.warning_policy(warning.kind()) {
+            WarningPolicy::Deny => Err(ParseError::Warning(warning)),
+            WarningPolicy::Allow => Ok(()),
+            WarningPolicy::Collect => {
+                self.warnings.borrow_mut().push(warning);
+                Ok(())
+            }
+        }
+    }
+
+    /// Gives access to the files, call `forget_files` after changing any of them
+    pub(crate) fn fs_mut(&mut self) -> &mut FS {
+        &mut self.fs
+    }
+
+    /// Drop the loaded files so they are read again from the file system
+    ///
+    /// Spans into the dropped files can no longer be resolved
+    pub(crate) fn forget_files(&mut self) {
+        let SourceFiles { files, first_id } = &mut self.files;
+        *first_id += files.len();
+        files.clear();
+    }
+
     pub(crate) fn get_file(&self, path: impl AsRef<Path>) -> Option<CachedFile> {
-        let (key, value) = self.files.get_key_value(path.as_ref())?;
+        self.files.files.get(path.as_ref()).cloned()
+    }
 
-        Some(CachedFile {
-            inner: Box::new(InnerCachedFile {
-                path: key.clone(),
-                data: value.clone(),
-            }),
-        })
+    /// The files read so far, used to resolve the spans of errors and warnings
+    pub fn files(&self) -> &SourceFiles {
+        &self.files
+    }
+
+    /// Render `error` with the files it points at, `{}` gives a single line and `{:?}` shows the
+    /// source around the error
+    pub fn report<'a, E>(&'a self, error: &'a E) -> Report<'a, E> {
+        Report::new(error, &self.files)
     }
 
     pub(crate) fn open_file(
@@ -90,43 +237,84 @@ impl<FS: LogixVfs> LogixLoader<FS> {
         path: impl AsRef<Path>,
     ) -> Result<CachedFile, logix_vfs::Error> {
         match self
+            .files
             .files
             .entry(Arc::<Path>::from(self.fs.canonicalize_path(path.as_ref())?))
         {
             indexmap::map::Entry::Vacant(entry) => {
+                let id = FileId(self.files.first_id + entry.index());
                 let path = entry.key().clone();
                 self.tmp.clear();
                 let mut r = self.fs.open_file(entry.key())?;
                 r.read_to_end(&mut self.tmp)
                     .map_err(|e| logix_vfs::Error::from_io(entry.key().to_path_buf(), e))?;
-                let data = entry.insert(Arc::from(self.tmp.as_slice())).clone();
-                Ok(CachedFile {
-                    inner: Box::new(InnerCachedFile { path, data }),
-                })
+                Ok(entry.insert(CachedFile::new(id, path, &self.tmp)).clone())
             }
-            indexmap::map::Entry::Occupied(entry) => Ok(CachedFile {
-                inner: Box::new(InnerCachedFile {
-                    path: entry.key().clone(),
-                    data: entry.get().clone(),
-                }),
-            }),
+            indexmap::map::Entry::Occupied(entry) => Ok(entry.get().clone()),
         }
     }
 
     /// Load the file located at `path` inside `FS` and parse it as `T`
+    ///
+    /// Warnings are handled according to the warning policy. Warnings with the
+    /// `WarningPolicy::Collect` policy are discarded, use `load_file_with_warnings` to get them
     pub fn load_file<T: LogixType>(&mut self, path: impl AsRef<Path>) -> Result<T, ParseError> {
+        self.load_file_with_warnings(path).map(|(value, _)| value)
+    }
+
+    /// Load the file located at `path` inside `FS` and parse it as `T`
+    ///
+    /// Returns the value together with the warnings that has the `WarningPolicy::Collect` policy
+    pub fn load_file_with_warnings<T: LogixType>(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<(T, Vec<Warn>), ParseError> {
+        self.warnings.get_mut().clear();
+        let value = self.load_file_inner(path)?;
+        Ok((value, std::mem::take(self.warnings.get_mut())))
+    }
+
+    /// Load the file located at `path` inside `FS` and parse it as `T`, reporting all errors
+    ///
+    /// Unlike `load_file`, parsing continues after an error where possible, so every problem in
+    /// the file is returned at once, sorted in source order. Collected warnings are discarded as
+    /// in `load_file`
+    pub fn load_file_all_errors<T: LogixType>(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<T, ParseErrors> {
+        self.warnings.get_mut().clear();
+        self.recovering = true;
+        let ret = self.load_file_inner(path);
+        self.recovering = false;
+        ret.map_err(ParseErrors::from)
+    }
+
+    fn load_file_inner<T: LogixType>(&mut self, path: impl AsRef<Path>) -> Result<T, ParseError> {
+        self.parse_file(path, T::descriptor().name, |p| {
+            T::logix_parse(p).map(|v| v.value)
+        })
+    }
+
+    /// Parse the file located at `path` using `parse`, the rest of the file must be empty
+    pub(crate) fn parse_file<R>(
+        &mut self,
+        path: impl AsRef<Path>,
+        while_parsing: &'static str,
+        parse: impl FnOnce(&mut LogixParser<FS>) -> Result<R>,
+    ) -> Result<R, ParseError> {
         let file = self.open_file(path)?;
         let mut p = LogixParser::new(self, &file);
 
-        let ret = T::logix_parse(&mut p)?;
+        let ret = parse(&mut p)?;
 
         // This will either skip any newlines and comments, or return EOF
-        p.req_newline(T::descriptor().name)?;
+        p.req_newline(while_parsing)?;
 
         // From now on EOF should always be returned
-        p.req_token(T::descriptor().name, Token::Newline(true))?;
+        p.req_token(while_parsing, Token::Newline(true))?;
 
-        Ok(ret.value)
+        Ok(ret)
     }
 }
 
@@ -136,23 +324,85 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn line_index() {
+        let file = CachedFile::from_slice("a", b"first\r\n\nthird\n");
+        assert_eq!(file.line_count(), 3);
+        assert_eq!(CachedFile::from_slice("a", b"").line_count(), 0);
+        assert_eq!(
+            (1..=5).map(|ln| file.line(ln)).collect::<Vec<_>>(),
+            [&b"first"[..], b"", b"third", b"", b""]
+        );
+        assert_eq!(
+            [0, 6, 7, 8, 13, 14].map(|pos| file.line_of(pos)),
+            [1, 1, 2, 3, 3, 4]
+        );
+        assert_eq!(file.line_start(3), 8);
+    }
+
     #[test]
     fn basics() {
         let tmp = tempfile::tempdir().unwrap();
         std::fs::write(tmp.path().join("test.logix"), b"10").unwrap();
 
-        format!(
-            "{:?}",
-            CachedFile {
-                inner: Box::new(InnerCachedFile {
-                    path: Arc::from(Path::new("a")),
-                    data: Arc::from(b"".as_slice()),
-                })
-            }
-        );
+        let _ = format!("{:?}", CachedFile::from_slice("a", b""));
 
         let mut loader = LogixLoader::new(RelFs::new(tmp.path()));
         loader.load_file::<u32>("test.logix").unwrap();
         loader.load_file::<u32>("test.logix").unwrap(); // Twice to test cache
     }
+
+    #[test]
+    fn file_ids() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.logix"), b"10").unwrap();
+        std::fs::write(tmp.path().join("b.logix"), b"10\nx").unwrap();
+
+        let mut loader = LogixLoader::new(RelFs::new(tmp.path()));
+        let a = loader.open_file("a.logix").unwrap();
+        let b = loader.open_file("b.logix").unwrap();
+        assert_eq!((a.id(), b.id()), (FileId(0), FileId(1)));
+        assert_eq!(loader.open_file("a.logix").unwrap(), a);
+
+        // The span is resolved through the loader, until the files are forgotten
+        let e = loader.load_file::<u32>("b.logix").unwrap_err();
+        let span = *e.span().unwrap();
+        let resolved = loader.files().resolve(&span).unwrap();
+        assert_eq!(resolved.to_string(), "b.logix:2:0");
+        assert_eq!(resolved.value(), "x");
+        assert_eq!(loader.files().path(b.id()), Some(Path::new("b.logix")));
+        assert_eq!(
+            loader.report(&e).to_string(),
+            "Unexpected identifier while parsing `u32`, expected end of file in b.logix:2:0"
+        );
+
+        loader.forget_files();
+        assert!(loader.files().resolve(&span).is_none());
+        let b = loader.open_file("b.logix").unwrap();
+        assert_eq!(b.id(), FileId(2));
+        assert_eq!(
+            e.to_string(),
+            "Unexpected identifier while parsing `u32`, expected end of file"
+        );
+    }
+
+    #[test]
+    fn large_file() {
+        // Multi-line strings and a very long line used to be slow or panic
+        let mut data = String::from("{\n");
+        for i in 0..20_000 {
+            data += &format!("  key{i}: #txt\"\n    line\n    line\n  \"#\n");
+        }
+        data += &format!("  long: \"{}\"\n}}\n", "x".repeat(100_000));
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("test.logix"), data).unwrap();
+
+        let mut loader = LogixLoader::new(RelFs::new(tmp.path()));
+        let map = loader
+            .load_file::<crate::types::Map<String>>("test.logix")
+            .unwrap();
+        assert_eq!(map.len(), 20_001);
+        assert_eq!(map[&crate::types::ShortStr::from("long")].len(), 100_000);
+    }
 }
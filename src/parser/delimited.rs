@@ -17,23 +17,33 @@ enum State {
     GotDelim { newline: bool, comma: bool },
 }
 
-pub struct ParseDelimited<'p, 'fs, 'f, FS: LogixVfs, T: LogixType> {
+pub struct ParseDelimited<'p, 'fs, 'f, FS: LogixVfs, T> {
     p: &'p mut LogixParser<'fs, 'f, FS>,
     while_parsing: &'static str,
     _phantom: PhantomData<T>,
     state: State,
+    depth: usize,
+    errors: Vec<ParseError>,
 }
 
-impl<'p, 'fs, 'f, FS: LogixVfs, T: LogixType> ParseDelimited<'p, 'fs, 'f, FS, T> {
-    pub(super) fn new(p: &'p mut LogixParser<'fs, 'f, FS>, while_parsing: &'static str) -> Self {
+impl<'p, 'fs, 'f, FS: LogixVfs, T> ParseDelimited<'p, 'fs, 'f, FS, T> {
+    pub(crate) fn new(p: &'p mut LogixParser<'fs, 'f, FS>, while_parsing: &'static str) -> Self {
+        let depth = p.depth();
         Self {
             p,
             while_parsing,
             _phantom: PhantomData,
             state: State::Init,
+            depth,
+            errors: Vec::new(),
         }
     }
 
+    /// The parser used to read the items
+    pub fn parser(&mut self) -> &mut LogixParser<'fs, 'f, FS> {
+        self.p
+    }
+
     pub fn skip_until_next(&mut self) -> Result<bool> {
         loop {
             let mut parse_value = false;
@@ -125,9 +135,13 @@ impl<'p, 'fs, 'f, FS: LogixVfs, T: LogixType> ParseDelimited<'p, 'fs, 'f, FS, T>
         }
     }
 
-    pub fn next_item(&mut self) -> Result<Option<Value<T>>> {
+    /// Parse the next item using `f`, returns `None` once the end is reached
+    pub fn next_with<R>(
+        &mut self,
+        f: impl FnOnce(&mut LogixParser<'fs, 'f, FS>) -> Result<R>,
+    ) -> Result<Option<R>> {
         if self.skip_until_next()? {
-            let value = T::logix_parse(self.p)?;
+            let value = f(self.p)?;
             self.state = State::ValueParsed;
             Ok(Some(value))
         } else {
@@ -135,21 +149,36 @@ impl<'p, 'fs, 'f, FS: LogixVfs, T: LogixType> ParseDelimited<'p, 'fs, 'f, FS, T>
         }
     }
 
-    pub fn req_next_item(&mut self) -> Result<Value<T>> {
-        if let Some(ret) = self.next_item()? {
-            Ok(ret)
-        } else {
-            let (span, token) = self.p.peek_token().unwrap();
-            Err(ParseError::UnexpectedToken {
-                span,
-                while_parsing: self.while_parsing,
-                got_token: token.token_type_name(),
-                wanted: Wanted::Item,
-            })
+    /// Handle an error that occurred while parsing an item
+    ///
+    /// Unless in recovering mode, the error is returned as is. Otherwise it is kept until the
+    /// end of the list and the rest of the item is skipped, up to the next delimiter or the end.
+    pub fn recover(&mut self, error: ParseError) -> Result<()> {
+        if !self.p.is_recovering() {
+            return Err(error);
+        }
+        match error {
+            ParseError::Multiple(e) => self.errors.extend(e),
+            e => self.errors.push(e),
         }
+        self.state = if self.p.skip_item(&mut self.errors, self.depth, true)? {
+            State::GotDelim {
+                newline: true,
+                comma: false,
+            }
+        } else {
+            State::ValueParsed
+        };
+        Ok(())
+    }
+
+    /// Returns the errors kept by `recover`, if any
+    fn finish_recovery(&mut self) -> Result<()> {
+        self.p.finish_recovery(std::mem::take(&mut self.errors))
     }
 
     pub fn req_end(&mut self, end_brace: Brace) -> Result<()> {
+        self.finish_recovery()?;
         let parse_value = self.skip_until_next()?;
         let (span, token) = self.p.peek_token().unwrap();
 
@@ -175,6 +204,32 @@ impl<'p, 'fs, 'f, FS: LogixVfs, T: LogixType> ParseDelimited<'p, 'fs, 'f, FS, T>
     }
 }
 
+impl<'p, 'fs, 'f, FS: LogixVfs, T: LogixType> ParseDelimited<'p, 'fs, 'f, FS, T> {
+    pub fn next_item(&mut self) -> Result<Option<Value<T>>> {
+        loop {
+            match self.next_with(T::logix_parse) {
+                Ok(Some(value)) => return Ok(Some(value)),
+                Ok(None) => return self.finish_recovery().map(|()| None),
+                Err(e) => self.recover(e)?,
+            }
+        }
+    }
+
+    pub fn req_next_item(&mut self) -> Result<Value<T>> {
+        if let Some(ret) = self.next_item()? {
+            Ok(ret)
+        } else {
+            let (span, token) = self.p.peek_token().unwrap();
+            Err(ParseError::UnexpectedToken {
+                span,
+                while_parsing: self.while_parsing,
+                got_token: token.token_type_name(),
+                wanted: Wanted::Item,
+            })
+        }
+    }
+}
+
 impl<'p, 'fs, 'f, FS: LogixVfs, T: LogixType> Iterator for ParseDelimited<'p, 'fs, 'f, FS, T> {
     type Item = Result<Value<T>>;
 
@@ -191,59 +246,61 @@ mod tests {
 
     use super::*;
 
-    fn test_vec<T: LogixType + fmt::Debug>(src: &str) -> Result<Vec<T>> {
+    /// Parse a list, errors are rendered with their location
+    fn test_vec<T: LogixType + fmt::Debug>(src: &str) -> Result<Vec<T>, String> {
         run_test(src, |p, _| {
             p.req_wrapped("list1", Brace::Square, |p| -> Result<_> {
                 p.parse_delimited("list2")
                     .map(|r| r.map(|v| v.value))
                     .collect()
             })
+            .map(|r| r.value)
+            .map_err(|e| p.loader.report(&e).to_string())
         })
-        .map(|r| r.value)
     }
 
     #[test]
-    fn basics() -> Result<()> {
+    fn basics() -> Result<(), String> {
         assert_eq!(test_vec::<i32>("[]")?, vec![]);
         assert_eq!(test_vec::<i32>("[\n]")?, vec![]);
         assert_eq!(
-            test_vec::<i32>("[,]").unwrap_err().to_string(),
+            test_vec::<i32>("[,]").unwrap_err(),
             "Unexpected `,` while parsing `list2`, expected item or end in test.logix:1:1"
         );
         assert_eq!(
-            test_vec::<i32>("[\n,]").unwrap_err().to_string(),
+            test_vec::<i32>("[\n,]").unwrap_err(),
             "Unexpected `,` while parsing `list2`, expected item or end in test.logix:2:0"
         );
         assert_eq!(
-            test_vec::<i32>("[,\n]").unwrap_err().to_string(),
+            test_vec::<i32>("[,\n]").unwrap_err(),
             "Unexpected `,` while parsing `list2`, expected item or end in test.logix:1:1"
         );
         assert_eq!(
-            test_vec::<i32>("[").unwrap_err().to_string(),
+            test_vec::<i32>("[").unwrap_err(),
             "Unexpected end of file while parsing `list2`, expected item or end in test.logix:1:1"
         );
         assert_eq!(
-            test_vec::<i32>("[\n").unwrap_err().to_string(),
+            test_vec::<i32>("[\n").unwrap_err(),
             "Unexpected end of file while parsing `list2`, expected item or end in test.logix:1:1"
         );
         assert_eq!(
-            test_vec::<i32>("[0").unwrap_err().to_string(),
+            test_vec::<i32>("[0").unwrap_err(),
             "Unexpected end of file while parsing `list2`, expected item or end in test.logix:1:2"
         );
         assert_eq!(
-            test_vec::<i32>("[0\n").unwrap_err().to_string(),
+            test_vec::<i32>("[0\n").unwrap_err(),
             "Unexpected end of file while parsing `list2`, expected item or end in test.logix:1:2"
         );
         assert_eq!(
-            test_vec::<i32>("[0,").unwrap_err().to_string(),
+            test_vec::<i32>("[0,").unwrap_err(),
             "Unexpected end of file while parsing `list2`, expected item or end in test.logix:1:3"
         );
         assert_eq!(
-            test_vec::<i32>("[0,\n").unwrap_err().to_string(),
+            test_vec::<i32>("[0,\n").unwrap_err(),
             "Unexpected end of file while parsing `list2`, expected item or end in test.logix:1:3"
         );
         assert_eq!(
-            test_vec::<i32>("[0 1]").unwrap_err().to_string(),
+            test_vec::<i32>("[0 1]").unwrap_err(),
             "Unexpected number while parsing `list2`, expected delimiter in test.logix:1:3"
         );
         assert_eq!(test_vec::<i32>("[10]")?, vec![10]);
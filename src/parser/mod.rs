@@ -9,7 +9,6 @@ use crate::{
     types::ShortStr,
     LogixType,
 };
-use bstr::ByteSlice;
 use logix_vfs::LogixVfs;
 
 mod delimited;
@@ -18,10 +17,9 @@ pub use delimited::ParseDelimited;
 #[derive(Clone)]
 struct ParseState {
     cur_pos: usize,
-    cur_col: usize,
-    cur_ln: usize,
     last_was_newline: bool,
     eof: bool,
+    depth: usize,
 }
 
 /// The parser used by the `LogixType` trait
@@ -38,17 +36,144 @@ impl<'fs, 'f, FS: LogixVfs> LogixParser<'fs, 'f, FS> {
             file,
             state: ParseState {
                 cur_pos: 0,
-                cur_col: 0,
-                cur_ln: 1,
                 last_was_newline: true,
                 eof: false,
+                depth: 0,
             },
         }
     }
 
+    /// Report a warning, returns an error if the warning policy denies it
     pub fn warning(&self, warning: Warn) -> Result<()> {
-        // TODO(2023.10): Make it possible to allow warnings
-        Err(ParseError::Warning(warning))
+        self.loader.warning(warning)
+    }
+
+    /// Returns true if errors should be collected and parsing continued where possible
+    pub fn is_recovering(&self) -> bool {
+        self.loader.recovering
+    }
+
+    /// The number of braces currently open
+    pub fn depth(&self) -> usize {
+        self.state.depth
+    }
+
+    /// Handle an error that occurred while parsing an entry of a struct or map
+    ///
+    /// Unless in recovering mode, the error is returned as is. Otherwise it is added to `errors`
+    /// and the rest of the entry is skipped, that is everything up to and including the next
+    /// newline, or up to the `end` brace, at `depth`. If parsing can't continue, all the
+    /// collected errors are returned.
+    pub fn recover(
+        &mut self,
+        errors: &mut Vec<ParseError>,
+        depth: usize,
+        end: Brace,
+        error: ParseError,
+    ) -> Result<()> {
+        if !self.is_recovering() {
+            return Err(error);
+        }
+
+        match error {
+            ParseError::Multiple(e) => errors.extend(e),
+            e => errors.push(e),
+        }
+
+        loop {
+            let cur_depth = self.state.depth;
+            if cur_depth < depth || self.state.eof {
+                return Err(ParseError::Multiple(std::mem::take(errors).into()));
+            } else if cur_depth == depth && self.state.last_was_newline {
+                // The error was at the end of the line, so the next entry starts here
+                return Ok(());
+            }
+            match self.peek_token() {
+                Ok((
+                    _,
+                    Token::Brace {
+                        start: false,
+                        brace,
+                    },
+                )) if cur_depth == depth && brace == end => {
+                    return Ok(());
+                }
+                Ok((_, Token::Newline(false))) if cur_depth == depth => {
+                    self.next_token()?;
+                    return Ok(());
+                }
+                // NOTE(2024.04): Errors are ignored here, the token is skipped either way
+                _ => {
+                    let _ = self.next_token();
+                }
+            }
+        }
+    }
+
+    /// Parse an item of a tuple or list using `f`, returns `None` if it failed in recovering mode
+    ///
+    /// Unless in recovering mode, the error is returned as is. Otherwise it is added to `errors`
+    /// and the rest of the item is skipped, up to the next `,` or closing brace at `depth`. If
+    /// parsing can't continue, all the collected errors are returned.
+    pub fn recover_item<R>(
+        &mut self,
+        errors: &mut Vec<ParseError>,
+        depth: usize,
+        f: impl FnOnce(&mut Self) -> Result<R>,
+    ) -> Result<Option<R>> {
+        match f(self) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if !self.is_recovering() => Err(e),
+            Err(e) => {
+                match e {
+                    ParseError::Multiple(e) => errors.extend(e),
+                    e => errors.push(e),
+                }
+                self.skip_item(errors, depth, false)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Skip the rest of an item after an error, up to the next `,` or closing brace at `depth`,
+    /// or the next newline if `newline` is set. Returns true if a newline was just skipped
+    pub(crate) fn skip_item(
+        &mut self,
+        errors: &mut Vec<ParseError>,
+        depth: usize,
+        newline: bool,
+    ) -> Result<bool> {
+        loop {
+            let cur_depth = self.state.depth;
+            if cur_depth < depth || self.state.eof {
+                return Err(ParseError::Multiple(std::mem::take(errors).into()));
+            } else if newline && cur_depth == depth && self.state.last_was_newline {
+                return Ok(true);
+            }
+            match self.peek_token() {
+                Ok((_, Token::Brace { start: false, .. } | Token::Delim(Delim::Comma)))
+                    if cur_depth == depth =>
+                {
+                    return Ok(false);
+                }
+                Ok((_, Token::Newline(false))) if newline && cur_depth == depth => {
+                    return Ok(false);
+                }
+                // NOTE(2024.04): Errors are ignored here, the token is skipped either way
+                _ => {
+                    let _ = self.next_token();
+                }
+            }
+        }
+    }
+
+    /// Returns all the errors collected by `recover`, if any
+    pub fn finish_recovery(&self, errors: Vec<ParseError>) -> Result<()> {
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ParseError::Multiple(errors.into()))
+        }
     }
 
     pub fn cur_span(&self) -> SourceSpan {
@@ -56,13 +181,7 @@ impl<'fs, 'f, FS: LogixVfs> LogixParser<'fs, 'f, FS> {
     }
 
     fn calc_span(&self, range: Range<usize>) -> SourceSpan {
-        SourceSpan::new(
-            self.file,
-            self.state.cur_pos + range.start,
-            self.state.cur_ln,
-            self.state.cur_col + range.start,
-            range.len(),
-        )
+        SourceSpan::new(self.file, self.state.cur_pos + range.start, range.len())
     }
 
     pub fn peek_token(&mut self) -> Result<(SourceSpan, Token<'f>)> {
@@ -85,39 +204,31 @@ impl<'fs, 'f, FS: LogixVfs> LogixParser<'fs, 'f, FS> {
             'ignore_token: loop {
                 let buf = &self.file.data()[self.state.cur_pos..];
                 let (span, token) = {
-                    let ParseRes {
-                        len,
-                        range,
-                        lines,
-                        token,
-                    } = parse_token(buf);
+                    let ParseRes { len, range, token } = parse_token(buf);
                     let span = self.calc_span(range);
 
                     self.state.cur_pos += len;
-                    if lines > 0 {
-                        debug_assert_ne!(len, 0);
-
-                        self.state.cur_ln += lines;
-                        if len == 1 {
-                            self.state.cur_col = 0;
-                        } else {
-                            self.state.cur_col = self.file.data()[..self.state.cur_pos]
-                                .lines()
-                                .next_back()
-                                .unwrap()
-                                .len();
-                        }
-                    } else {
-                        self.state.cur_col += len;
-                    }
                     (span, token)
                 };
 
                 return match token {
+                    Ok(token @ Token::Brace { start, .. }) => {
+                        if start {
+                            self.state.depth += 1;
+                            if self.state.depth > self.loader.max_depth {
+                                return Err(ParseError::NestingTooDeep {
+                                    span,
+                                    max_depth: self.loader.max_depth,
+                                });
+                            }
+                        } else {
+                            self.state.depth = self.state.depth.saturating_sub(1);
+                        }
+                        Ok((span, token))
+                    }
                     Ok(
                         token @ (Token::Ident(..)
                         | Token::Action(..)
-                        | Token::Brace { .. }
                         | Token::Delim(..)
                         | Token::Literal(..)),
                     ) => Ok((span, token)),
@@ -270,7 +381,10 @@ impl<'fs, 'f, FS: LogixVfs> LogixParser<'fs, 'f, FS> {
 mod tests {
     use logix_vfs::RelFs;
 
-    use crate::token::{Brace, Literal, StrLit, StrTag};
+    use crate::{
+        span::ResolvedSpan,
+        token::{Brace, Literal, StrLit, StrTag},
+    };
 
     use super::*;
 
@@ -279,8 +393,12 @@ mod tests {
     }
 
     impl<'f> Tester<'f> {
-        fn span(&self, pos: usize, ln: usize, start: usize, len: usize) -> SourceSpan {
-            SourceSpan::new(self.f, pos, ln, start, len)
+        fn span(&self, pos: usize, len: usize) -> SourceSpan {
+            SourceSpan::new(self.f, pos, len)
+        }
+
+        fn resolve(&self, span: SourceSpan) -> ResolvedSpan<'f> {
+            ResolvedSpan::new(span, self.f)
         }
     }
 
@@ -300,33 +418,30 @@ mod tests {
     #[test]
     fn basics() -> Result<()> {
         run_test("Hello { world: \"!!!\" }", |p, t| -> Result<()> {
-            assert_eq!(p.next_token()?, (t.span(0, 1, 0, 5), Token::Ident("Hello")));
+            assert_eq!(p.next_token()?, (t.span(0, 5), Token::Ident("Hello")));
             assert_eq!(
                 p.next_token()?,
                 (
-                    t.span(6, 1, 6, 1),
+                    t.span(6, 1),
                     Token::Brace {
                         start: true,
                         brace: Brace::Curly
                     }
                 )
             );
-            assert_eq!(p.next_token()?, (t.span(8, 1, 8, 5), Token::Ident("world")));
-            assert_eq!(
-                p.next_token()?,
-                (t.span(13, 1, 13, 1), Token::Delim(Delim::Colon))
-            );
+            assert_eq!(p.next_token()?, (t.span(8, 5), Token::Ident("world")));
+            assert_eq!(p.next_token()?, (t.span(13, 1), Token::Delim(Delim::Colon)));
             assert_eq!(
                 p.next_token()?,
                 (
-                    t.span(15, 1, 15, 5),
+                    t.span(15, 5),
                     Token::Literal(Literal::Str(StrLit::new(StrTag::Raw, "!!!")))
                 )
             );
             assert_eq!(
                 p.next_token()?,
                 (
-                    t.span(21, 1, 21, 1),
+                    t.span(21, 1),
                     Token::Brace {
                         start: false,
                         brace: Brace::Curly
@@ -334,17 +449,66 @@ mod tests {
                 )
             );
 
-            assert_eq!(
-                p.next_token()?,
-                (t.span(22, 1, 22, 0), Token::Newline(true))
-            );
+            assert_eq!(p.next_token()?, (t.span(22, 0), Token::Newline(true)));
 
-            assert_eq!(
-                p.next_token()?,
-                (t.span(22, 1, 22, 0), Token::Newline(true))
-            );
+            assert_eq!(p.next_token()?, (t.span(22, 0), Token::Newline(true)));
             // A second time to trigger additional code
             Ok(())
         })
     }
+
+    /// Returns the span of the value and its first line, column and last line
+    fn value_span<T: LogixType>(src: &str) -> (SourceSpan, SourceSpan, (usize, usize, usize)) {
+        run_test(src, |p, t| {
+            let span = T::logix_parse(p).unwrap().span;
+            let resolved = t.resolve(span);
+            assert_eq!(resolved.value(), src);
+            let pos = (resolved.line(), resolved.col(), resolved.last_line());
+            (span, t.span(0, src.len()), pos)
+        })
+    }
+
+    #[test]
+    fn multi_line_spans() {
+        let (span, expected, pos) = value_span::<crate::types::Map<u32>>("{\n  a: 1\n  b: 2\n}");
+        assert_eq!(span, expected);
+        assert_eq!(pos, (1, 0, 4));
+
+        let (span, expected, pos) = value_span::<Vec<u32>>("[1, 2,\n  3]");
+        assert_eq!(span, expected);
+        assert_eq!(pos, (1, 0, 2));
+
+        let (span, expected, pos) = value_span::<String>("#txt\"\n  hello\n  world\n\"#");
+        assert_eq!(span, expected);
+        assert_eq!(pos, (1, 0, 4));
+
+        let (span, expected, pos) = value_span::<u32>("10");
+        assert_eq!(span, expected);
+        assert_eq!(pos.2, 1);
+    }
+
+    #[test]
+    fn multi_line_offsets() {
+        run_test("x: #esc\"a\n  b\\q\n\"#", |p, t| {
+            p.next_token().unwrap();
+            p.next_token().unwrap();
+            let (span, _) = p.next_token().unwrap();
+            let resolved = t.resolve(span);
+            assert_eq!(
+                (resolved.line(), resolved.col(), resolved.last_line()),
+                (1, 3, 3)
+            );
+
+            // Offsets into the token continue on the following lines
+            assert_eq!(span.with_off(10, 2), t.span(13, 2));
+            assert_eq!(t.resolve(span.with_off(10, 2)).value(), "\\q");
+            assert_eq!(span.with_off(5, 4), t.span(8, 4));
+            assert_eq!(t.resolve(span.with_off(5, 4)).last_line(), 2);
+
+            let joined = t.span(0, 1).join(&span.with_off(10, 2));
+            assert_eq!(joined, t.span(0, 15));
+            assert_eq!(t.resolve(joined).value(), "x: #esc\"a\n  b\\q");
+            assert_eq!(span.with_off(10, 2).join(&t.span(0, 1)), joined);
+        })
+    }
 }
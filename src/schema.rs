@@ -0,0 +1,468 @@
+//! Render `LogixTypeDescriptor` trees as reference documentation or as a JSON Schema
+
+use std::{
+    fmt::{self, Write},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+    path::PathBuf,
+};
+
+use crate::{
+    type_trait::{LogixMemberDescriptor, LogixTypeDescriptor, LogixValueDescriptor},
+    types::{ExecutablePath, FullPath, NameOnlyPath, RelPath, ShortStr, ValidPath},
+    LogixType,
+};
+
+/// The types that get their own section, each listed once in the order they are first seen
+struct TypeTable {
+    types: Vec<(String, &'static LogixTypeDescriptor)>,
+}
+
+impl TypeTable {
+    fn new(root: &'static LogixTypeDescriptor) -> Self {
+        let mut ret = Self { types: Vec::new() };
+        ret.visit(root);
+        ret
+    }
+
+    /// Returns the unique key of a non-native type
+    fn key(&self, desc: &LogixTypeDescriptor) -> Option<&str> {
+        let desc = match desc.value {
+            LogixValueDescriptor::BackRef { target } => target.get(),
+            _ => desc,
+        };
+        self.types
+            .iter()
+            .find(|(_, d)| std::ptr::eq(*d, desc))
+            .map(|(key, _)| key.as_str())
+    }
+
+    fn visit(&mut self, desc: &'static LogixTypeDescriptor) {
+        let desc = desc.resolve();
+        if !matches!(
+            desc.value,
+            LogixValueDescriptor::Tuple { .. }
+                | LogixValueDescriptor::Struct { .. }
+                | LogixValueDescriptor::Enum { .. }
+        ) {
+            return self.visit_value(&desc.value);
+        }
+        // NOTE(2024.04): This is what stops recursive types from looping forever
+        if self.key(desc).is_some() {
+            return;
+        }
+
+        // Different types may share a name, for example generic types, so make the key unique
+        let mut key = desc.name.to_owned();
+        for n in 2.. {
+            if self.types.iter().all(|(k, _)| *k != key) {
+                break;
+            }
+            key = format!("{}-{n}", desc.name);
+        }
+        self.types.push((key, desc));
+
+        self.visit_value(&desc.value);
+    }
+
+    /// Visits the types referred to by a value, enum variants are not types of their own
+    fn visit_value(&mut self, value: &'static LogixValueDescriptor) {
+        match value {
+            LogixValueDescriptor::Native => {}
+            LogixValueDescriptor::Tuple { members } => {
+                for member in members {
+                    self.visit(member);
+                }
+            }
+            LogixValueDescriptor::Struct { members } => {
+                for member in members {
+                    self.visit(member.ty);
+                }
+            }
+            LogixValueDescriptor::Enum { variants } => {
+                for variant in variants {
+                    self.visit_value(&variant.value);
+                }
+            }
+            LogixValueDescriptor::List { item }
+            | LogixValueDescriptor::Array { item, .. }
+            | LogixValueDescriptor::Set { item }
+            | LogixValueDescriptor::Optional { inner: item } => self.visit(item),
+            LogixValueDescriptor::Map { key, value } => {
+                self.visit(key);
+                self.visit(value);
+            }
+            LogixValueDescriptor::BackRef { target } => self.visit(target.get()),
+        }
+    }
+}
+
+/// Render a markdown reference of `root` and every type it refers to
+///
+/// Each struct, tuple and enum gets a section of its own, native types are only named.
+pub fn to_markdown(root: &'static LogixTypeDescriptor) -> String {
+    let mut ret = String::new();
+    let table = TypeTable::new(root);
+    if table.types.is_empty() {
+        ret = format!("# `{}`\n\n", root.name);
+        if !root.doc.is_empty() {
+            ret += &format!("{}\n", root.doc);
+        }
+    } else {
+        write_markdown(&mut ret, &table).unwrap();
+    }
+    ret
+}
+
+fn write_markdown(out: &mut String, table: &TypeTable) -> fmt::Result {
+    for (i, (key, desc)) in table.types.iter().enumerate() {
+        if i != 0 {
+            out.push('\n');
+        }
+        writeln!(out, "{} `{key}`\n", if i == 0 { "#" } else { "##" })?;
+        if !desc.doc.is_empty() {
+            writeln!(out, "{}\n", desc.doc)?;
+        }
+        write_markdown_value(out, table, &desc.value, "###")?;
+    }
+    Ok(())
+}
+
+fn write_markdown_value(
+    out: &mut String,
+    table: &TypeTable,
+    value: &LogixValueDescriptor,
+    heading: &str,
+) -> fmt::Result {
+    match value {
+        LogixValueDescriptor::Native
+        | LogixValueDescriptor::List { .. }
+        | LogixValueDescriptor::Array { .. }
+        | LogixValueDescriptor::Set { .. }
+        | LogixValueDescriptor::Map { .. }
+        | LogixValueDescriptor::Optional { .. }
+        | LogixValueDescriptor::BackRef { .. } => Ok(()),
+        LogixValueDescriptor::Tuple { members } => {
+            writeln!(out, "A tuple with the members:\n")?;
+            for (i, member) in members.iter().enumerate() {
+                writeln!(out, "{}. {}", i + 1, markdown_type_ref(table, member))?;
+            }
+            writeln!(out)
+        }
+        LogixValueDescriptor::Struct { members } if members.is_empty() => {
+            writeln!(out, "A struct without members\n")
+        }
+        LogixValueDescriptor::Struct { members } => {
+            writeln!(out, "| Member | Type | Required | Description |")?;
+            writeln!(out, "|--------|------|----------|-------------|")?;
+            for LogixMemberDescriptor {
+                name,
+                doc,
+                optional,
+                ty,
+            } in members
+            {
+                writeln!(
+                    out,
+                    "| `{name}` | {} | {} | {} |",
+                    markdown_type_ref(table, ty),
+                    if *optional { "no" } else { "yes" },
+                    doc.replace('|', "\\|").replace('\n', "<br>"),
+                )?;
+            }
+            writeln!(out)
+        }
+        LogixValueDescriptor::Enum { variants } => {
+            writeln!(out, "One of the variants:\n")?;
+            for variant in variants {
+                writeln!(out, "{heading} `{}`\n", variant.name)?;
+                if !variant.doc.is_empty() {
+                    writeln!(out, "{}\n", variant.doc)?;
+                }
+                write_markdown_value(out, table, &variant.value, "####")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn markdown_type_ref(table: &TypeTable, desc: &LogixTypeDescriptor) -> String {
+    if let Some(key) = table.key(desc) {
+        return format!("[`{key}`](#{})", markdown_anchor(key));
+    }
+    match &desc.value {
+        LogixValueDescriptor::List { item } => {
+            format!("list of {}", markdown_type_ref(table, item))
+        }
+        LogixValueDescriptor::Array { len, item } => {
+            format!("array of {len} {}", markdown_type_ref(table, item))
+        }
+        LogixValueDescriptor::Set { item } => {
+            format!("set of {}", markdown_type_ref(table, item))
+        }
+        LogixValueDescriptor::Map { key: _, value } => {
+            format!("map of {}", markdown_type_ref(table, value))
+        }
+        LogixValueDescriptor::Optional { inner } => {
+            format!("optional {}", markdown_type_ref(table, inner))
+        }
+        LogixValueDescriptor::BackRef { target } => markdown_type_ref(table, target.get()),
+        LogixValueDescriptor::Native
+        | LogixValueDescriptor::Tuple { .. }
+        | LogixValueDescriptor::Struct { .. }
+        | LogixValueDescriptor::Enum { .. } => format!("`{}`", desc.name),
+    }
+}
+
+/// The anchor generated for a heading by GitHub flavoured markdown
+fn markdown_anchor(key: &str) -> String {
+    key.chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c.to_ascii_lowercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Render a JSON Schema (draft 2020-12) of `root` and every type it refers to
+///
+/// The schema follows the data model of the value rather than the logix syntax. Structs are
+/// objects, tuples are arrays and enums are either the name of a unit variant or an object
+/// with the variant name as the only key.
+pub fn to_json_schema(root: &'static LogixTypeDescriptor) -> String {
+    let table = TypeTable::new(root);
+
+    let mut schema = vec![(
+        "$schema",
+        Json::str("https://json-schema.org/draft/2020-12/schema"),
+    )];
+    match json_type_ref(&table, root) {
+        Json::Object(items) => schema.extend(items),
+        _ => unreachable!(),
+    }
+    if !table.types.is_empty() {
+        schema.push((
+            "$defs",
+            Json::Object(
+                table
+                    .types
+                    .iter()
+                    .map(|(key, desc)| {
+                        let mut def = json_value(&table, &desc.value);
+                        def.add_description(desc.doc);
+                        (key.as_str(), def)
+                    })
+                    .collect(),
+            ),
+        ));
+    }
+
+    let mut ret = String::new();
+    Json::Object(schema).write(&mut ret, 0).unwrap();
+    ret.push('\n');
+    ret
+}
+
+fn json_type_ref<'a>(table: &'a TypeTable, desc: &'a LogixTypeDescriptor) -> Json<'a> {
+    if let Some(key) = table.key(desc) {
+        return Json::Object(vec![("$ref", Json::String(format!("#/$defs/{key}")))]);
+    }
+    match desc.value {
+        LogixValueDescriptor::Optional { inner } => return json_type_ref(table, inner),
+        LogixValueDescriptor::BackRef { target } => return json_type_ref(table, target.get()),
+        _ => {}
+    }
+    if !matches!(desc.value, LogixValueDescriptor::Native) {
+        let mut ret = json_value(table, &desc.value);
+        ret.add_description(desc.doc);
+        return ret;
+    }
+
+    let ty = native_json_type(desc);
+
+    let mut ret = Json::Object(ty.map(|ty| ("type", Json::str(ty))).into_iter().collect());
+    ret.add_description(desc.doc);
+    ret
+}
+
+/// The JSON type of a native type, decided by comparing with the descriptors of the builtin types
+fn native_json_type(desc: &LogixTypeDescriptor) -> Option<&'static str> {
+    macro_rules! json_type {
+        ($($json:literal => $($ty:ty),+;)+) => {
+            $($(if desc == <$ty as LogixType>::descriptor() {
+                return Some($json);
+            })+)+
+        };
+    }
+
+    json_type! {
+        "integer" => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize;
+        "integer" => NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize;
+        "integer" => NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize;
+        "number" => f32, f64;
+        "boolean" => bool;
+        "string" => char, String, ShortStr, PathBuf;
+        "string" => FullPath, RelPath, NameOnlyPath, ValidPath, ExecutablePath;
+    }
+    None
+}
+
+fn json_value<'a>(table: &'a TypeTable, value: &'a LogixValueDescriptor) -> Json<'a> {
+    match value {
+        LogixValueDescriptor::Native => Json::Object(vec![]),
+        LogixValueDescriptor::Tuple { members } => Json::Object(vec![
+            ("type", Json::str("array")),
+            (
+                "prefixItems",
+                Json::Array(members.iter().map(|m| json_type_ref(table, m)).collect()),
+            ),
+            ("items", Json::Bool(false)),
+        ]),
+        LogixValueDescriptor::Struct { members } => Json::Object(vec![
+            ("type", Json::str("object")),
+            (
+                "properties",
+                Json::Object(
+                    members
+                        .iter()
+                        .map(|m| {
+                            let mut prop = json_type_ref(table, m.ty);
+                            prop.add_description(m.doc);
+                            (m.name, prop)
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "required",
+                Json::Array(
+                    members
+                        .iter()
+                        .filter(|m| !m.optional)
+                        .map(|m| Json::str(m.name))
+                        .collect(),
+                ),
+            ),
+            ("additionalProperties", Json::Bool(false)),
+        ]),
+        LogixValueDescriptor::List { item } => Json::Object(vec![
+            ("type", Json::str("array")),
+            ("items", json_type_ref(table, item)),
+        ]),
+        LogixValueDescriptor::Array { len, item } => Json::Object(vec![
+            ("type", Json::str("array")),
+            ("items", json_type_ref(table, item)),
+            ("minItems", Json::Int(*len)),
+            ("maxItems", Json::Int(*len)),
+        ]),
+        LogixValueDescriptor::Set { item } => Json::Object(vec![
+            ("type", Json::str("array")),
+            ("items", json_type_ref(table, item)),
+            ("uniqueItems", Json::Bool(true)),
+        ]),
+        LogixValueDescriptor::Map { key: _, value } => Json::Object(vec![
+            ("type", Json::str("object")),
+            ("additionalProperties", json_type_ref(table, value)),
+        ]),
+        // NOTE(2024.04): Whether the value can be left out is decided by `required` in the struct
+        LogixValueDescriptor::Optional { inner } => json_type_ref(table, inner),
+        LogixValueDescriptor::BackRef { target } => json_type_ref(table, target.get()),
+        LogixValueDescriptor::Enum { variants } => Json::Object(vec![(
+            "oneOf",
+            Json::Array(
+                variants
+                    .iter()
+                    .map(|variant| {
+                        let mut ret = match &variant.value {
+                            LogixValueDescriptor::Struct { members } if members.is_empty() => {
+                                Json::Object(vec![("const", Json::str(variant.name))])
+                            }
+                            value => Json::Object(vec![
+                                ("type", Json::str("object")),
+                                (
+                                    "properties",
+                                    Json::Object(vec![(variant.name, json_value(table, value))]),
+                                ),
+                                ("required", Json::Array(vec![Json::str(variant.name)])),
+                                ("additionalProperties", Json::Bool(false)),
+                            ]),
+                        };
+                        ret.add_description(variant.doc);
+                        ret
+                    })
+                    .collect(),
+            ),
+        )]),
+    }
+}
+
+enum Json<'a> {
+    Bool(bool),
+    Int(usize),
+    String(String),
+    Array(Vec<Json<'a>>),
+    Object(Vec<(&'a str, Json<'a>)>),
+}
+
+impl<'a> Json<'a> {
+    fn str(s: &str) -> Self {
+        Self::String(s.to_owned())
+    }
+
+    /// Set the description, replacing any less specific description from the type
+    fn add_description(&mut self, doc: &str) {
+        if let (Self::Object(items), false) = (self, doc.is_empty()) {
+            items.retain(|(key, _)| *key != "description");
+            items.push(("description", Json::str(doc)));
+        }
+    }
+
+    fn write(&self, out: &mut String, indent: usize) -> fmt::Result {
+        match self {
+            Self::Bool(v) => write!(out, "{v}"),
+            Self::Int(v) => write!(out, "{v}"),
+            Self::String(s) => write_json_str(out, s),
+            Self::Array(items) if items.is_empty() => out.write_str("[]"),
+            Self::Object(items) if items.is_empty() => out.write_str("{}"),
+            Self::Array(items) => {
+                out.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    write!(out, "{:1$}", "", (indent + 1) * 2)?;
+                    item.write(out, indent + 1)?;
+                    out.write_str(if i + 1 == items.len() { "\n" } else { ",\n" })?;
+                }
+                write!(out, "{:1$}]", "", indent * 2)
+            }
+            Self::Object(items) => {
+                out.write_str("{\n")?;
+                for (i, (key, item)) in items.iter().enumerate() {
+                    write!(out, "{:1$}", "", (indent + 1) * 2)?;
+                    write_json_str(out, key)?;
+                    out.write_str(": ")?;
+                    item.write(out, indent + 1)?;
+                    out.write_str(if i + 1 == items.len() { "\n" } else { ",\n" })?;
+                }
+                write!(out, "{:1$}}}", "", indent * 2)
+            }
+        }
+    }
+}
+
+fn write_json_str(out: &mut String, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if c.is_control() => write!(out, "\\u{:04x}", u32::from(c))?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
@@ -0,0 +1,554 @@
+use std::{fmt, marker::PhantomData, mem, path::Path};
+
+use ::serde::de::{
+    self, value::BorrowedStrDeserializer, DeserializeOwned, DeserializeSeed, Visitor,
+};
+use logix_vfs::LogixVfs;
+
+use crate::{
+    error::{ParseError, Result, Wanted},
+    parser::{LogixParser, ParseDelimited},
+    span::SourceSpan,
+    token::{Brace, Delim, Literal, Token},
+    type_trait::Value,
+    LogixLoader, LogixType,
+};
+
+impl de::Error for ParseError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        // NOTE(2024.04): The span is not known here, `locate` fills it in
+        Self::Custom {
+            span: SourceSpan::empty(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Gives an error from serde the span of the value it was reported for, unless it has one
+fn locate(error: ParseError, span: SourceSpan) -> ParseError {
+    match error {
+        ParseError::Custom {
+            span: empty,
+            message,
+        } if empty == SourceSpan::empty() => ParseError::Custom { span, message },
+        error => error,
+    }
+}
+
+/// The name of `T` without the module path and generic arguments
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+impl<FS: LogixVfs> LogixLoader<FS> {
+    /// Load the file located at `path` inside `FS` and deserialize it as `T` using serde
+    pub fn deserialize_file<T: DeserializeOwned>(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<T, ParseError> {
+        self.parse_file(path, short_type_name::<T>(), |p| {
+            Deserializer::new(p).value(PhantomData)
+        })
+    }
+}
+
+/// Reads one value from a [`LogixParser`] using serde
+pub struct Deserializer<'p, 'fs, 'f, FS: LogixVfs> {
+    p: &'p mut LogixParser<'fs, 'f, FS>,
+}
+
+impl<'p, 'fs, 'f, FS: LogixVfs> Deserializer<'p, 'fs, 'f, FS> {
+    pub fn new(p: &'p mut LogixParser<'fs, 'f, FS>) -> Self {
+        Self { p }
+    }
+
+    /// Deserialize the next value using `seed`, errors from serde are located at its first token
+    fn value<T: DeserializeSeed<'f>>(&mut self, seed: T) -> Result<T::Value> {
+        let (span, _) = self.p.peek_token()?;
+        seed.deserialize(&mut *self).map_err(|e| locate(e, span))
+    }
+
+    /// Parse the value as a `T` and pass it on to `visit`
+    fn native<T: LogixType, R>(&mut self, visit: impl FnOnce(T) -> Result<R>) -> Result<R> {
+        let Value { value, span } = T::logix_parse(self.p)?;
+        visit(value).map_err(|e| locate(e, span))
+    }
+
+    fn req_ident(&mut self, while_parsing: &'static str) -> Result<(SourceSpan, &'f str)> {
+        match self.p.next_token()? {
+            (span, Token::Ident(name)) => Ok((span, name)),
+            (span, token) => Err(ParseError::UnexpectedToken {
+                span,
+                while_parsing,
+                wanted: Wanted::Ident,
+                got_token: token.token_type_name(),
+            }),
+        }
+    }
+
+    /// Read `(value)`, what comes before the parenthesis has already been read
+    fn newtype<T: DeserializeSeed<'f>>(
+        &mut self,
+        while_parsing: &'static str,
+        seed: T,
+    ) -> Result<T::Value> {
+        self.p.req_token(
+            while_parsing,
+            Token::Brace {
+                start: true,
+                brace: Brace::Paren,
+            },
+        )?;
+        let value = self.value(seed)?;
+        self.p.req_token(
+            while_parsing,
+            Token::Brace {
+                start: false,
+                brace: Brace::Paren,
+            },
+        )?;
+        Ok(value)
+    }
+
+    /// Read `(a, b)`, what comes before the parenthesis has already been read
+    fn tuple<V: Visitor<'f>>(
+        &mut self,
+        while_parsing: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        let span = self.p.req_token(
+            while_parsing,
+            Token::Brace {
+                start: true,
+                brace: Brace::Paren,
+            },
+        )?;
+        let value = visitor
+            .visit_seq(Items {
+                de: self,
+                while_parsing,
+                first: true,
+            })
+            .map_err(|e| locate(e, span))?;
+        self.p.req_token(
+            while_parsing,
+            Token::Brace {
+                start: false,
+                brace: Brace::Paren,
+            },
+        )?;
+        Ok(value)
+    }
+
+    /// Read `{ key: value }` with one entry per line, what comes before the brace has already
+    /// been read
+    fn entries<V: Visitor<'f>>(
+        &mut self,
+        while_parsing: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        let span = self.p.req_token(
+            while_parsing,
+            Token::Brace {
+                start: true,
+                brace: Brace::Curly,
+            },
+        )?;
+        self.p.req_token(while_parsing, Token::Newline(false))?;
+
+        let mut entries = Entries {
+            de: self,
+            while_parsing,
+            span,
+            done: false,
+        };
+        let value = visitor
+            .visit_map(&mut entries)
+            .map_err(|e| locate(e, entries.span))?;
+        if !entries.done {
+            self.p.req_token(
+                while_parsing,
+                Token::Brace {
+                    start: false,
+                    brace: Brace::Curly,
+                },
+            )?;
+        }
+        Ok(value)
+    }
+}
+
+macro_rules! deserialize_native {
+    ($($method:ident($type:ty) => $visit:ident,)+) => {$(
+        fn $method<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+            self.native::<$type, _>(|value| visitor.$visit(value))
+        }
+    )+};
+}
+
+impl<'a, 'p, 'fs, 'f, FS: LogixVfs> de::Deserializer<'f> for &'a mut Deserializer<'p, 'fs, 'f, FS> {
+    type Error = ParseError;
+
+    fn deserialize_any<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        match self.p.peek_token()? {
+            (_, Token::Ident("true" | "false")) => self.deserialize_bool(visitor),
+            (span, Token::Ident(name)) => {
+                self.p.next_token()?;
+                match self.p.peek_token()?.1 {
+                    Token::Brace {
+                        start: true,
+                        brace: Brace::Curly,
+                    } => self.entries("value", visitor),
+                    Token::Brace {
+                        start: true,
+                        brace: Brace::Paren,
+                    } => self.tuple("value", visitor),
+                    _ => visitor.visit_borrowed_str(name),
+                }
+                .map_err(|e| locate(e, span))
+            }
+            (_, Token::Literal(Literal::Str(_)) | Token::Action(_)) => {
+                self.deserialize_string(visitor)
+            }
+            (_, Token::Literal(Literal::Num(_))) => {
+                if let Some(value) = self.p.forked(|p| Ok(u64::logix_parse(p).ok()))? {
+                    visitor
+                        .visit_u64(value.value)
+                        .map_err(|e| locate(e, value.span))
+                } else if let Some(value) = self.p.forked(|p| Ok(i64::logix_parse(p).ok()))? {
+                    visitor
+                        .visit_i64(value.value)
+                        .map_err(|e| locate(e, value.span))
+                } else {
+                    self.deserialize_f64(visitor)
+                }
+            }
+            (
+                _,
+                Token::Brace {
+                    start: true,
+                    brace: Brace::Square,
+                },
+            ) => self.deserialize_seq(visitor),
+            (
+                _,
+                Token::Brace {
+                    start: true,
+                    brace: Brace::Curly,
+                },
+            ) => self.deserialize_map(visitor),
+            (
+                _,
+                Token::Brace {
+                    start: true,
+                    brace: Brace::Paren,
+                },
+            ) => self.tuple("tuple", visitor),
+            (span, token) => Err(ParseError::UnexpectedToken {
+                span,
+                while_parsing: "value",
+                wanted: Wanted::Item,
+                got_token: token.token_type_name(),
+            }),
+        }
+    }
+
+    deserialize_native! {
+        deserialize_bool(bool) => visit_bool,
+        deserialize_i8(i8) => visit_i8,
+        deserialize_i16(i16) => visit_i16,
+        deserialize_i32(i32) => visit_i32,
+        deserialize_i64(i64) => visit_i64,
+        deserialize_i128(i128) => visit_i128,
+        deserialize_u8(u8) => visit_u8,
+        deserialize_u16(u16) => visit_u16,
+        deserialize_u32(u32) => visit_u32,
+        deserialize_u64(u64) => visit_u64,
+        deserialize_u128(u128) => visit_u128,
+        deserialize_f32(f32) => visit_f32,
+        deserialize_f64(f64) => visit_f64,
+        deserialize_char(char) => visit_char,
+        deserialize_str(String) => visit_string,
+        deserialize_string(String) => visit_string,
+    }
+
+    /// Bytes are written as a list of numbers
+    fn deserialize_bytes<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    /// There is no literal for `None`, so a value that is present is always `Some`
+    fn deserialize_option<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        let span = self.p.req_token(
+            "unit",
+            Token::Brace {
+                start: true,
+                brace: Brace::Paren,
+            },
+        )?;
+        self.p.req_token(
+            "unit",
+            Token::Brace {
+                start: false,
+                brace: Brace::Paren,
+            },
+        )?;
+        visitor.visit_unit().map_err(|e| locate(e, span))
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'f>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        let span = self.p.req_token(name, Token::Ident(name))?;
+        visitor.visit_unit().map_err(|e| locate(e, span))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'f>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        let span = self.p.req_token(name, Token::Ident(name))?;
+        self.newtype(name, Newtype(visitor))
+            .map_err(|e| locate(e, span))
+    }
+
+    fn deserialize_seq<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        let span = self.p.req_token(
+            "list",
+            Token::Brace {
+                start: true,
+                brace: Brace::Square,
+            },
+        )?;
+        let mut items = List(ParseDelimited::new(self.p, "list"));
+        let value = visitor.visit_seq(&mut items).map_err(|e| locate(e, span))?;
+        items.0.req_end(Brace::Square)?;
+        self.p.req_token(
+            "list",
+            Token::Brace {
+                start: false,
+                brace: Brace::Square,
+            },
+        )?;
+        Ok(value)
+    }
+
+    /// Tuples are written as `(a, b)`, but arrays are also tuples to serde and use `[a, b]`
+    fn deserialize_tuple<V: Visitor<'f>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        match self.p.peek_token()?.1 {
+            Token::Brace {
+                start: true,
+                brace: Brace::Square,
+            } => self.deserialize_seq(visitor),
+            _ => self.tuple("tuple", visitor),
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'f>>(
+        self,
+        name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        let span = self.p.req_token(name, Token::Ident(name))?;
+        self.tuple(name, visitor).map_err(|e| locate(e, span))
+    }
+
+    fn deserialize_map<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        self.entries("map", visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'f>>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.p.req_token(name, Token::Ident(name))?;
+        self.entries(name, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'f>>(
+        self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let (span, variant) = self.req_ident(name)?;
+        visitor
+            .visit_enum(Variant {
+                de: self,
+                name,
+                variant,
+            })
+            .map_err(|e| locate(e, span))
+    }
+
+    fn deserialize_identifier<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        let (span, name) = self.req_ident("identifier")?;
+        visitor
+            .visit_borrowed_str(name)
+            .map_err(|e| locate(e, span))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_any(visitor)
+    }
+}
+
+/// Passes the deserializer on to `visit_newtype_struct`
+struct Newtype<V>(V);
+
+impl<'f, V: Visitor<'f>> DeserializeSeed<'f> for Newtype<V> {
+    type Value = V::Value;
+
+    fn deserialize<D: de::Deserializer<'f>>(self, de: D) -> Result<V::Value, D::Error> {
+        self.0.visit_newtype_struct(de)
+    }
+}
+
+/// The items of a list
+struct List<'a, 'fs, 'f, FS: LogixVfs>(ParseDelimited<'a, 'fs, 'f, FS, ()>);
+
+impl<'a, 'fs, 'f, FS: LogixVfs> de::SeqAccess<'f> for List<'a, 'fs, 'f, FS> {
+    type Error = ParseError;
+
+    fn next_element_seed<T: DeserializeSeed<'f>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.0.next_with(|p| Deserializer::new(p).value(seed))
+    }
+}
+
+/// The comma separated items of a tuple
+struct Items<'a, 'p, 'fs, 'f, FS: LogixVfs> {
+    de: &'a mut Deserializer<'p, 'fs, 'f, FS>,
+    while_parsing: &'static str,
+    first: bool,
+}
+
+impl<'a, 'p, 'fs, 'f, FS: LogixVfs> de::SeqAccess<'f> for Items<'a, 'p, 'fs, 'f, FS> {
+    type Error = ParseError;
+
+    fn next_element_seed<T: DeserializeSeed<'f>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if let (
+            _,
+            Token::Brace {
+                start: false,
+                brace: Brace::Paren,
+            },
+        ) = self.de.p.peek_token()?
+        {
+            return Ok(None);
+        }
+        if !mem::take(&mut self.first) {
+            self.de
+                .p
+                .req_token(self.while_parsing, Token::Delim(Delim::Comma))?;
+        }
+        self.de.value(seed).map(Some)
+    }
+}
+
+/// The entries of a struct or map, `span` is the last key or the closing brace
+struct Entries<'a, 'p, 'fs, 'f, FS: LogixVfs> {
+    de: &'a mut Deserializer<'p, 'fs, 'f, FS>,
+    while_parsing: &'static str,
+    span: SourceSpan,
+    done: bool,
+}
+
+impl<'a, 'p, 'fs, 'f, FS: LogixVfs> de::MapAccess<'f> for Entries<'a, 'p, 'fs, 'f, FS> {
+    type Error = ParseError;
+
+    fn next_key_seed<K: DeserializeSeed<'f>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.de.p.next_token()? {
+            (span, Token::Ident(key)) => {
+                self.span = span;
+                seed.deserialize(BorrowedStrDeserializer::<ParseError>::new(key))
+                    .map(Some)
+                    .map_err(|e| locate(e, span))
+            }
+            (
+                span,
+                Token::Brace {
+                    start: false,
+                    brace: Brace::Curly,
+                },
+            ) => {
+                self.span = span;
+                self.done = true;
+                Ok(None)
+            }
+            (span, token) => Err(ParseError::UnexpectedToken {
+                span,
+                while_parsing: self.while_parsing,
+                wanted: Wanted::Ident,
+                got_token: token.token_type_name(),
+            }),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'f>>(&mut self, seed: V) -> Result<V::Value> {
+        self.de
+            .p
+            .req_token(self.while_parsing, Token::Delim(Delim::Colon))?;
+        let value = self.de.value(seed)?;
+        self.de
+            .p
+            .req_token(self.while_parsing, Token::Newline(false))?;
+        Ok(value)
+    }
+}
+
+/// An enum variant, the name has been read but not what follows
+struct Variant<'a, 'p, 'fs, 'f, FS: LogixVfs> {
+    de: &'a mut Deserializer<'p, 'fs, 'f, FS>,
+    name: &'static str,
+    variant: &'f str,
+}
+
+impl<'a, 'p, 'fs, 'f, FS: LogixVfs> de::EnumAccess<'f> for Variant<'a, 'p, 'fs, 'f, FS> {
+    type Error = ParseError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'f>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let value = seed.deserialize(BorrowedStrDeserializer::<ParseError>::new(self.variant))?;
+        Ok((value, self))
+    }
+}
+
+impl<'a, 'p, 'fs, 'f, FS: LogixVfs> de::VariantAccess<'f> for Variant<'a, 'p, 'fs, 'f, FS> {
+    type Error = ParseError;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'f>>(self, seed: T) -> Result<T::Value> {
+        self.de.newtype(self.name, seed)
+    }
+
+    fn tuple_variant<V: Visitor<'f>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.de.tuple(self.name, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'f>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.de.entries(self.name, visitor)
+    }
+}
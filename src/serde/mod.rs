@@ -0,0 +1,28 @@
+//! Read and write `.logix` files using types that implement the serde traits
+//!
+//! This is for types that can't derive `LogixType`, such as those from other crates. The syntax
+//! is the same as for the derive macro:
+//!
+//! * Structs are written as `Name { member: value }` with one member per line, tuple structs as
+//!   `Name(a, b)` and unit structs as `Name`
+//! * Enum variants are written like the structs above, using the name of the variant
+//! * Maps are written as `{ key: value }`, where each key is an identifier
+//! * Sequences are written as `[a, b]`, tuples as `(a, b)`
+//! * An `Option` is `None` when the member is left out
+//!
+//! Errors reported by the `Deserialize` implementations become [`ParseError::Custom`], located at
+//! the value that was being read.
+//!
+//! Writing with [`LogixWriter::serialize_file`] uses the same syntax. Strings get the format
+//! that keeps them intact, and `None` members are left out. A `None` anywhere else, or a map key
+//! that is not an identifier, fails with [`SerializeError`].
+//!
+//! [`ParseError::Custom`]: crate::error::ParseError::Custom
+//! [`LogixWriter::serialize_file`]: crate::LogixWriter::serialize_file
+//! [`SerializeError`]: crate::error::SerializeError
+
+mod de;
+mod ser;
+
+pub use de::Deserializer;
+pub use ser::Serializer;
@@ -0,0 +1,348 @@
+use std::fmt;
+
+use ::serde::ser::{self, Serialize};
+
+use crate::{error::SerializeError, writer::is_valid_ident, LogixWriter};
+
+type Result<T = (), E = SerializeError> = std::result::Result<T, E>;
+
+impl ser::Error for SerializeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl<'w> LogixWriter<'w> {
+    /// Write `value` as a complete logix file using serde and return it as a string
+    pub fn serialize_to_file_string<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+        let mut ret = String::new();
+        LogixWriter::new(&mut ret).serialize_file(value)?;
+        Ok(ret)
+    }
+
+    /// Write `value` as a complete logix file using serde, including the final newline
+    pub fn serialize_file<T: Serialize + ?Sized>(&mut self, value: &T) -> Result {
+        value.serialize(Serializer::new(self))?;
+        Ok(self.write_raw("\n")?)
+    }
+
+    /// Write `value` with a writer from `nested` and return the text
+    fn render<T: Serialize + ?Sized>(&self, value: &T, extra_indent: usize) -> Result<String> {
+        let mut ret = String::new();
+        value.serialize(Serializer::new(&mut self.nested(&mut ret, extra_indent)))?;
+        Ok(ret)
+    }
+}
+
+/// Writes one value to a [`LogixWriter`] using serde
+pub struct Serializer<'a, 'w> {
+    w: &'a mut LogixWriter<'w>,
+}
+
+impl<'a, 'w> Serializer<'a, 'w> {
+    pub fn new(w: &'a mut LogixWriter<'w>) -> Self {
+        Self { w }
+    }
+
+    fn tuple(self, name: Option<&str>) -> Result<Tuple<'a, 'w>> {
+        if let Some(name) = name {
+            self.w.write_ident(name)?;
+        }
+        self.w.write_raw("(")?;
+        Ok(Tuple {
+            w: self.w,
+            first: true,
+        })
+    }
+
+    fn entries(self, name: Option<&str>) -> Result<Entries<'a, 'w>> {
+        if let Some(name) = name {
+            self.w.write_ident(name)?;
+            self.w.write_raw(" ")?;
+        }
+        self.w.open_map()?;
+        Ok(Entries {
+            w: self.w,
+            key: None,
+        })
+    }
+}
+
+macro_rules! serialize_native {
+    ($($method:ident: $type:ty),+) => {$(
+        fn $method(self, v: $type) -> Result {
+            Ok(self.w.write_value(&v)?)
+        }
+    )+};
+}
+
+impl<'a, 'w> ser::Serializer for Serializer<'a, 'w> {
+    type Ok = ();
+    type Error = SerializeError;
+    type SerializeSeq = Seq<'a, 'w>;
+    type SerializeTuple = Tuple<'a, 'w>;
+    type SerializeTupleStruct = Tuple<'a, 'w>;
+    type SerializeTupleVariant = Tuple<'a, 'w>;
+    type SerializeMap = Entries<'a, 'w>;
+    type SerializeStruct = Entries<'a, 'w>;
+    type SerializeStructVariant = Entries<'a, 'w>;
+
+    serialize_native!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char
+    );
+
+    fn serialize_str(self, v: &str) -> Result {
+        Ok(self.w.write_str(v)?)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result {
+        Ok(self.w.write_list(v)?)
+    }
+
+    fn serialize_none(self) -> Result {
+        // NOTE(2024.04): There is no literal for `None`, the member is left out by `Entries`
+        Err(SerializeError::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result {
+        Ok(self.w.write_raw("()")?)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result {
+        Ok(self.w.write_ident(name)?)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result {
+        Ok(self.w.write_ident(variant)?)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result {
+        let mut tuple = self.tuple(Some(name))?;
+        ser::SerializeTuple::serialize_element(&mut tuple, value)?;
+        ser::SerializeTuple::end(tuple)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result {
+        self.serialize_newtype_struct(variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Seq<'a, 'w>> {
+        Ok(Seq {
+            w: self.w,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Tuple<'a, 'w>> {
+        self.tuple(None)
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> Result<Tuple<'a, 'w>> {
+        self.tuple(Some(name))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Tuple<'a, 'w>> {
+        self.tuple(Some(variant))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Entries<'a, 'w>> {
+        self.entries(None)
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Entries<'a, 'w>> {
+        self.entries(Some(name))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Entries<'a, 'w>> {
+        self.entries(Some(variant))
+    }
+}
+
+/// Writes a list, the items are collected first to decide if it fits on one line
+pub struct Seq<'a, 'w> {
+    w: &'a mut LogixWriter<'w>,
+    items: Vec<String>,
+}
+
+impl ser::SerializeSeq for Seq<'_, '_> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result {
+        self.items.push(self.w.render(value, 1)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result {
+        Ok(self.w.write_rendered_list(self.items)?)
+    }
+}
+
+/// Writes the items of a tuple, tuple struct or tuple variant
+pub struct Tuple<'a, 'w> {
+    w: &'a mut LogixWriter<'w>,
+    first: bool,
+}
+
+impl ser::SerializeTuple for Tuple<'_, '_> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result {
+        if !std::mem::take(&mut self.first) {
+            self.w.write_raw(", ")?;
+        }
+        value.serialize(Serializer::new(self.w))
+    }
+
+    fn end(self) -> Result {
+        Ok(self.w.write_raw(")")?)
+    }
+}
+
+impl ser::SerializeTupleStruct for Tuple<'_, '_> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result {
+        ser::SerializeTuple::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for Tuple<'_, '_> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result {
+        ser::SerializeTuple::end(self)
+    }
+}
+
+/// Writes the members of a map, struct or struct variant, one per line
+pub struct Entries<'a, 'w> {
+    w: &'a mut LogixWriter<'w>,
+    key: Option<String>,
+}
+
+impl Entries<'_, '_> {
+    fn entry<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result {
+        if !is_valid_ident(key) {
+            return Err(SerializeError::InvalidIdent(key.into()));
+        }
+        let value = match self.w.render(value, 0) {
+            // NOTE(2024.04): A `None` member is written by leaving it out
+            Err(SerializeError::None) => return Ok(()),
+            res => res?,
+        };
+        self.w.write_key(key)?;
+        self.w.write_raw(&value)?;
+        Ok(self.w.write_raw("\n")?)
+    }
+
+    fn end(self) -> Result {
+        Ok(self.w.close_map()?)
+    }
+}
+
+impl ser::SerializeMap for Entries<'_, '_> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result {
+        let key = self.w.render(key, 0)?;
+        // NOTE(2024.04): Keys are identifiers, so a string key is written without the quotes
+        let key = match key.strip_prefix('"').and_then(|k| k.strip_suffix('"')) {
+            Some(inner) => inner.to_owned(),
+            None => key,
+        };
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result {
+        let key = self.key.take().expect("serialize_key is called first");
+        self.entry(&key, value)
+    }
+
+    fn end(self) -> Result {
+        Entries::end(self)
+    }
+}
+
+impl ser::SerializeStruct for Entries<'_, '_> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result {
+        self.entry(key, value)
+    }
+
+    fn end(self) -> Result {
+        Entries::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for Entries<'_, '_> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result {
+        self.entry(key, value)
+    }
+
+    fn end(self) -> Result {
+        Entries::end(self)
+    }
+}
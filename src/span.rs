@@ -1,73 +1,33 @@
-use std::{borrow::Cow, fmt, path::Path};
+use std::{borrow::Cow, fmt, ops::Range, path::Path};
 
 use bstr::ByteSlice;
 use logix_vfs::LogixVfs;
 
-use crate::{loader::CachedFile, LogixLoader};
+use crate::{
+    loader::{CachedFile, FileId},
+    LogixLoader,
+};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
-struct Range {
-    start: u16,
-    end: u16,
-}
-
-impl Range {
-    fn len(&self) -> usize {
-        usize::from(self.end - self.start)
-    }
-}
-
-impl fmt::Debug for Range {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let range: std::ops::Range<u16> = self.start..self.end;
-        fmt::Debug::fmt(&range, f)
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
-enum SpanRange {
-    SingleLine {
-        line: usize,
-        col: Range,
-    },
-    MultiLine {
-        start_line: usize,
-        start_col: u16,
-        last_line: usize,
-        last_col: u16,
-        end_pos: usize,
-    },
-}
-
-impl SpanRange {
-    fn get_range_for_line(&self, cur_line: usize) -> Option<std::ops::Range<usize>> {
-        match self {
-            Self::SingleLine { line, col } => {
-                (*line == cur_line).then(|| usize::from(col.start)..usize::from(col.end))
-            }
-            Self::MultiLine { .. } => todo!(),
-        }
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+/// A range of bytes in a loaded file
+///
+/// Only the id of the file and the byte positions are stored, so spans are cheap to copy. Use
+/// [`SourceFiles::resolve`](crate::error::SourceFiles::resolve) to get the line, column and text
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub struct SourceSpan {
-    file: CachedFile,
-    pos: usize,
-    range: SpanRange,
+    file: FileId,
+    start: usize,
+    end: usize,
 }
 
 impl SourceSpan {
     pub fn empty() -> Self {
         Self {
-            file: CachedFile::empty(),
-            pos: 0,
-            range: SpanRange::SingleLine {
-                line: 0,
-                col: Range { start: 0, end: 0 },
-            },
+            file: FileId::NONE,
+            start: 0,
+            end: 0,
         }
     }
+
     pub fn new_for_test(
         loader: &LogixLoader<impl LogixVfs>,
         path: impl AsRef<Path>,
@@ -76,111 +36,127 @@ impl SourceSpan {
         len: usize,
     ) -> Self {
         let file = loader.get_file(path).unwrap();
-        let mut pos = 0;
-        for (i, line_data) in file.data().lines_with_terminator().enumerate() {
-            if i + 1 == line {
-                pos += col;
-                break;
-            }
-            pos += line_data.len();
+        let pos = file.line_start(line) + col;
+        Self::new(&file, pos, len)
+    }
+
+    /// Create a span of `len` bytes starting at `pos`
+    pub(crate) fn new(file: &CachedFile, pos: usize, len: usize) -> Self {
+        Self {
+            file: file.id(),
+            start: pos,
+            end: pos + len,
         }
+    }
 
-        Self::new(&file, pos, line, col, len)
+    /// The file this span is in
+    pub fn file_id(&self) -> FileId {
+        self.file
+    }
+
+    /// The byte range of this span in the file
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the `len` bytes starting `off` bytes into this span
+    pub fn with_off(&self, off: usize, len: usize) -> Self {
+        Self {
+            file: self.file,
+            start: self.start + off,
+            end: self.start + off + len,
+        }
     }
 
-    pub(crate) fn new(file: &CachedFile, pos: usize, line: usize, col: usize, len: usize) -> Self {
-        let scol = u16::try_from(col).unwrap();
-        let ecol = u16::try_from(col + len).unwrap();
+    /// Returns a span covering both spans and everything in between
+    pub(crate) fn join(&self, other: &Self) -> SourceSpan {
+        assert_eq!(self.file, other.file);
+
         Self {
-            file: file.clone(),
-            pos,
-            range: SpanRange::SingleLine {
-                line,
-                col: Range {
-                    start: scol,
-                    end: ecol,
-                },
-            },
+            file: self.file,
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
         }
     }
 
-    pub fn path(&self) -> &Path {
+    /// Returns a span of the character at `pos`
+    pub(crate) fn from_pos(file: &CachedFile, pos: usize) -> SourceSpan {
+        let len = usize::from(pos < file.data().len());
+        Self::new(file, pos, len)
+    }
+}
+
+/// A span together with the file it is in, see
+/// [`SourceFiles::resolve`](crate::error::SourceFiles::resolve)
+#[derive(Debug, Clone, Copy)]
+pub struct ResolvedSpan<'a> {
+    span: SourceSpan,
+    file: &'a CachedFile,
+}
+
+impl<'a> ResolvedSpan<'a> {
+    pub(crate) fn new(span: SourceSpan, file: &'a CachedFile) -> Self {
+        Self { span, file }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
+    pub fn path(&self) -> &'a Path {
         self.file.path()
     }
 
     /// The first line in this span
     pub fn line(&self) -> usize {
-        match self.range {
-            SpanRange::SingleLine { line, col: _ } => line,
-            SpanRange::MultiLine { start_line, .. } => start_line,
-        }
+        self.file.line_of(self.span.start)
     }
 
-    /// The last line in this span
+    /// The last line in this span, a span that only ends with the newline is still on one line
     pub fn last_line(&self) -> usize {
-        match self.range {
-            SpanRange::SingleLine { line, col: _ } => line,
-            SpanRange::MultiLine { last_line, .. } => last_line,
-        }
+        let SourceSpan { start, end, .. } = self.span;
+        self.file.line_of(start.max(end.saturating_sub(1)))
     }
-    /// The start column of the start line
+
+    /// The start column of the start line, counted in bytes
     pub fn col(&self) -> usize {
-        match self.range {
-            SpanRange::SingleLine {
-                line: _,
-                col: Range { start, end: _ },
-            } => start.into(),
-            SpanRange::MultiLine { start_col, .. } => start_col.into(),
-        }
+        self.span.start - self.file.line_start(self.line())
     }
 
     /// The value of this entire span
-    pub fn value(&self) -> Cow<str> {
-        let end_pos = match &self.range {
-            SpanRange::SingleLine { line: _, col } => self.pos + col.len(),
-            &SpanRange::MultiLine { end_pos, .. } => end_pos,
-        };
-        String::from_utf8_lossy(&self.file.data()[self.pos..end_pos])
+    pub fn value(&self) -> Cow<'a, str> {
+        String::from_utf8_lossy(&self.file.data()[self.span.range()])
     }
 
+    /// The lines of this span and `context` lines around it, together with the columns covered
+    /// by the span on each line
     pub fn lines(
         &self,
         context: usize,
-    ) -> impl Iterator<Item = (usize, Option<std::ops::Range<usize>>, Cow<str>)> {
-        self.file
-            .lines()
-            .enumerate()
-            .skip(self.line().saturating_sub(context + 1))
-            .map_while(move |(i, line)| {
-                let ln = i + 1;
-                if ln <= self.line() + context {
-                    Some((ln, self.range.get_range_for_line(ln), line.to_str_lossy()))
+    ) -> impl Iterator<Item = (usize, Option<Range<usize>>, Cow<'a, str>)> {
+        let file = self.file;
+        let (first, last) = (self.line(), self.last_line());
+        let end = (last + context).min(file.line_count());
+        let SourceSpan {
+            start,
+            end: span_end,
+            ..
+        } = self.span;
+
+        (first.saturating_sub(context).max(1)..=end).map(move |ln| {
+            let line = file.line(ln);
+            let line_start = file.line_start(ln);
+            let range = (first..=last).contains(&ln).then(|| {
+                let start = if ln == first { start - line_start } else { 0 };
+                let end = if ln == last {
+                    span_end - line_start
                 } else {
-                    None
-                }
-            })
-    }
-
-    pub fn with_off(&self, off: usize, len: usize) -> Self {
-        let off = u16::try_from(off).unwrap();
-        let len = u16::try_from(len).unwrap();
-        Self {
-            file: self.file.clone(),
-            pos: self.pos + usize::from(off),
-            range: match self.range {
-                SpanRange::SingleLine {
-                    line,
-                    col: Range { start, end: _ },
-                } => SpanRange::SingleLine {
-                    line,
-                    col: Range {
-                        start: start + off,
-                        end: start + off + len,
-                    },
-                },
-                SpanRange::MultiLine { .. } => todo!(),
-            },
-        }
+                    line.len()
+                };
+                start..end
+            });
+            (ln, range, line.to_str_lossy())
+        })
     }
 
     pub fn calc_ln_width(&self, extra: usize) -> usize {
@@ -192,97 +168,14 @@ impl SourceSpan {
             _ => 10,
         }
     }
-
-    pub(crate) fn join(&self, other: &Self) -> SourceSpan {
-        assert_eq!(self.file, other.file);
-
-        let mut ret = self.clone();
-
-        match (&self.range, &other.range) {
-            (
-                SpanRange::SingleLine {
-                    line: sline,
-                    col: scol,
-                },
-                SpanRange::SingleLine {
-                    line: oline,
-                    col: ocol,
-                },
-            ) => match sline.cmp(oline) {
-                std::cmp::Ordering::Less => {
-                    ret.pos = self.pos;
-                    ret.range = SpanRange::MultiLine {
-                        start_line: *sline,
-                        start_col: scol.start,
-                        last_line: *oline,
-                        last_col: ocol.end,
-                        end_pos: other.pos + ocol.len(),
-                    };
-                }
-                std::cmp::Ordering::Equal => {
-                    ret.pos = self.pos.min(other.pos);
-                    ret.range = SpanRange::SingleLine {
-                        line: *sline,
-                        col: Range {
-                            start: ocol.start.min(scol.start),
-                            end: ocol.end.max(scol.end),
-                        },
-                    };
-                }
-                std::cmp::Ordering::Greater => {
-                    ret.pos = self.pos;
-                    ret.range = SpanRange::MultiLine {
-                        start_line: *oline,
-                        start_col: ocol.start,
-                        last_line: *sline,
-                        last_col: scol.end,
-                        end_pos: self.pos + scol.len(),
-                    };
-                }
-            },
-            (SpanRange::SingleLine { .. }, SpanRange::MultiLine { .. }) => todo!(),
-            (SpanRange::MultiLine { .. }, SpanRange::SingleLine { .. }) => todo!(),
-            (SpanRange::MultiLine { .. }, SpanRange::MultiLine { .. }) => todo!(),
-        }
-
-        ret
-    }
-
-    pub(crate) fn from_pos(file: &CachedFile, pos: usize) -> SourceSpan {
-        let mut cur = 0;
-        let mut ln = 1;
-        let mut col = Range { start: 0, end: 0 };
-
-        for line in file.data().lines_with_terminator() {
-            let range = cur..cur + line.len();
-
-            if range.contains(&pos) {
-                let start = u16::try_from(pos - range.start).unwrap();
-                col = Range {
-                    start,
-                    end: start + 1,
-                };
-                break;
-            }
-
-            cur = range.end;
-            ln += 1;
-        }
-
-        Self {
-            file: file.clone(),
-            pos,
-            range: SpanRange::SingleLine { line: ln, col },
-        }
-    }
 }
 
-impl fmt::Display for SourceSpan {
+impl fmt::Display for ResolvedSpan<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
             "{}:{}:{}",
-            self.file.path().display(),
+            self.path().display(),
             self.line(),
             self.col()
         )
@@ -293,35 +186,68 @@ impl fmt::Display for SourceSpan {
 mod tests {
     use super::*;
 
+    fn resolved(file: &CachedFile, pos: usize, len: usize) -> ResolvedSpan<'_> {
+        ResolvedSpan::new(SourceSpan::new(file, pos, len), file)
+    }
+
     #[test]
     fn coverage_hacks() {
+        let file = CachedFile::from_slice("test.logix", b"hello world");
+        assert_eq!(resolved(&file, 6, 5).value(), "world");
+        assert_eq!(resolved(&file, 6, 5).to_string(), "test.logix:1:6");
+        assert_eq!(SourceSpan::empty().range(), 0..0);
+    }
+
+    #[test]
+    fn positions() {
+        let file = CachedFile::from_slice("test.logix", b"ab\ncd\n\nef");
+        let span = resolved(&file, 4, 5);
+        assert_eq!((span.line(), span.col(), span.last_line()), (2, 1, 4));
+        assert_eq!(span.value(), "d\n\nef");
         assert_eq!(
-            SourceSpan {
-                file: CachedFile::from_slice("test.logix", b"hello world"),
-                pos: 6,
-                range: SpanRange::SingleLine {
-                    line: 1,
-                    col: Range { start: 6, end: 11 },
-                }
-            }
-            .value(),
-            "world"
+            span.lines(1).collect::<Vec<_>>(),
+            vec![
+                (1, None, "ab".into()),
+                (2, Some(1..2), "cd".into()),
+                (3, Some(0..0), "".into()),
+                (4, Some(0..2), "ef".into()),
+            ]
         );
-        assert_eq!(SourceSpan::empty().value(), "");
+
+        // Ending with the newline does not make it a multi-line span
+        let span = SourceSpan::new(&file, 3, 3);
+        let with_off = ResolvedSpan::new(span.with_off(2, 2), &file);
+        let span = ResolvedSpan::new(span, &file);
+        assert_eq!((span.line(), span.last_line()), (2, 2));
+        assert_eq!(with_off.line(), 2);
+        assert_eq!(with_off.last_line(), 3);
+        let from_pos = ResolvedSpan::new(SourceSpan::from_pos(&file, 9), &file);
+        assert_eq!(from_pos.value(), "");
+    }
+
+    #[test]
+    fn long_lines() {
+        let mut data = vec![b'a'; 100_000];
+        data.extend_from_slice(b"\nb");
+        let file = CachedFile::from_slice("test.logix", &data);
+        let span = resolved(&file, 70_000, 30_002);
+        assert_eq!((span.line(), span.col(), span.last_line()), (1, 70_000, 2));
     }
 
     #[test]
     fn line_width() {
-        assert_eq!(SourceSpan::empty().calc_ln_width(0), 3);
-        assert_eq!(SourceSpan::empty().calc_ln_width(10), 3);
-        assert_eq!(SourceSpan::empty().calc_ln_width(100), 3);
-        assert_eq!(SourceSpan::empty().calc_ln_width(1000), 4);
-        assert_eq!(SourceSpan::empty().calc_ln_width(10000), 5);
-        assert_eq!(SourceSpan::empty().calc_ln_width(100000), 6);
-        assert_eq!(SourceSpan::empty().calc_ln_width(1000000), 10);
-        assert_eq!(SourceSpan::empty().calc_ln_width(10000000), 10);
-        assert_eq!(SourceSpan::empty().calc_ln_width(100000000), 10);
-        assert_eq!(SourceSpan::empty().calc_ln_width(1000000000), 10);
-        assert_eq!(SourceSpan::empty().calc_ln_width(10000000000), 10);
+        let file = CachedFile::from_slice("test.logix", b"");
+        let span = resolved(&file, 0, 0);
+        assert_eq!(span.calc_ln_width(0), 3);
+        assert_eq!(span.calc_ln_width(10), 3);
+        assert_eq!(span.calc_ln_width(100), 3);
+        assert_eq!(span.calc_ln_width(1000), 4);
+        assert_eq!(span.calc_ln_width(10000), 5);
+        assert_eq!(span.calc_ln_width(100000), 6);
+        assert_eq!(span.calc_ln_width(1000000), 10);
+        assert_eq!(span.calc_ln_width(10000000), 10);
+        assert_eq!(span.calc_ln_width(100000000), 10);
+        assert_eq!(span.calc_ln_width(1000000000), 10);
+        assert_eq!(span.calc_ln_width(10000000000), 10);
     }
 }
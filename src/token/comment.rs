@@ -4,12 +4,19 @@ use bstr::ByteSlice;
 
 use super::{ParseRes, Token, TokenError};
 
-pub fn parse_comment(buf: &[u8], start: usize) -> Option<ParseRes> {
+fn comment_text(comment: &[u8]) -> Result<Token<'_>, TokenError> {
+    from_utf8(comment.trim())
+        .map(Token::Comment)
+        .map_err(|_| TokenError::LitStrNotUtf8)
+}
+
+pub fn parse_comment(buf: &[u8], start: usize) -> Option<ParseRes<'_>> {
     if let Some(cur) = buf[start..].strip_prefix(b"//") {
         let comment = cur.lines().next().unwrap();
-        Some(ParseRes::new(
+        Some(ParseRes::new_res(
             start..start + comment.len() + 2,
-            Token::Comment(from_utf8(comment.trim()).unwrap()),
+            0,
+            comment_text(comment),
         ))
     } else if buf[start..].starts_with(b"/*") {
         let mut end = start + 2;
@@ -22,13 +29,10 @@ pub fn parse_comment(buf: &[u8], start: usize) -> Option<ParseRes> {
                 Some(b"*/") => {
                     end += 2;
                     if level == 0 {
-                        return Some(ParseRes::new_lines(
-                            buf,
+                        return Some(ParseRes::new_res(
                             start..end,
                             0,
-                            Ok(Token::Comment(
-                                from_utf8(buf[start + 2..end - 2].trim()).unwrap(),
-                            )),
+                            comment_text(&buf[start + 2..end - 2]),
                         ));
                     } else {
                         level -= 1;
@@ -42,11 +46,12 @@ pub fn parse_comment(buf: &[u8], start: usize) -> Option<ParseRes> {
             }
         }
 
-        Some(ParseRes::new_res(
-            buf.len()..buf.len() + 1,
-            0,
-            Err(TokenError::MissingCommentTerminator),
-        ))
+        // NOTE(2024.04): The span points just past the end, but never skip beyond the buffer
+        Some(ParseRes {
+            len: buf.len(),
+            range: buf.len()..buf.len() + 1,
+            token: Err(TokenError::MissingCommentTerminator),
+        })
     } else {
         None
     }
@@ -0,0 +1,139 @@
+use std::ops::Range;
+
+use bstr::ByteSlice;
+
+use super::{parse_token, ParseRes, Token, TokenError};
+
+/// The location of a [`Lexeme`] in the buffer given to the [`Lexer`]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct LexSpan {
+    /// Byte offset of the first byte
+    pub start: usize,
+    /// Byte offset just past the last byte
+    pub end: usize,
+    /// The 1-based line of the first byte
+    pub line: usize,
+    /// The column of the first byte, counted in bytes from 0 like `SourceSpan::col`
+    pub col: usize,
+}
+
+impl LexSpan {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum LexemeKind<'a> {
+    /// A token, including comments and newlines, the last lexeme is always `Token::Newline(true)`
+    Token(Token<'a>),
+    /// A run of spaces and tabs
+    Whitespace,
+    /// Text that could not be lexed, `at` is where the problem was found
+    Error { error: TokenError, at: LexSpan },
+}
+
+/// A piece of the input returned by the [`Lexer`]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Lexeme<'a> {
+    pub span: LexSpan,
+    pub kind: LexemeKind<'a>,
+}
+
+/// Splits a buffer into lexemes, unlike `LogixParser::next_token` nothing is skipped
+///
+/// Together the lexemes cover the whole buffer, so it can be used for syntax highlighting and
+/// other tooling that needs to see the comments and whitespace. Errors are returned as
+/// [`LexemeKind::Error`] and lexing continues after the offending text.
+pub struct Lexer<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    done: bool,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self {
+            buf,
+            pos: 0,
+            line: 1,
+            line_start: 0,
+            done: false,
+        }
+    }
+
+    fn span(&self, range: Range<usize>) -> LexSpan {
+        let start = self.pos + range.start;
+        let skipped = &self.buf[self.pos..start];
+        let (line, line_start) = match skipped.rfind_byte(b'\n') {
+            Some(off) => (
+                self.line + skipped.find_iter(b"\n").count(),
+                self.pos + off + 1,
+            ),
+            None => (self.line, self.line_start),
+        };
+        LexSpan {
+            start,
+            end: self.pos + range.end,
+            line,
+            col: start - line_start,
+        }
+    }
+
+    fn take(&mut self, len: usize, kind: LexemeKind<'a>) -> Lexeme<'a> {
+        let span = self.span(0..len);
+        let taken = &self.buf[self.pos..self.pos + len];
+        if let Some(off) = taken.rfind_byte(b'\n') {
+            self.line += taken.find_iter(b"\n").count();
+            self.line_start = self.pos + off + 1;
+        }
+        self.pos += len;
+        Lexeme { span, kind }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Lexeme<'a>;
+
+    fn next(&mut self) -> Option<Lexeme<'a>> {
+        if self.done {
+            return None;
+        }
+
+        let buf = &self.buf[self.pos..];
+        Some(match buf.first() {
+            None => {
+                self.done = true;
+                self.take(0, LexemeKind::Token(Token::Newline(true)))
+            }
+            Some(b' ' | b'\t') => {
+                let len = buf.find_not_byteset(b" \t").unwrap_or(buf.len());
+                self.take(len, LexemeKind::Whitespace)
+            }
+            Some(b'\n') => self.take(1, LexemeKind::Token(Token::Newline(false))),
+            Some(_) => {
+                let ParseRes {
+                    len, range, token, ..
+                } = parse_token(buf);
+                match token {
+                    Ok(token) => self.take(len, LexemeKind::Token(token)),
+                    Err(error) => {
+                        let range = range.start.min(buf.len())..range.end.min(buf.len());
+                        let at = self.span(range);
+                        self.take(len.clamp(1, buf.len()), LexemeKind::Error { error, at })
+                    }
+                }
+            }
+        })
+    }
+}
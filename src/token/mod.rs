@@ -1,13 +1,15 @@
-//! The `Token` type and other relevant types returned by `LogixParser::next_token`
+//! The `Token` type and other relevant types returned by `LogixParser::next_token` and the `Lexer`
 
 use std::{borrow::Cow, fmt};
 
 use crate::error::TokenError;
 
 mod comment;
+mod lexer;
 mod parse;
 mod string;
 pub use self::{
+    lexer::{LexSpan, Lexeme, LexemeKind, Lexer},
     parse::{parse_token, ParseRes},
     string::StrLit,
 };
@@ -61,6 +63,10 @@ impl StrTagSuffix {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 impl StrTagSuffix {
@@ -71,7 +77,7 @@ impl StrTagSuffix {
         } else {
             let mut s = String::with_capacity(num_hashes + 1);
             s.push('"');
-            s.extend(std::iter::repeat('#').take(num_hashes));
+            s.extend(std::iter::repeat_n('#', num_hashes));
             Cow::Owned(s)
         })
     }
@@ -19,8 +19,6 @@ pub struct ParseRes<'a> {
     pub len: usize,
     /// The range that contains the token
     pub range: Range<usize>,
-    /// Number of lines skipped, normally 0, but set for multi-line comments and strings
-    pub lines: usize,
     /// The current token
     pub token: Result<Token<'a>, TokenError>,
 }
@@ -38,22 +36,6 @@ impl<'a> ParseRes<'a> {
         Self {
             len: range.end + extra,
             range,
-            lines: 0,
-            token,
-        }
-    }
-
-    pub(super) fn new_lines(
-        buf: &[u8],
-        range: Range<usize>,
-        extra: usize,
-        token: Result<Token<'a>, TokenError>,
-    ) -> Self {
-        let lines = buf[range.start..range.end + extra].find_iter(b"\n").count();
-        Self {
-            len: range.end + extra,
-            range,
-            lines,
             token,
         }
     }
@@ -77,17 +59,59 @@ impl<'a> ParseRes<'a> {
     }
 }
 
-pub fn parse_token(buf: &[u8]) -> ParseRes {
+/// Returns the length of the number at the start of `buf`
+///
+/// A number is digits, `_` and `.` with an optional `-` in front, and `e` or `E` followed by an
+/// optional sign for the exponent. After `0x`, `0o` or `0b` the hex digits are taken as well, the
+/// type being parsed is responsible for validating the content.
+fn num_len(buf: &[u8]) -> usize {
+    let mut pos = usize::from(buf.first() == Some(&b'-'));
+    let radix = matches!(buf.get(pos..pos + 2), Some(b"0x" | b"0o" | b"0b"));
+    if radix {
+        pos += 2;
+    }
+    loop {
+        match buf.get(pos) {
+            Some(b'0'..=b'9' | b'_') => pos += 1,
+            Some(b'a'..=b'f' | b'A'..=b'F') if radix => pos += 1,
+            Some(b'.') if !radix => pos += 1,
+            Some(b'e' | b'E') => {
+                pos += 1;
+                if matches!(buf.get(pos), Some(b'+' | b'-')) {
+                    pos += 1;
+                }
+            }
+            _ => return pos,
+        }
+    }
+}
+
+pub fn parse_token(buf: &[u8]) -> ParseRes<'_> {
     let start = buf.find_not_byteset(b" \t").unwrap_or(0);
 
     match buf.get(start) {
         Some(b'a'..=b'z' | b'A'..=b'Z' | b'_') => {
             ParseRes::take_byteset(buf, start, start, IDENT1, Token::Ident)
         }
+        // NOTE(2024.04): `-inf` is an identifier just like `inf` and `nan`, any other letter
+        // after `-` is an error so keys and other identifiers can't start with `-`
+        Some(b'-') if buf.get(start + 1).is_some_and(u8::is_ascii_alphabetic) => {
+            let end = start + 4;
+            if buf.get(start..end) == Some(b"-inf")
+                && !buf
+                    .get(end)
+                    .is_some_and(|c| IDENT1.0.as_bytes().contains(c))
+            {
+                ParseRes::new(start..end, Token::Ident("-inf"))
+            } else {
+                let c = char::from(buf[start + 1]);
+                ParseRes::new_res(start + 1..start + 2, 0, Err(TokenError::UnexpectedChar(c)))
+            }
+        }
         Some(b'-' | b'0'..=b'9') => {
-            ParseRes::take_byteset(buf, start, start, ByteSet("0123456789-._"), |s| {
-                Token::Literal(Literal::Num(s))
-            })
+            let end = start + num_len(&buf[start..]);
+            let num = from_utf8(&buf[start..end]).unwrap();
+            ParseRes::new(start..end, Token::Literal(Literal::Num(num)))
         }
         Some(b'/') => {
             if let Some(ret) = super::comment::parse_comment(buf, start) {
@@ -110,8 +134,7 @@ pub fn parse_token(buf: &[u8]) -> ParseRes {
             let off = buf[start..]
                 .find_not_byteset("\r\n \t")
                 .unwrap_or(buf.len() - start);
-            ParseRes::new_lines(
-                buf,
+            ParseRes::new_res(
                 start..start,
                 off,
                 Ok(Token::Newline(buf.len() == start + off)),
@@ -127,12 +150,18 @@ pub fn parse_token(buf: &[u8]) -> ParseRes {
         }
         Some(b'@') => {
             if matches!(buf.get(start + 1), Some(b'a'..=b'z' | b'A'..=b'Z' | b'_')) {
-                ParseRes::take_byteset(buf, start, start + 1, IDENT1, |a| match a {
-                    "include" => Token::Action(Action::Include),
-                    _ => todo!(),
-                })
+                let res = ParseRes::take_byteset(buf, start, start + 1, IDENT1, Token::Ident);
+                match res.token {
+                    Ok(Token::Ident("include")) => {
+                        ParseRes::new(res.range, Token::Action(Action::Include))
+                    }
+                    Ok(Token::Ident(name)) => {
+                        ParseRes::new_res(res.range, 0, Err(TokenError::UnknownAction(name.into())))
+                    }
+                    _ => unreachable!(),
+                }
             } else {
-                todo!()
+                ParseRes::new_res(start..start + 1, 0, Err(TokenError::UnexpectedChar('@')))
             }
         }
         _ => {
@@ -156,7 +185,6 @@ mod tests {
             ParseRes {
                 len: 1,
                 range: 0..1,
-                lines: 0,
                 token: Ok(Token::Brace {
                     start: true,
                     brace: Brace::Curly
@@ -164,4 +192,22 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn dash() {
+        assert_eq!(parse_token(b"-inf").token, Ok(Token::Ident("-inf")));
+        assert_eq!(parse_token(b"-inf,").token, Ok(Token::Ident("-inf")));
+        assert_eq!(
+            parse_token(b"-1.5").token,
+            Ok(Token::Literal(Literal::Num("-1.5")))
+        );
+        for src in [&b"-infinity"[..], b"-foo: 1", b"-Inf"] {
+            let res = parse_token(src);
+            assert_eq!(res.range, 1..2);
+            assert_eq!(
+                res.token,
+                Err(TokenError::UnexpectedChar(char::from(src[1])))
+            );
+        }
+    }
 }
@@ -8,6 +8,7 @@ use crate::{
 
 use super::ParseRes;
 
+/// Decode `value` that starts at `start`, on failure the whole token up to `end` is skipped
 fn parse_utf8<'a>(
     start: usize,
     end: usize,
@@ -16,16 +17,15 @@ fn parse_utf8<'a>(
 ) -> ParseRes<'a> {
     std::str::from_utf8(value).map(f).unwrap_or_else(|e| {
         let new_start = start + e.valid_up_to();
-        let extra = (start + end + 1) - new_start;
         ParseRes::new_res(
             new_start..new_start + 1,
-            extra,
+            end - (new_start + 1),
             Err(TokenError::LitStrNotUtf8),
         )
     })
 }
 
-pub fn parse_basic(buf: &[u8], start: usize) -> ParseRes {
+pub fn parse_basic(buf: &[u8], start: usize) -> ParseRes<'_> {
     let mut pos = start + 1;
     let mut tag = StrTag::Raw;
 
@@ -33,7 +33,7 @@ pub fn parse_basic(buf: &[u8], start: usize) -> ParseRes {
         pos += off;
         match buf[pos] {
             b'"' => {
-                return parse_utf8(start + 1, pos, &buf[start + 1..pos], |value| {
+                return parse_utf8(start + 1, pos + 1, &buf[start + 1..pos], |value| {
                     ParseRes::new(
                         start..pos + 1,
                         Token::Literal(Literal::Str(StrLit::new(tag, value))),
@@ -46,11 +46,12 @@ pub fn parse_basic(buf: &[u8], start: usize) -> ParseRes {
             }
             unk => {
                 assert_eq!(unk, b'\n');
-                return ParseRes::new_res(
-                    pos..pos + 1,
-                    0,
-                    Err(TokenError::MissingStringTerminator),
-                );
+                // NOTE(2024.04): The newline is not skipped, it ends the line as usual
+                return ParseRes {
+                    len: pos,
+                    range: pos..pos + 1,
+                    token: Err(TokenError::MissingStringTerminator),
+                };
             }
         }
     }
@@ -62,7 +63,7 @@ pub fn parse_basic(buf: &[u8], start: usize) -> ParseRes {
     )
 }
 
-pub fn parse_tagged(buf: &[u8], start: usize) -> Option<ParseRes> {
+pub fn parse_tagged(buf: &[u8], start: usize) -> Option<ParseRes<'_>> {
     let num_hashes = buf[start..].find_not_byteset(b"#").unwrap();
     let suffix = StrTagSuffix::new(num_hashes);
 
@@ -89,9 +90,8 @@ pub fn parse_tagged(buf: &[u8], start: usize) -> Option<ParseRes> {
 
     if let Some((value, _)) = buf[pos..].split_once_str(&suffix) {
         let end = pos + value.len() + suffix.len();
-        return Some(parse_utf8(start + pos, end, value, |v| {
-            ParseRes::new_lines(
-                buf,
+        return Some(parse_utf8(pos, end, value, |v| {
+            ParseRes::new_res(
                 start..end,
                 0,
                 Ok(Token::Literal(Literal::Str(StrLit::new(tag, v)))),
@@ -17,6 +17,22 @@ impl<'a> StrLit<'a> {
         Self { tag, value }
     }
 
+    /// How the value is to be decoded
+    pub fn tag(&self) -> StrTag {
+        self.tag
+    }
+
+    /// The length of the quote, tag and hashes in front of the value in the token at `span`
+    fn prefix_len(&self, span: &SourceSpan) -> usize {
+        // The token is either `"value"` or a tag with the same number of hashes on both sides,
+        // such as `#esc"value"#`, and every tag is three letters long
+        let extra = span.range().len().saturating_sub(self.value.len());
+        match extra.saturating_sub(5) / 2 {
+            0 => 1,
+            hashes => hashes + 4,
+        }
+    }
+
     pub fn decode_str(&self, span: &SourceSpan) -> Result<Cow<'a, str>> {
         match self.tag {
             StrTag::Raw => Ok(Cow::Borrowed(self.value)),
@@ -24,7 +40,9 @@ impl<'a> StrLit<'a> {
                 super::esc::decode_str(self.value)
                     .map(Cow::Owned)
                     .map_err(|(off, len, error)| ParseError::StrEscError {
-                        span: span.with_off(off, len),
+                        // NOTE(2024.04): The offsets count from the char before the value, which is
+                        // the start of the span only for basic strings, not for `#esc"..."#`
+                        span: span.with_off(self.prefix_len(span) - 1 + off, len),
                         error,
                     })
             }
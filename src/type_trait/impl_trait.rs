@@ -1,14 +1,207 @@
+use std::{
+    num::{
+        IntErrorKind, NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize,
+        NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+    rc::Rc,
+    sync::Arc,
+};
+
 use crate::{
-    error::{ParseError, Result, Wanted},
+    error::{NumError, ParseError, Result, Wanted, WriteError, WriteResult},
     parser::LogixParser,
-    token::{Literal, Token},
-    type_trait::{LogixTypeDescriptor, LogixValueDescriptor, Value},
-    LogixType,
+    token::{Brace, Delim, Literal, Token},
+    type_trait::{type_descriptor, LogixTypeDescriptor, LogixValueDescriptor, Value},
+    LogixType, LogixWriter,
 };
 use logix_vfs::LogixVfs;
 
+/// Parse an integer literal, on failure the offset and length of the offending part is returned
+///
+/// Supports the `0x`, `0o` and `0b` prefixes as well as `_` as a digit separator
+fn parse_int<T>(
+    num: &str,
+    signed: bool,
+    from_str_radix: fn(&str, u32) -> Result<T, std::num::ParseIntError>,
+) -> Result<T, (usize, usize, NumError)> {
+    let (negative, unsigned) = match num.strip_prefix('-') {
+        Some(_) if !signed => return Err((0, 1, NumError::Negative)),
+        Some(unsigned) => (true, unsigned),
+        None => (false, num),
+    };
+
+    let (radix, digits) = match unsigned.get(..2) {
+        Some("0x") => (16, &unsigned[2..]),
+        Some("0o") => (8, &unsigned[2..]),
+        Some("0b") => (2, &unsigned[2..]),
+        _ => (10, unsigned),
+    };
+    let start = num.len() - digits.len();
+
+    let mut clean = String::with_capacity(num.len());
+    if negative {
+        clean.push('-');
+    }
+    for (off, c) in digits.char_indices() {
+        if c.is_digit(radix) {
+            clean.push(c);
+        } else if c != '_' || off == 0 {
+            return Err((start + off, c.len_utf8(), NumError::InvalidDigit(c)));
+        }
+    }
+
+    from_str_radix(&clean, radix).map_err(|e| {
+        let error = match e.kind() {
+            IntErrorKind::PosOverflow => NumError::TooLarge,
+            IntErrorKind::NegOverflow => NumError::TooSmall,
+            _ => NumError::Empty,
+        };
+        (0, num.len(), error)
+    })
+}
+
+/// Parse a decimal floating point literal, on failure the offset and length of the offending part
+/// is returned
+///
+/// `str::parse` also accepts things like `infinity`, so the literal is checked here first
+fn parse_float<T: std::str::FromStr>(num: &str) -> Result<T, (usize, usize, NumError)> {
+    let bytes = num.as_bytes();
+    let digits = |pos: &mut usize| {
+        let start = *pos;
+        while bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+            *pos += 1;
+        }
+        *pos > start
+    };
+
+    let mut pos = usize::from(bytes.first() == Some(&b'-'));
+    let mut valid = digits(&mut pos);
+    if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+        valid |= digits(&mut pos);
+    }
+    if valid && matches!(bytes.get(pos), Some(b'e' | b'E')) {
+        pos += 1;
+        if matches!(bytes.get(pos), Some(b'+' | b'-')) {
+            pos += 1;
+        }
+        valid = digits(&mut pos);
+    }
+
+    match num[pos..].chars().next() {
+        Some(c) => Err((pos, c.len_utf8(), NumError::InvalidDigit(c))),
+        None if !valid => Err((0, num.len(), NumError::Empty)),
+        None => num.parse().map_err(|_| (0, num.len(), NumError::Empty)),
+    }
+}
+
+fn parse_int_token<FS: LogixVfs, T>(
+    p: &mut LogixParser<FS>,
+    while_parsing: &'static str,
+    wanted: Wanted,
+    signed: bool,
+    from_str_radix: fn(&str, u32) -> Result<T, std::num::ParseIntError>,
+) -> Result<Value<T>> {
+    match p.next_token()? {
+        (span, Token::Literal(Literal::Num(num))) => Ok(Value {
+            value: parse_int(num, signed, from_str_radix).map_err(|(off, len, error)| {
+                ParseError::NumError {
+                    span: span.with_off(off, len),
+                    while_parsing,
+                    error,
+                }
+            })?,
+            span,
+        }),
+        (span, token) => Err(ParseError::UnexpectedToken {
+            span,
+            got_token: token.token_type_name(),
+            wanted,
+            while_parsing,
+        }),
+    }
+}
+
 macro_rules! impl_for_int {
-    ($signed:literal => $($type:ty),+) => {$(
+    ($signed:literal, $is_signed:literal => $($type:ty),+) => {$(
+        impl LogixType for $type {
+            fn descriptor() -> &'static LogixTypeDescriptor {
+                &LogixTypeDescriptor {
+                    name: stringify!($type),
+                    doc: "",
+                    value: LogixValueDescriptor::Native,
+                }
+            }
+
+            fn default_value() -> Option<Self> {
+                None
+            }
+
+            fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+                parse_int_token(
+                    p,
+                    Self::descriptor().name,
+                    Wanted::LitNum(concat!($signed, " integer")),
+                    $is_signed,
+                    <$type>::from_str_radix,
+                )
+            }
+
+            fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+                w.write_num(self)
+            }
+        }
+    )*};
+}
+
+impl_for_int!("signed", true => i8, i16, i32, i64, i128, isize);
+impl_for_int!("unsigned", false => u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_for_non_zero {
+    ($signed:literal, $is_signed:literal => $($type:ident($inner:ty)),+) => {$(
+        impl LogixType for $type {
+            fn descriptor() -> &'static LogixTypeDescriptor {
+                &LogixTypeDescriptor {
+                    name: stringify!($type),
+                    doc: "",
+                    value: LogixValueDescriptor::Native,
+                }
+            }
+
+            fn default_value() -> Option<Self> {
+                None
+            }
+
+            fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+                let Value { value, span } = parse_int_token(
+                    p,
+                    Self::descriptor().name,
+                    Wanted::LitNum(concat!("non-zero ", $signed, " integer")),
+                    $is_signed,
+                    <$inner>::from_str_radix,
+                )?;
+                match Self::new(value) {
+                    Some(value) => Ok(Value { value, span }),
+                    None => Err(ParseError::NumError {
+                        span,
+                        while_parsing: Self::descriptor().name,
+                        error: NumError::Zero,
+                    }),
+                }
+            }
+
+            fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+                w.write_num(self)
+            }
+        }
+    )*};
+}
+
+impl_for_non_zero!("signed", true => NonZeroI8(i8), NonZeroI16(i16), NonZeroI32(i32), NonZeroI64(i64), NonZeroI128(i128), NonZeroIsize(isize));
+impl_for_non_zero!("unsigned", false => NonZeroU8(u8), NonZeroU16(u16), NonZeroU32(u32), NonZeroU64(u64), NonZeroU128(u128), NonZeroUsize(usize));
+
+macro_rules! impl_for_float {
+    ($($type:ty),+) => {$(
         impl LogixType for $type {
             fn descriptor() -> &'static LogixTypeDescriptor {
                 &LogixTypeDescriptor {
@@ -25,27 +218,118 @@ macro_rules! impl_for_int {
             fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
                 match p.next_token()? {
                     (span, Token::Literal(Literal::Num(num))) => Ok(Value {
-                        value: num.parse().unwrap(), // TODO(2023.10): Return a sensible error
+                        value: parse_float(num).map_err(|(off, len, error)| {
+                            ParseError::NumError {
+                                span: span.with_off(off, len),
+                                while_parsing: Self::descriptor().name,
+                                error,
+                            }
+                        })?,
                         span,
                     }),
+                    (span, Token::Ident("inf")) => Ok(Value { value: <$type>::INFINITY, span }),
+                    (span, Token::Ident("-inf")) => Ok(Value { value: <$type>::NEG_INFINITY, span }),
+                    (span, Token::Ident("nan")) => Ok(Value { value: <$type>::NAN, span }),
                     (span, token) => Err(ParseError::UnexpectedToken {
                         span,
                         got_token: token.token_type_name(),
-                        wanted: Wanted::LitNum(concat!($signed, " integer")),
+                        wanted: Wanted::LitFloat,
                         while_parsing: Self::descriptor().name,
                     }),
                 }
             }
+
+            fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+                if self.is_nan() {
+                    w.write_num("nan")
+                } else if self.is_infinite() {
+                    w.write_num(if self.is_sign_positive() { "inf" } else { "-inf" })
+                } else {
+                    // NOTE(2024.04): Unlike `Display`, `Debug` uses an exponent for large and small values
+                    w.write_num(format_args!("{self:?}"))
+                }
+            }
         }
     )*};
 }
 
-impl_for_int!("signed" => i8, i16, i32, i64);
-impl_for_int!("unsigned" => u8, u16, u32, u64);
+impl_for_float!(f32, f64);
+
+impl LogixType for bool {
+    fn descriptor() -> &'static LogixTypeDescriptor {
+        &LogixTypeDescriptor {
+            name: "bool",
+            doc: "",
+            value: LogixValueDescriptor::Native,
+        }
+    }
+
+    fn default_value() -> Option<Self> {
+        None
+    }
+
+    fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+        match p.next_token()? {
+            (span, Token::Ident("true")) => Ok(Value { value: true, span }),
+            (span, Token::Ident("false")) => Ok(Value { value: false, span }),
+            (span, token) => Err(ParseError::UnexpectedToken {
+                span,
+                got_token: token.token_type_name(),
+                wanted: Wanted::Bool,
+                while_parsing: Self::descriptor().name,
+            }),
+        }
+    }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        w.write_ident(if *self { "true" } else { "false" })
+    }
+}
+
+impl LogixType for char {
+    fn descriptor() -> &'static LogixTypeDescriptor {
+        &LogixTypeDescriptor {
+            name: "char",
+            doc: "",
+            value: LogixValueDescriptor::Native,
+        }
+    }
+
+    fn default_value() -> Option<Self> {
+        None
+    }
+
+    fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+        let (span, token) = p.next_token()?;
+        if let Token::Literal(Literal::Str(value)) = token {
+            let value = value.decode_str(&span)?;
+            let mut it = value.chars();
+            if let (Some(value), None) = (it.next(), it.next()) {
+                return Ok(Value { value, span });
+            }
+        }
+        Err(ParseError::UnexpectedToken {
+            span,
+            got_token: token.token_type_name(),
+            wanted: Wanted::LitChar,
+            while_parsing: Self::descriptor().name,
+        })
+    }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        w.write_str(self.encode_utf8(&mut [0; 4]))
+    }
+}
 
 impl<T: LogixType> LogixType for Option<T> {
     fn descriptor() -> &'static LogixTypeDescriptor {
-        T::descriptor()
+        type_descriptor::<Self>("optional", || LogixTypeDescriptor {
+            name: "optional",
+            doc: "a value that can be left out",
+            value: LogixValueDescriptor::Optional {
+                inner: T::descriptor(),
+            },
+        })
     }
 
     fn default_value() -> Option<Self> {
@@ -55,4 +339,102 @@ impl<T: LogixType> LogixType for Option<T> {
     fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
         T::logix_parse(p).map(|v| v.map(Some))
     }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        match self {
+            Some(v) => v.logix_write(w),
+            // NOTE(2024.04): There is no literal for `None`, it can only be written by being omitted
+            None => Err(WriteError::None),
+        }
+    }
+
+    fn logix_omit(&self) -> bool {
+        self.is_none()
+    }
+}
+
+macro_rules! impl_for_ptr {
+    ($($type:ident),+) => {$(
+        impl<T: LogixType> LogixType for $type<T> {
+            fn descriptor() -> &'static LogixTypeDescriptor {
+                T::descriptor()
+            }
+
+            fn default_value() -> Option<Self> {
+                T::default_value().map($type::new)
+            }
+
+            fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+                T::logix_parse(p).map(|v| v.map($type::new))
+            }
+
+            fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+                T::logix_write(self, w)
+            }
+
+            fn logix_omit(&self) -> bool {
+                T::logix_omit(self)
+            }
+        }
+    )*};
+}
+
+impl_for_ptr!(Box, Rc, Arc);
+
+macro_rules! impl_for_tuple {
+    ($(($($name:ident),+))+) => {$(
+        impl<$($name: LogixType),+> LogixType for ($($name,)+) {
+            fn descriptor() -> &'static LogixTypeDescriptor {
+                type_descriptor::<Self>("tuple", || LogixTypeDescriptor {
+                    name: "tuple",
+                    doc: "",
+                    value: LogixValueDescriptor::Tuple {
+                        members: vec![$($name::descriptor()),+],
+                    },
+                })
+            }
+
+            fn default_value() -> Option<Self> {
+                None
+            }
+
+            fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+                p.req_wrapped("tuple", Brace::Paren, |p| {
+                    let depth = p.depth();
+                    let mut errors = Vec::new();
+                    let mut first = true;
+                    #[allow(non_snake_case)]
+                    let ($($name,)+) = ($(p.recover_item(&mut errors, depth, |p| {
+                        if !std::mem::take(&mut first) {
+                            p.req_token("tuple", Token::Delim(Delim::Comma))?;
+                        }
+                        Ok($name::logix_parse(p)?.value)
+                    })?,)+);
+                    p.finish_recovery(errors)?;
+                    // NOTE(2024.04): Every item is parsed if no errors were collected
+                    Ok(($($name.unwrap(),)+))
+                })
+            }
+
+            fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+                #[allow(non_snake_case)]
+                let ($($name,)+) = self;
+                w.write_unnamed_tuple(|w| {
+                    $(w.write_item($name)?;)+
+                    Ok(())
+                })
+            }
+        }
+    )+};
+}
+
+impl_for_tuple! {
+    (A)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+    (A, B, C, D, E)
+    (A, B, C, D, E, F)
+    (A, B, C, D, E, F, G)
+    (A, B, C, D, E, F, G, H)
 }
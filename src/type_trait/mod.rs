@@ -2,7 +2,20 @@
 
 mod impl_trait;
 
-use crate::{error::Result, parser::LogixParser, span::SourceSpan};
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    sync::{OnceLock, RwLock},
+};
+
+use crate::{
+    error::{Result, WriteResult},
+    parser::LogixParser,
+    span::SourceSpan,
+    writer::LogixWriter,
+};
 pub use logix_vfs::LogixVfs;
 
 /// Represents a value and the location in the config file
@@ -36,11 +49,97 @@ pub enum LogixValueDescriptor {
         members: Vec<&'static LogixTypeDescriptor>,
     },
     /// Describes the named members of a struct
-    Struct {
-        members: Vec<(&'static str, &'static LogixTypeDescriptor)>,
-    },
+    Struct { members: Vec<LogixMemberDescriptor> },
     /// Describes the variants of an enum
     Enum { variants: Vec<LogixTypeDescriptor> },
+    /// A list of any length where every item has the same type
+    List { item: &'static LogixTypeDescriptor },
+    /// A list with exactly `len` items of the same type
+    Array {
+        len: usize,
+        item: &'static LogixTypeDescriptor,
+    },
+    /// A list of unique items of the same type
+    Set { item: &'static LogixTypeDescriptor },
+    /// A map where every entry has the same key and value type
+    Map {
+        key: &'static LogixTypeDescriptor,
+        value: &'static LogixTypeDescriptor,
+    },
+    /// A value that can be left out
+    Optional { inner: &'static LogixTypeDescriptor },
+    /// Refers back to a type that contains it, this is how recursive types are described
+    ///
+    /// Use `target.get()` to get the full descriptor, it is available once the outer one is complete
+    BackRef { target: LogixTypeRef },
+}
+
+/// A reference to the descriptor of a type that is looked up when needed
+#[derive(Clone, Copy)]
+pub struct LogixTypeRef {
+    type_id: TypeId,
+    get: fn() -> &'static LogixTypeDescriptor,
+}
+
+impl LogixTypeRef {
+    /// Refers to the descriptor of `T`
+    pub fn of<T: LogixType>() -> Self {
+        Self {
+            type_id: typeid::of::<T>(),
+            get: T::descriptor,
+        }
+    }
+
+    /// Returns the descriptor, must not be called while the descriptor is being created
+    pub fn get(&self) -> &'static LogixTypeDescriptor {
+        (self.get)()
+    }
+}
+
+// NOTE(2024.04): Function pointers are not guaranteed to be unique, so compare the type instead
+impl PartialEq for LogixTypeRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+    }
+}
+
+impl Eq for LogixTypeRef {}
+
+impl PartialOrd for LogixTypeRef {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LogixTypeRef {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.type_id.cmp(&other.type_id)
+    }
+}
+
+impl std::hash::Hash for LogixTypeRef {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.type_id.hash(state)
+    }
+}
+
+impl fmt::Debug for LogixTypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LogixTypeRef").field(&self.type_id).finish()
+    }
+}
+
+/// Describes a named member of a struct
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct LogixMemberDescriptor {
+    /// Name of the member
+    pub name: &'static str,
+    /// Documentation for the member
+    pub doc: &'static str,
+    /// True if the member can be left out
+    pub optional: bool,
+    /// Describes the type of the member
+    pub ty: &'static LogixTypeDescriptor,
 }
 
 /// Describes a type in the logix config file
@@ -54,6 +153,83 @@ pub struct LogixTypeDescriptor {
     pub value: LogixValueDescriptor,
 }
 
+impl LogixTypeDescriptor {
+    /// Follows a [`LogixValueDescriptor::BackRef`] to the type it refers to, returns `self` otherwise
+    pub fn resolve(&'static self) -> &'static Self {
+        match self.value {
+            LogixValueDescriptor::BackRef { target } => target.get(),
+            _ => self,
+        }
+    }
+}
+
+/// Returns the descriptor of the type `T`, it is created by `init` the first time
+///
+/// A `static` inside a generic function is shared by every instantiation, so each type gets
+/// its descriptor from here instead. If `init` asks for the descriptor of `T` again, as a
+/// recursive type does, a [`LogixValueDescriptor::BackRef`] named `name` is returned in its place
+pub fn type_descriptor<T: LogixType>(
+    name: &'static str,
+    init: impl FnOnce() -> LogixTypeDescriptor,
+) -> &'static LogixTypeDescriptor {
+    type Registry = RwLock<HashMap<TypeId, &'static LogixTypeDescriptor>>;
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    static BACK_REFS: OnceLock<Registry> = OnceLock::new();
+
+    thread_local! {
+        static IN_PROGRESS: RefCell<Vec<TypeId>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Removes the type from `IN_PROGRESS` when dropped, even if `init` panics
+    struct InProgress(TypeId);
+
+    impl Drop for InProgress {
+        fn drop(&mut self) {
+            IN_PROGRESS.with_borrow_mut(|v| v.retain(|id| *id != self.0));
+        }
+    }
+
+    // NOTE(2024.04): `typeid` erases the lifetimes so `T` doesn't need to be `'static`, `&'a str`
+    // and `&'static str` get the same descriptor which is what we want anyway
+    let type_id = typeid::of::<T>();
+    let registry = REGISTRY.get_or_init(Default::default);
+    if let Some(ret) = registry.read().unwrap().get(&type_id) {
+        return ret;
+    }
+
+    if IN_PROGRESS.with_borrow(|v| v.contains(&type_id)) {
+        let back_refs = BACK_REFS.get_or_init(Default::default);
+        if let Some(ret) = back_refs.read().unwrap().get(&type_id) {
+            return ret;
+        }
+        return back_refs
+            .write()
+            .unwrap()
+            .entry(type_id)
+            .or_insert_with(|| {
+                Box::leak(Box::new(LogixTypeDescriptor {
+                    name,
+                    doc: "",
+                    value: LogixValueDescriptor::BackRef {
+                        target: LogixTypeRef::of::<T>(),
+                    },
+                }))
+            });
+    }
+
+    // NOTE(2024.04): The lock must not be held here, `init` usually needs other descriptors
+    IN_PROGRESS.with_borrow_mut(|v| v.push(type_id));
+    let guard = InProgress(type_id);
+    let desc = init();
+    drop(guard);
+
+    registry
+        .write()
+        .unwrap()
+        .entry(type_id)
+        .or_insert_with(|| Box::leak(Box::new(desc)))
+}
+
 /// This trait is used to represent types that can be stored in a logix config.
 pub trait LogixType: Sized {
     /// A description of the type, intended used for documentation and auto-completion
@@ -62,4 +238,10 @@ pub trait LogixType: Sized {
     fn default_value() -> Option<Self>;
     /// Parse the value from the given parser state
     fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>>;
+    /// Write the value to the given writer, the output can be read back using `logix_parse`
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult;
+    /// Returns true if the value can be left out of a struct or map, `default_value` restores it
+    fn logix_omit(&self) -> bool {
+        false
+    }
 }
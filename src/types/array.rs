@@ -1,17 +1,22 @@
+use std::collections::VecDeque;
+
 use crate::{
-    error::Result,
+    error::{Result, WriteResult},
     token::Brace,
-    type_trait::{LogixTypeDescriptor, LogixValueDescriptor, Value},
-    LogixParser, LogixType,
+    type_trait::{type_descriptor, LogixTypeDescriptor, LogixValueDescriptor, Value},
+    LogixParser, LogixType, LogixWriter,
 };
 
 impl<const SIZE: usize, T: LogixType> LogixType for [T; SIZE] {
     fn descriptor() -> &'static LogixTypeDescriptor {
-        &LogixTypeDescriptor {
+        type_descriptor::<Self>("Array", || LogixTypeDescriptor {
             name: "Array",
             doc: "a fixed size array",
-            value: LogixValueDescriptor::Native,
-        }
+            value: LogixValueDescriptor::Array {
+                len: SIZE,
+                item: T::descriptor(),
+            },
+        })
     }
 
     fn default_value() -> Option<Self> {
@@ -29,15 +34,21 @@ impl<const SIZE: usize, T: LogixType> LogixType for [T; SIZE] {
             Ok(ret.try_into().ok().unwrap())
         })
     }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        w.write_list(self)
+    }
 }
 
 impl<T: LogixType> LogixType for Vec<T> {
     fn descriptor() -> &'static LogixTypeDescriptor {
-        &LogixTypeDescriptor {
+        type_descriptor::<Self>("list", || LogixTypeDescriptor {
             name: "list",
             doc: "a dynamically sized array",
-            value: LogixValueDescriptor::Native,
-        }
+            value: LogixValueDescriptor::List {
+                item: T::descriptor(),
+            },
+        })
     }
 
     fn default_value() -> Option<Self> {
@@ -54,4 +65,26 @@ impl<T: LogixType> LogixType for Vec<T> {
             Ok(ret)
         })
     }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        w.write_list(self)
+    }
+}
+
+impl<T: LogixType> LogixType for VecDeque<T> {
+    fn descriptor() -> &'static LogixTypeDescriptor {
+        Vec::<T>::descriptor()
+    }
+
+    fn default_value() -> Option<Self> {
+        None
+    }
+
+    fn logix_parse<FS: logix_vfs::LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+        Vec::<T>::logix_parse(p).map(|v| v.map(VecDeque::from))
+    }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        w.write_list(self)
+    }
 }
@@ -3,11 +3,11 @@ use std::path::PathBuf;
 use logix_vfs::LogixVfs;
 
 use crate::{
-    error::Result,
+    error::{Result, WriteResult},
     parser::LogixParser,
     token::{Action, Token},
     type_trait::{LogixTypeDescriptor, Value},
-    LogixType,
+    LogixType, LogixWriter,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -41,4 +41,11 @@ impl<T: LogixType> LogixType for Data<T> {
             })
         }
     }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        match self {
+            Self::ByPath(path) => w.write_include(path),
+            Self::Inline(value) => value.logix_write(w),
+        }
+    }
 }
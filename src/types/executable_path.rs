@@ -1,12 +1,12 @@
 use logix_vfs::LogixVfs;
 
 use crate::{
-    error::{ParseError, PathError, Result, SourceSpan, Wanted},
+    error::{ParseError, PathError, Result, SourceSpan, Wanted, WriteResult},
     parser::LogixParser,
     token::{Literal, StrLit, Token},
     type_trait::{LogixTypeDescriptor, LogixValueDescriptor, Value},
     types::{FullPath, NameOnlyPath, ValidPath},
-    LogixType,
+    LogixType, LogixWriter,
 };
 use std::{
     borrow::Cow,
@@ -1,31 +1,96 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
 use indexmap::IndexMap;
 use logix_vfs::LogixVfs;
 
 use crate::{
-    error::{Result, Warn},
+    error::{Result, Warn, WriteResult},
     parser::LogixParser,
+    span::SourceSpan,
     token::{Brace, Token},
-    type_trait::{LogixTypeDescriptor, LogixValueDescriptor, Value},
+    type_trait::{type_descriptor, LogixTypeDescriptor, LogixValueDescriptor, Value},
     types::ShortStr,
-    LogixType,
+    LogixType, LogixWriter,
 };
 
 pub type Map<V, K = ShortStr> = IndexMap<K, V>;
 
+fn map_descriptor<M: LogixType, V: LogixType>() -> &'static LogixTypeDescriptor {
+    type_descriptor::<M>("map", || LogixTypeDescriptor {
+        name: "map",
+        doc: "a map where the keys are identifiers",
+        value: LogixValueDescriptor::Map {
+            key: ShortStr::descriptor(),
+            value: V::descriptor(),
+        },
+    })
+}
+
+/// Parse the entries of a map, `insert` returns true if the key was already present
+fn parse_map<FS: LogixVfs, K: From<ShortStr>, V: LogixType>(
+    p: &mut LogixParser<FS>,
+    mut insert: impl FnMut(K, V) -> bool,
+) -> Result<SourceSpan> {
+    let start = p.req_token(
+        "map",
+        Token::Brace {
+            start: true,
+            brace: Brace::Curly,
+        },
+    )?;
+    p.req_token("map", Token::Newline(false))?;
+
+    let depth = p.depth();
+    let mut errors = Vec::new();
+    loop {
+        let res = match p.read_key_value("map", Brace::Curly) {
+            Ok(Some((key, value))) => {
+                if insert(K::from(key.value.clone()), value.value) {
+                    p.warning(Warn::DuplicateMapEntry {
+                        span: key.span,
+                        key: key.value,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            Ok(None) => break,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            p.recover(&mut errors, depth, Brace::Curly, e)?;
+        }
+    }
+    p.finish_recovery(errors)?;
+
+    // NOTE(2024.04): The closing brace was just consumed, so this covers the whole map
+    Ok(start.join(&p.cur_span()))
+}
+
+fn write_map<'a, K: AsRef<str> + 'a, V: LogixType + 'a>(
+    w: &mut LogixWriter,
+    entries: impl IntoIterator<Item = (&'a K, &'a V)>,
+) -> WriteResult {
+    w.write_map(|w| {
+        for (key, value) in entries {
+            w.write_member(key.as_ref(), value)?;
+        }
+        Ok(())
+    })
+}
+
 impl<T: LogixType, K> LogixType for Map<T, K>
 where
     K: AsRef<str>,
     K: From<ShortStr>,
-    K: std::hash::Hash,
+    K: Hash,
     K: Eq,
 {
     fn descriptor() -> &'static LogixTypeDescriptor {
-        static RET: LogixTypeDescriptor = LogixTypeDescriptor {
-            name: "string",
-            doc: "a valid utf-8 string",
-            value: LogixValueDescriptor::Native,
-        };
-        &RET
+        map_descriptor::<Self, T>()
     }
 
     fn default_value() -> Option<Self> {
@@ -34,28 +99,63 @@ where
 
     fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
         let mut map = IndexMap::new();
+        let span = parse_map(p, |k, v| map.insert(k, v).is_some())?;
+        Ok(Value { value: map, span })
+    }
 
-        let start = p.req_token(
-            "map",
-            Token::Brace {
-                start: true,
-                brace: Brace::Curly,
-            },
-        )?;
-        p.req_token("map", Token::Newline(false))?;
-
-        while let Some((key, value)) = p.read_key_value("map", Brace::Curly)? {
-            if let (i, Some(_)) = map.insert_full(K::from(key.value), value.value) {
-                p.warning(Warn::DuplicateMapEntry {
-                    span: key.span,
-                    key: map.get_index(i).unwrap().0.as_ref().into(),
-                })?;
-            }
-        }
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        write_map(w, self)
+    }
+}
+
+impl<T: LogixType, K, S> LogixType for HashMap<K, T, S>
+where
+    K: AsRef<str>,
+    K: From<ShortStr>,
+    K: Hash,
+    K: Eq,
+    S: BuildHasher + Default,
+{
+    fn descriptor() -> &'static LogixTypeDescriptor {
+        map_descriptor::<Self, T>()
+    }
+
+    fn default_value() -> Option<Self> {
+        Some(Self::default())
+    }
+
+    fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+        let mut map = HashMap::default();
+        let span = parse_map(p, |k, v| map.insert(k, v).is_some())?;
+        Ok(Value { value: map, span })
+    }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        write_map(w, self)
+    }
+}
+
+impl<T: LogixType, K> LogixType for BTreeMap<K, T>
+where
+    K: AsRef<str>,
+    K: From<ShortStr>,
+    K: Ord,
+{
+    fn descriptor() -> &'static LogixTypeDescriptor {
+        map_descriptor::<Self, T>()
+    }
+
+    fn default_value() -> Option<Self> {
+        Some(Self::new())
+    }
+
+    fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+        let mut map = BTreeMap::new();
+        let span = parse_map(p, |k, v| map.insert(k, v).is_some())?;
+        Ok(Value { value: map, span })
+    }
 
-        Ok(Value {
-            value: map,
-            span: start,
-        })
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        write_map(w, self)
     }
 }
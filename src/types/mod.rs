@@ -7,6 +7,7 @@ mod array;
 mod data;
 mod executable_path;
 mod map;
+mod set;
 mod string;
 
 pub use self::{
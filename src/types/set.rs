@@ -0,0 +1,84 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    hash::{BuildHasher, Hash},
+};
+
+use logix_vfs::LogixVfs;
+
+use crate::{
+    error::{Result, Warn, WriteResult},
+    parser::LogixParser,
+    token::Brace,
+    type_trait::{type_descriptor, LogixTypeDescriptor, LogixValueDescriptor, Value},
+    LogixType, LogixWriter,
+};
+
+fn set_descriptor<S: LogixType, T: LogixType>() -> &'static LogixTypeDescriptor {
+    type_descriptor::<S>("set", || LogixTypeDescriptor {
+        name: "set",
+        doc: "a list of unique values",
+        value: LogixValueDescriptor::Set {
+            item: T::descriptor(),
+        },
+    })
+}
+
+/// Parse the items of a set, `insert` returns false if the value was already present
+fn parse_set<FS: LogixVfs, T: LogixType, R>(
+    p: &mut LogixParser<FS>,
+    mut ret: R,
+    mut insert: impl FnMut(&mut R, T) -> bool,
+) -> Result<Value<R>> {
+    p.req_wrapped("set", Brace::Square, |p| {
+        let mut it = p.parse_delimited::<T>("set");
+        while let Some(Value { value, span }) = it.next_item()? {
+            if !insert(&mut ret, value) {
+                it.parser().warning(Warn::DuplicateSetEntry {
+                    span,
+                    type_name: "set",
+                })?;
+            }
+        }
+        Ok(ret)
+    })
+}
+
+impl<T, S> LogixType for HashSet<T, S>
+where
+    T: LogixType + Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn descriptor() -> &'static LogixTypeDescriptor {
+        set_descriptor::<Self, T>()
+    }
+
+    fn default_value() -> Option<Self> {
+        Some(Self::default())
+    }
+
+    fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+        parse_set(p, HashSet::default(), HashSet::insert)
+    }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        w.write_list(self)
+    }
+}
+
+impl<T: LogixType + Ord> LogixType for BTreeSet<T> {
+    fn descriptor() -> &'static LogixTypeDescriptor {
+        set_descriptor::<Self, T>()
+    }
+
+    fn default_value() -> Option<Self> {
+        Some(Self::default())
+    }
+
+    fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
+        parse_set(p, BTreeSet::new(), BTreeSet::insert)
+    }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        w.write_list(self)
+    }
+}
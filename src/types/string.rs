@@ -4,11 +4,11 @@ use logix_vfs::LogixVfs;
 use smol_str::SmolStr;
 
 use crate::{
-    error::{ParseError, Result, Wanted},
+    error::{ParseError, Result, Wanted, WriteResult},
     parser::LogixParser,
     token::{Literal, Token},
     type_trait::{LogixTypeDescriptor, LogixValueDescriptor, Value},
-    LogixType,
+    LogixType, LogixWriter,
 };
 
 /// Represents a short string, will not need allocation for typical identifiers
@@ -40,6 +40,12 @@ impl From<ShortStr> for Box<str> {
     }
 }
 
+impl From<ShortStr> for String {
+    fn from(value: ShortStr) -> Self {
+        value.value.into()
+    }
+}
+
 impl From<Arc<str>> for ShortStr {
     fn from(value: Arc<str>) -> Self {
         Self {
@@ -161,6 +167,10 @@ macro_rules! impl_for_str {
                     }),
                 })
             }
+
+            fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+                w.write_str(self)
+            }
         }
     )*};
 }
@@ -2,11 +2,11 @@ use bstr::ByteSlice;
 use logix_vfs::LogixVfs;
 
 use crate::{
-    error::{ParseError, PathError, Result, SourceSpan, Wanted},
+    error::{ParseError, PathError, Result, SourceSpan, Wanted, WriteResult},
     parser::LogixParser,
     token::{Literal, StrLit, Token},
     type_trait::{LogixTypeDescriptor, LogixValueDescriptor, Value},
-    LogixType,
+    LogixType, LogixWriter,
 };
 use std::{
     borrow::Cow,
@@ -22,7 +22,7 @@ macro_rules! impl_path_type_traits {
                 lit.decode_str(span)?
                     .try_into()
                     .map_err(|error| ParseError::PathError {
-                        span: span.clone(),
+                        span: *span,
                         error,
                     })
             }
@@ -134,6 +134,10 @@ macro_rules! impl_path_type_traits {
                     }),
                 }
             }
+
+            fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+                w.write_path(self.as_path())
+            }
         }
     };
 
@@ -298,4 +302,8 @@ impl LogixType for PathBuf {
     fn logix_parse<FS: LogixVfs>(p: &mut LogixParser<FS>) -> Result<Value<Self>> {
         Ok(ValidPath::logix_parse(p)?.map(|v| v.into()))
     }
+
+    fn logix_write(&self, w: &mut LogixWriter) -> WriteResult {
+        w.write_path(self)
+    }
 }
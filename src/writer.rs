@@ -0,0 +1,396 @@
+use std::{fmt, path::Path};
+
+use crate::{
+    error::{WriteError, WriteResult},
+    span::SourceSpan,
+    token::{StrLit, StrTag, StrTagSuffix},
+    LogixType,
+};
+
+const INDENT: &str = "  ";
+
+/// The writer used by the `LogixType` trait to produce `.logix` text
+///
+/// The output follows the canonical layout of a logix file, two spaces of
+/// indentation per level, one struct or map member per line and lists on a
+/// single line unless an item spans several lines.
+pub struct LogixWriter<'w> {
+    out: &'w mut dyn fmt::Write,
+    indent: usize,
+    first_item: bool,
+    /// Written at the start of every line after the first, before the indentation
+    base_indent: &'w str,
+    /// Written once per level of indentation
+    indent_unit: &'w str,
+}
+
+impl<'w> LogixWriter<'w> {
+    pub fn new(out: &'w mut dyn fmt::Write) -> Self {
+        Self::with_indent(out, "", INDENT)
+    }
+
+    /// A writer for a value that starts on a line indented by `base_indent` in an existing file
+    /// that is indented by `indent_unit` per level
+    pub(crate) fn with_indent(
+        out: &'w mut dyn fmt::Write,
+        base_indent: &'w str,
+        indent_unit: &'w str,
+    ) -> Self {
+        Self {
+            out,
+            indent: 0,
+            first_item: true,
+            base_indent,
+            indent_unit,
+        }
+    }
+
+    /// Write `value` as a complete logix file and return it as a string
+    pub fn to_file_string<T: LogixType>(value: &T) -> WriteResult<String> {
+        let mut ret = String::new();
+        LogixWriter::new(&mut ret).write_file(value)?;
+        Ok(ret)
+    }
+
+    /// Write `value` as a complete logix file, including the final newline
+    pub fn write_file<T: LogixType>(&mut self, value: &T) -> WriteResult {
+        value.logix_write(self)?;
+        Ok(self.out.write_char('\n')?)
+    }
+
+    /// Write a single value
+    pub fn write_value<T: LogixType>(&mut self, value: &T) -> WriteResult {
+        value.logix_write(self)
+    }
+
+    /// Write an identifier, fails if `ident` is not a valid identifier
+    pub fn write_ident(&mut self, ident: &str) -> WriteResult {
+        if is_valid_ident(ident) {
+            Ok(self.out.write_str(ident)?)
+        } else {
+            Err(WriteError::InvalidIdent(ident.into()))
+        }
+    }
+
+    /// Write a number literal
+    pub fn write_num(&mut self, num: impl fmt::Display) -> WriteResult {
+        Ok(write!(self.out, "{num}")?)
+    }
+
+    /// Write a string literal, picking the string format that keeps the value intact
+    ///
+    /// * Plain strings are written as `"value"`
+    /// * Strings with several lines of text are written as `#txt`
+    /// * Strings containing `"` or `\` are written as `#raw`
+    /// * Anything else is written as a basic string with backslash escapes (`#esc`)
+    pub fn write_str(&mut self, value: &str) -> WriteResult {
+        let has_ctrl = value.chars().any(char::is_control);
+
+        if !has_ctrl && !value.contains(['"', '\\']) {
+            Ok(write!(self.out, "\"{value}\"")?)
+        } else if let Some(txt) = self.try_txt_str(value) {
+            Ok(self.out.write_str(&txt)?)
+        } else if !has_ctrl {
+            let suffix = StrTagSuffix::new(required_hashes(value));
+            Ok(write!(
+                self.out,
+                "{}raw\"{value}{}",
+                &suffix.as_str()[1..],
+                suffix.as_str()
+            )?)
+        } else {
+            self.out.write_char('"')?;
+            for c in value.chars() {
+                match c {
+                    '\n' => self.out.write_str("\\n")?,
+                    '\r' => self.out.write_str("\\r")?,
+                    '\t' => self.out.write_str("\\t")?,
+                    '"' => self.out.write_str("\\\"")?,
+                    '\\' => self.out.write_str("\\\\")?,
+                    c if c.is_control() => write!(self.out, "\\u{{{:x}}}", u32::from(c))?,
+                    c => self.out.write_char(c)?,
+                }
+            }
+            Ok(self.out.write_char('"')?)
+        }
+    }
+
+    /// Write a path as a string literal, fails if the path is not valid utf-8
+    pub fn write_path(&mut self, path: &Path) -> WriteResult {
+        match path.to_str() {
+            Some(path) => self.write_str(path),
+            None => Err(WriteError::PathNotUtf8(path.into())),
+        }
+    }
+
+    /// Write an `@include` action referring to `path`
+    pub fn write_include(&mut self, path: &Path) -> WriteResult {
+        self.out.write_str("@include(")?;
+        self.write_path(path)?;
+        Ok(self.out.write_char(')')?)
+    }
+
+    /// Write a struct with the name `name`, use `write_member` inside `f` to write the members
+    pub fn write_struct(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut Self) -> WriteResult,
+    ) -> WriteResult {
+        self.write_ident(name)?;
+        self.out.write_char(' ')?;
+        self.write_map(f)
+    }
+
+    /// Write a map, use `write_member` inside `f` to write the entries
+    pub fn write_map(&mut self, f: impl FnOnce(&mut Self) -> WriteResult) -> WriteResult {
+        self.open_map()?;
+        f(self)?;
+        self.close_map()
+    }
+
+    /// Write a `key: value` line of a struct or map, omitted values are skipped
+    pub fn write_member<T: LogixType>(&mut self, key: &str, value: &T) -> WriteResult {
+        if value.logix_omit() {
+            return Ok(());
+        }
+        self.write_key(key)?;
+        value.logix_write(self)?;
+        Ok(self.out.write_char('\n')?)
+    }
+
+    /// Write the opening brace of a map, the entries are indented until `close_map`
+    pub(crate) fn open_map(&mut self) -> WriteResult {
+        self.out.write_str("{\n")?;
+        self.indent += 1;
+        Ok(())
+    }
+
+    pub(crate) fn close_map(&mut self) -> WriteResult {
+        self.indent -= 1;
+        self.write_indent()?;
+        Ok(self.out.write_char('}')?)
+    }
+
+    /// Write the start of a `key: value` line, the value and the newline are written by the caller
+    pub(crate) fn write_key(&mut self, key: &str) -> WriteResult {
+        self.write_indent()?;
+        self.write_ident(key)?;
+        Ok(self.out.write_str(": ")?)
+    }
+
+    /// Write `text` as is, it must not contain anything that needs indentation
+    #[cfg(feature = "serde")]
+    pub(crate) fn write_raw(&mut self, text: &str) -> WriteResult {
+        Ok(self.out.write_str(text)?)
+    }
+
+    /// A writer for a value that ends up inside this one, `extra_indent` levels deeper
+    pub(crate) fn nested<'a>(
+        &self,
+        out: &'a mut dyn fmt::Write,
+        extra_indent: usize,
+    ) -> LogixWriter<'a>
+    where
+        'w: 'a,
+    {
+        LogixWriter {
+            out,
+            indent: self.indent + extra_indent,
+            first_item: true,
+            base_indent: self.base_indent,
+            indent_unit: self.indent_unit,
+        }
+    }
+
+    /// Write a tuple with the name `name`, use `write_item` inside `f` to write the members
+    pub fn write_tuple(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut Self) -> WriteResult,
+    ) -> WriteResult {
+        self.write_ident(name)?;
+        self.write_unnamed_tuple(f)
+    }
+
+    /// Write a tuple without a name, use `write_item` inside `f` to write the members
+    pub fn write_unnamed_tuple(&mut self, f: impl FnOnce(&mut Self) -> WriteResult) -> WriteResult {
+        self.out.write_char('(')?;
+        let first_item = std::mem::replace(&mut self.first_item, true);
+        f(self)?;
+        self.first_item = first_item;
+        Ok(self.out.write_char(')')?)
+    }
+
+    /// Write the next member of a tuple
+    pub fn write_item<T: LogixType>(&mut self, value: &T) -> WriteResult {
+        if !std::mem::take(&mut self.first_item) {
+            self.out.write_str(", ")?;
+        }
+        value.logix_write(self)
+    }
+
+    /// Write a list of items, it is kept on one line unless any item spans multiple lines
+    pub fn write_list<'a, T: LogixType + 'a>(
+        &mut self,
+        items: impl IntoIterator<Item = &'a T>,
+    ) -> WriteResult {
+        let mut rendered = Vec::new();
+        for item in items {
+            let mut s = String::new();
+            self.nested(&mut s, 1).write_value(item)?;
+            rendered.push(s);
+        }
+        self.write_rendered_list(rendered)
+    }
+
+    /// Write a list of items that were written by writers from `nested(.., 1)`
+    pub(crate) fn write_rendered_list(&mut self, rendered: Vec<String>) -> WriteResult {
+        if rendered.iter().any(|s| s.contains('\n')) {
+            self.out.write_str("[\n")?;
+            for item in rendered {
+                self.write_indent()?;
+                self.out.write_str(self.indent_unit)?;
+                self.out.write_str(&item)?;
+                self.out.write_char('\n')?;
+            }
+            self.write_indent()?;
+        } else {
+            self.out.write_char('[')?;
+            for (i, item) in rendered.iter().enumerate() {
+                if i != 0 {
+                    self.out.write_str(", ")?;
+                }
+                self.out.write_str(item)?;
+            }
+        }
+        Ok(self.out.write_char(']')?)
+    }
+
+    fn write_indent(&mut self) -> WriteResult {
+        self.out.write_str(self.base_indent)?;
+        for _ in 0..self.indent {
+            self.out.write_str(self.indent_unit)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the `#txt` representation of `value` if it is multi-line text that survives the round-trip
+    fn try_txt_str(&self, value: &str) -> Option<String> {
+        if !value.trim_end_matches('\n').contains('\n')
+            || value.chars().any(|c| c != '\n' && c.is_control())
+        {
+            return None;
+        }
+
+        let prefix = format!(
+            "{}{}",
+            self.base_indent,
+            self.indent_unit.repeat(self.indent + 1)
+        );
+        let mut body = String::from("\n");
+        for (i, part) in value.split('\n').enumerate() {
+            if i != 0 {
+                body.push('\n');
+            }
+            if !part.is_empty() {
+                body.push_str(&prefix);
+                body.push_str(part);
+                body.push('\n');
+            }
+        }
+        body.push_str(&prefix);
+
+        let decoded = StrLit::new(StrTag::Txt, &body)
+            .decode_str(&SourceSpan::empty())
+            .ok()?;
+        if decoded != value {
+            return None;
+        }
+
+        let suffix = StrTagSuffix::new(required_hashes(value));
+        Some(format!(
+            "{}txt\"{body}{}",
+            &suffix.as_str()[1..],
+            suffix.as_str()
+        ))
+    }
+}
+
+/// The number of hashes needed so the string suffix does not occur inside `value`
+fn required_hashes(value: &str) -> usize {
+    (1..)
+        .find(|&n| !value.contains(StrTagSuffix::new(n).as_str()))
+        .unwrap()
+}
+
+pub(crate) fn is_valid_ident(s: &str) -> bool {
+    let mut it = s.bytes();
+    matches!(it.next(), Some(b'a'..=b'z' | b'A'..=b'Z' | b'_'))
+        && it.all(|c| matches!(c, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn str_value(value: &str, indent: usize) -> String {
+        let mut ret = String::new();
+        let mut w = LogixWriter::new(&mut ret);
+        w.indent = indent;
+        w.write_str(value).unwrap();
+        ret
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(str_value("hello", 0), "\"hello\"");
+        assert_eq!(str_value("a \"b\"", 0), "#raw\"a \"b\"\"#");
+        assert_eq!(str_value("a \"# b", 0), "##raw\"a \"# b\"##");
+        assert_eq!(str_value("c:\\dir", 0), "#raw\"c:\\dir\"#");
+        assert_eq!(str_value("a\tb", 0), "\"a\\tb\"");
+        assert_eq!(str_value("line\n", 0), "\"line\\n\"");
+        assert_eq!(str_value("\u{1}\"", 0), "\"\\u{1}\\\"\"");
+        assert_eq!(
+            str_value("first\nsecond\n\nthird", 1),
+            "#txt\"\n    first\n\n    second\n\n\n    third\n    \"#"
+        );
+        // Leading whitespace would be stripped by `#txt`, so it must be escaped
+        assert_eq!(str_value("  a\n  b", 0), "\"  a\\n  b\"");
+    }
+
+    #[test]
+    fn floats() {
+        let value = |v: f64| LogixWriter::to_file_string(&v).unwrap();
+        assert_eq!(value(1e300), "1e300\n");
+        assert_eq!(value(-1.5e-7), "-1.5e-7\n");
+        assert_eq!(value(0.5), "0.5\n");
+        assert_eq!(value(2.0), "2.0\n");
+        assert_eq!(value(f64::INFINITY), "inf\n");
+        assert_eq!(value(f64::NEG_INFINITY), "-inf\n");
+        assert_eq!(value(f64::NAN), "nan\n");
+        assert_eq!(LogixWriter::to_file_string(&1e30f32).unwrap(), "1e30\n");
+    }
+
+    #[test]
+    fn errors() {
+        use crate::types::Map;
+
+        assert_eq!(
+            LogixWriter::to_file_string(&Map::<u32, String>::from([("a b".into(), 1)])),
+            Err(WriteError::InvalidIdent("a b".into()))
+        );
+        assert_eq!(
+            LogixWriter::to_file_string(&vec![Some(1), None]),
+            Err(WriteError::None)
+        );
+    }
+
+    #[test]
+    fn idents() {
+        assert!(is_valid_ident("hello_world-2"));
+        assert!(is_valid_ident("_a"));
+        assert!(!is_valid_ident(""));
+        assert!(!is_valid_ident("2a"));
+        assert!(!is_valid_ident("a b"));
+    }
+}
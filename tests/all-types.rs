@@ -1,9 +1,16 @@
-use std::{path::PathBuf, rc::Rc, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    num::NonZeroU32,
+    path::PathBuf,
+    rc::Rc,
+    sync::Arc,
+};
 
 use logix_type::{
     error::Result,
+    format::format,
     types::{Data, ExecutablePath, FullPath, Map, NameOnlyPath, RelPath, ShortStr, ValidPath},
-    LogixLoader, LogixType,
+    LogixLoader, LogixType, LogixWriter,
 };
 use logix_vfs::{LogixVfs, RelFs};
 
@@ -29,6 +36,22 @@ struct Root {
     type_u32: u32,
     type_i64: i64,
     type_u64: u64,
+    type_i128: i128,
+    type_u128: u128,
+    type_isize: isize,
+    type_usize: usize,
+    type_non_zero: NonZeroU32,
+    type_hex: u32,
+    type_octal: u32,
+    type_binary: i8,
+    type_separated: i64,
+    type_bool_true: bool,
+    type_bool_false: bool,
+    type_f32: f32,
+    type_f64: f64,
+    type_f64_exp: f64,
+    type_f64_inf: f64,
+    type_char: char,
     type_str: ShortStr,
     type_string: String,
     type_path: PathBuf,
@@ -62,6 +85,15 @@ struct Root {
     arc_map: Map<u32, Arc<str>>,
     rc_map: Map<u32, Rc<str>>,
     box_map: Map<u32, Box<str>>,
+    btree_map: BTreeMap<String, u32>,
+    hash_map: HashMap<String, u32>,
+    btree_set: BTreeSet<i32>,
+    hash_set: HashSet<String>,
+    vec_deque: VecDeque<u8>,
+    tuple: (u32, String, bool),
+    boxed: Box<u32>,
+    rc_string: Rc<String>,
+    arc_node: Arc<NamedNode>,
 }
 
 #[derive(logix_type::LogixType, PartialEq, Debug)]
@@ -83,6 +115,22 @@ fn expected_root() -> Root {
         type_u32: 69696,
         type_i64: -7202218937,
         type_u64: 7202218937,
+        type_i128: -170141183460469231731687303715884105728,
+        type_u128: 340282366920938463463374607431768211455,
+        type_isize: -1024,
+        type_usize: 2048,
+        type_non_zero: NonZeroU32::new(7).unwrap(),
+        type_hex: 0xc0ffee,
+        type_octal: 0o755,
+        type_binary: -0b1010,
+        type_separated: 1_000_000,
+        type_bool_true: true,
+        type_bool_false: false,
+        type_f32: 0.5,
+        type_f64: -1234.5678,
+        type_f64_exp: 1.5e-7,
+        type_f64_inf: f64::NEG_INFINITY,
+        type_char: '\u{a4}',
         type_str: "Hello, world!".into(),
         type_string: "Howdy, universe!".into(),
         type_path: "hello.txt".into(),
@@ -174,6 +222,18 @@ fn expected_root() -> Root {
         arc_map: [(Arc::from("a"), 16)].into(),
         rc_map: [(Rc::from("b"), 54)].into(),
         box_map: [(Box::from("c"), 32)].into(),
+        btree_map: [("x".to_owned(), 1), ("y".to_owned(), 2)].into(),
+        hash_map: [("z".to_owned(), 3)].into(),
+        btree_set: [-1, 5, 9].into(),
+        hash_set: ["tag".to_owned()].into(),
+        vec_deque: [4, 5, 6].into(),
+        tuple: (12, "twelve".into(), true),
+        boxed: Box::new(17),
+        rc_string: Rc::new("shared".into()),
+        arc_node: Arc::new(NamedNode {
+            s: ShortStr::from("Hello, arc!"),
+            v: 5,
+        }),
     }
 }
 
@@ -205,6 +265,22 @@ fn load_and_compare(loader: &mut LogixLoader<impl LogixVfs>) -> Result<()> {
         type_u32,
         type_i64,
         type_u64,
+        type_i128,
+        type_u128,
+        type_isize,
+        type_usize,
+        type_non_zero,
+        type_hex,
+        type_octal,
+        type_binary,
+        type_separated,
+        type_bool_true,
+        type_bool_false,
+        type_f32,
+        type_f64,
+        type_f64_exp,
+        type_f64_inf,
+        type_char,
         type_str,
         type_string,
         type_path,
@@ -238,6 +314,15 @@ fn load_and_compare(loader: &mut LogixLoader<impl LogixVfs>) -> Result<()> {
         arc_map,
         rc_map,
         box_map,
+        btree_map,
+        hash_map,
+        btree_set,
+        hash_set,
+        vec_deque,
+        tuple,
+        boxed,
+        rc_string,
+        arc_node,
     } = loader.load_file("all-types.logix")?;
     assert_eq!(type_i8, expected.type_i8);
     assert_eq!(type_u8, expected.type_u8);
@@ -247,6 +332,22 @@ fn load_and_compare(loader: &mut LogixLoader<impl LogixVfs>) -> Result<()> {
     assert_eq!(type_u32, expected.type_u32);
     assert_eq!(type_i64, expected.type_i64);
     assert_eq!(type_u64, expected.type_u64);
+    assert_eq!(type_i128, expected.type_i128);
+    assert_eq!(type_u128, expected.type_u128);
+    assert_eq!(type_isize, expected.type_isize);
+    assert_eq!(type_usize, expected.type_usize);
+    assert_eq!(type_non_zero, expected.type_non_zero);
+    assert_eq!(type_hex, expected.type_hex);
+    assert_eq!(type_octal, expected.type_octal);
+    assert_eq!(type_binary, expected.type_binary);
+    assert_eq!(type_separated, expected.type_separated);
+    assert_eq!(type_bool_true, expected.type_bool_true);
+    assert_eq!(type_bool_false, expected.type_bool_false);
+    assert_eq!(type_f32, expected.type_f32);
+    assert_eq!(type_f64, expected.type_f64);
+    assert_eq!(type_f64_exp, expected.type_f64_exp);
+    assert_eq!(type_f64_inf, expected.type_f64_inf);
+    assert_eq!(type_char, expected.type_char);
     assert_eq!(type_str, expected.type_str);
     assert_eq!(type_unit, expected.type_unit);
     assert_eq!(type_enum, expected.type_enum);
@@ -304,6 +405,16 @@ fn load_and_compare(loader: &mut LogixLoader<impl LogixVfs>) -> Result<()> {
     assert_eq!(rc_map, expected.rc_map);
     assert_eq!(box_map, expected.box_map);
 
+    assert_eq!(btree_map, expected.btree_map);
+    assert_eq!(hash_map, expected.hash_map);
+    assert_eq!(btree_set, expected.btree_set);
+    assert_eq!(hash_set, expected.hash_set);
+    assert_eq!(vec_deque, expected.vec_deque);
+    assert_eq!(tuple, expected.tuple);
+    assert_eq!(boxed, expected.boxed);
+    assert_eq!(rc_string, expected.rc_string);
+    assert_eq!(arc_node, expected.arc_node);
+
     Ok(())
 }
 
@@ -400,3 +511,25 @@ fn terminating_spaces() -> Result<()> {
     .unwrap();
     load_and_compare(&mut l)
 }
+
+#[test]
+fn write_and_load() -> Result<()> {
+    let (dir, mut l) = temp_loader();
+    let text = LogixWriter::to_file_string(&expected_root()).unwrap();
+    std::fs::write(dir.path().join("all-types.logix"), &text).unwrap();
+    load_and_compare(&mut l)?;
+
+    // Writing the loaded value again should give the exact same text
+    let root: Root = l.load_file("all-types.logix")?;
+    assert_eq!(LogixWriter::to_file_string(&root).unwrap(), text);
+    Ok(())
+}
+
+#[test]
+fn format_and_load() -> Result<()> {
+    let (dir, mut l) = temp_loader();
+    let text = format(ALL_TYPES_FILE.as_bytes()).unwrap();
+    assert_eq!(format(text.as_bytes()).unwrap(), text);
+    std::fs::write(dir.path().join("all-types.logix"), &text).unwrap();
+    load_and_compare(&mut l)
+}
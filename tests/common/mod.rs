@@ -0,0 +1,46 @@
+//! Setup shared by the tests that load a single file
+#![allow(dead_code)] // NOTE(2024.04): Each test only uses some of the helpers
+
+use logix_type::{
+    error::{ParseError, SourceSpan},
+    LogixLoader, LogixType,
+};
+use logix_vfs::RelFs;
+
+/// A loader for a temporary directory that contains `test.logix`
+pub struct TestFile {
+    _root: tempfile::TempDir,
+    pub loader: LogixLoader<RelFs>,
+}
+
+impl TestFile {
+    pub fn new(src: &str) -> Self {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("test.logix"), src).unwrap();
+        let loader = LogixLoader::new(RelFs::new(root.path()));
+        Self {
+            _root: root,
+            loader,
+        }
+    }
+
+    pub fn load<T: LogixType>(&mut self) -> Result<T, ParseError> {
+        self.loader.load_file("test.logix")
+    }
+
+    /// The error as a single line that ends with its location
+    pub fn report(&self, e: &ParseError) -> String {
+        self.loader.report(e).to_string()
+    }
+
+    /// The span of `len` bytes at the 1-based `line` and 0-based `col` in `test.logix`
+    pub fn span(&self, line: usize, col: usize, len: usize) -> SourceSpan {
+        SourceSpan::new_for_test(&self.loader, "test.logix", line, col, len)
+    }
+}
+
+/// Load `src` as a `T`, an error is returned as a single line that ends with its location
+pub fn load<T: LogixType>(src: &str) -> Result<T, String> {
+    let mut file = TestFile::new(src);
+    file.load().map_err(|e| file.report(&e))
+}
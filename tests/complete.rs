@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use logix_type::{
+    complete::{complete, Completion, CompletionKind},
+    LogixType,
+};
+
+/// The settings of the editor
+#[derive(LogixType)]
+struct Settings {
+    /// The name of the user
+    name: String,
+    /// The color theme
+    theme: Theme,
+    font: Option<Font>,
+    tags: Vec<Theme>,
+    wrap: bool,
+    log: PathBuf,
+}
+
+#[derive(LogixType)]
+enum Theme {
+    /// A dark background
+    Dark,
+    Light,
+    Custom {
+        name: String,
+        base: Box<Theme>,
+    },
+}
+
+/// The font used for text
+#[derive(LogixType)]
+struct Font {
+    family: String,
+    size: u32,
+}
+
+/// Returns the completions at `|` in `src`
+fn texts(src: &str) -> Vec<&'static str> {
+    let offset = src.find('|').unwrap();
+    let src = src.replacen('|', "", 1);
+    complete::<Settings>(src.as_bytes(), offset)
+        .items
+        .into_iter()
+        .map(|c| c.text)
+        .collect()
+}
+
+#[test]
+fn members() {
+    assert_eq!(
+        texts("Settings {\n  |\n}\n"),
+        ["name", "theme", "font", "tags", "wrap", "log"]
+    );
+    // Members that are already used are left out, also those after the cursor
+    assert_eq!(
+        texts("Settings {\n  name: \"x\"\n  |\n  tags: []\n}\n"),
+        ["theme", "font", "wrap", "log"]
+    );
+    // The member being written is not used yet
+    assert_eq!(
+        texts("Settings {\n  name: \"x\"\n  th|\n}\n"),
+        ["theme", "font", "tags", "wrap", "log"]
+    );
+    assert_eq!(
+        texts("Settings {\n  th|eme: Dark\n}\n"),
+        ["name", "theme", "font", "tags", "wrap", "log"]
+    );
+    assert_eq!(
+        texts("Settings {\n  font: Font {\n    size: 12\n    |\n  }\n}\n"),
+        ["family"]
+    );
+    // Struct variants have members too
+    assert_eq!(
+        texts("Settings {\n  theme: Custom {\n    |\n  }\n}\n"),
+        ["name", "base"]
+    );
+}
+
+#[test]
+fn values() {
+    assert_eq!(texts("|"), ["Settings"]);
+    assert_eq!(texts("// The settings\nSett|"), ["Settings"]);
+    assert_eq!(texts("Settings {\n  font: |\n}\n"), ["Font"]);
+    assert_eq!(
+        texts("Settings {\n  theme: D|\n}\n"),
+        ["Dark", "Light", "Custom"]
+    );
+    assert_eq!(
+        texts("Settings {\n  tags: [Dark, |]\n}\n"),
+        ["Dark", "Light", "Custom"]
+    );
+    assert_eq!(texts("Settings {\n  wrap: |\n}\n"), ["true", "false"]);
+    assert_eq!(
+        texts("Settings {\n  name: |\n}\n"),
+        ["\"", "#esc\"", "#raw\"", "#txt\"", "@include"]
+    );
+    assert_eq!(
+        texts("Settings {\n  name: #ra|\n}\n"),
+        ["\"", "#esc\"", "#raw\"", "#txt\"", "@include"]
+    );
+    assert_eq!(
+        texts("Settings {\n  name: @inc|\n}\n"),
+        ["\"", "#esc\"", "#raw\"", "#txt\"", "@include"]
+    );
+    assert_eq!(
+        texts("Settings {\n  log: |\n}\n"),
+        ["\"", "#esc\"", "#raw\"", "#txt\""]
+    );
+}
+
+#[test]
+fn nothing() {
+    let none: [&str; 0] = [];
+    // Inside comments and strings
+    assert_eq!(texts("// |\nSettings {\n}\n"), none);
+    assert_eq!(texts("Settings {\n  name: \"a|b\"\n}\n"), none);
+    // Where a number or something other than a name goes
+    assert_eq!(texts("Settings {\n  font: Font {\n    size: 1|\n"), none);
+    assert_eq!(texts("Settings {\n  theme: Dark |\n}\n"), none);
+    assert_eq!(texts("Settings {\n}\n|"), none);
+}
+
+#[test]
+fn details() {
+    let src = "Settings {\n  theme: Da\n  font: \n}\n";
+    let off = src.find("Da").unwrap();
+    let res = complete::<Settings>(src.as_bytes(), off + 2);
+    assert_eq!(res.range, off..off + 2);
+    assert_eq!(
+        res.items[0],
+        Completion {
+            kind: CompletionKind::Variant,
+            text: "Dark",
+            detail: "Theme",
+            doc: "A dark background",
+        }
+    );
+
+    let off = src.find("font: ").unwrap() + 6;
+    let res = complete::<Settings>(src.as_bytes(), off);
+    assert_eq!(res.range, off..off);
+    assert_eq!(
+        res.items,
+        [Completion {
+            kind: CompletionKind::Type,
+            text: "Font",
+            detail: "",
+            doc: "The font used for text",
+        }]
+    );
+
+    let res = complete::<Settings>(b"Settings {\n  \n}\n", 13);
+    assert_eq!(
+        res.items[1],
+        Completion {
+            kind: CompletionKind::Member,
+            text: "theme",
+            detail: "Theme",
+            doc: "The color theme",
+        }
+    );
+}
@@ -0,0 +1,146 @@
+use logix_type::{
+    cst::{Element, Node, NodeKind, TokenKind},
+    token::StrTag,
+};
+
+fn round_trip(src: &[u8]) -> Node {
+    let tree = Node::parse(src);
+    assert_eq!(tree.to_bytes(), src, "{tree:#?}");
+    assert_eq!(tree.text_len(), src.len());
+    tree
+}
+
+/// A compact description of the tree, tokens are written as their text except for trivia
+fn outline(node: &Node) -> String {
+    let items = node
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            Element::Node(node) => Some(outline(node)),
+            Element::Token(token) if token.kind.is_trivia() => None,
+            Element::Token(token) => Some(token.text.to_string()),
+        })
+        .collect::<Vec<_>>();
+    format!("{:?}[{}]", node.kind, items.join(" "))
+}
+
+#[test]
+fn structure() {
+    let src = concat!(
+        "// The config\n",
+        "Config {\n",
+        "  name: \"test\" // trailing\n",
+        "  size: Size(10, 20)\n",
+        "  tags: [\n",
+        "    #raw\"a\"#,\n",
+        "    ##txt\"b\"##\n",
+        "  ]\n",
+        "  /* nested /* block */ comment */\n",
+        "  extra: {\n",
+        "    a: (1, true)\n",
+        "  }\n",
+        "  text: @include(\"text.txt\")\n",
+        "  kind: Variant\n",
+        "}\n",
+    );
+    let tree = round_trip(src.as_bytes());
+    assert_eq!(
+        outline(&tree),
+        concat!(
+            "File[Struct[Config { ",
+            "Member[name : \"test\"] ",
+            "Member[size : Tuple[Size ( 10 , 20 )]] ",
+            "Member[tags : List[[ #raw\"a\"# , ##txt\"b\"## ]]] ",
+            "Member[extra : Map[{ Member[a : Tuple[( 1 , true )]] }]] ",
+            "Member[text : Include[@include ( \"text.txt\" )]] ",
+            "Member[kind : Variant] ",
+            "}]]",
+        )
+    );
+
+    let strings = tree
+        .tokens()
+        .filter_map(|token| match token.kind {
+            TokenKind::Str { tag, hashes } => Some((tag, hashes)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        strings,
+        vec![
+            (StrTag::Raw, 0),
+            (StrTag::Raw, 1),
+            (StrTag::Txt, 2),
+            (StrTag::Raw, 0),
+        ]
+    );
+
+    let comments = tree
+        .tokens()
+        .filter(|token| token.kind == TokenKind::Comment)
+        .map(|token| token.text.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        comments,
+        vec![
+            "// The config",
+            "// trailing",
+            "/* nested /* block */ comment */"
+        ]
+    );
+}
+
+#[test]
+fn invalid_input() {
+    let tree = round_trip(b"Config {\n  a: 1 ]\n  : 2\n  \"key\": $\n  b: (1, 2}\n}\n}");
+    assert_eq!(
+        outline(&tree),
+        concat!(
+            "File[Struct[Config { ",
+            "Member[a : 1] Error[]] ",
+            "Error[:] Error[2] ",
+            "Error[\"key\"] Error[:] $ ",
+            "Member[b : Tuple[( 1 , 2]] ",
+            "}] ",
+            "Error[}] Error[}]]",
+        )
+    );
+
+    let tree = round_trip(b"Config {\n  a: \"unterminated\n  b: #txt\"x\n\n}");
+    assert_eq!(tree.children.len(), 1);
+
+    for src in [
+        &b""[..],
+        b"\n\n",
+        b"{",
+        b"}",
+        b"@include",
+        b"@include(",
+        b"@what(1)",
+        b"a: b: c",
+        b"[1, 2 /* open",
+        b"\"bad \x8e utf-8\" // \xff\n",
+        b"\r\nA {\r\n}\r\n",
+        b"<a, b>",
+    ] {
+        round_trip(src);
+    }
+}
+
+#[test]
+fn deep_nesting() {
+    let src = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+    let tree = round_trip(src.as_bytes());
+
+    let Some(Element::Node(first)) = tree.children.first() else {
+        panic!("Expected a node");
+    };
+    let mut node = first;
+    let mut depth = 1;
+    while let Some(Element::Node(child)) = node.children.get(1) {
+        depth += 1;
+        node = child;
+    }
+    assert_eq!(node.kind, NodeKind::Error);
+    assert_eq!(depth, 129);
+}
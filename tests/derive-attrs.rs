@@ -0,0 +1,249 @@
+use std::marker::PhantomData;
+
+use logix_type::{
+    type_trait::{LogixMemberDescriptor, LogixValueDescriptor},
+    LogixType, LogixWriter,
+};
+
+use common::load;
+
+mod common;
+
+#[derive(LogixType, PartialEq, Debug)]
+struct Config {
+    name: String,
+    #[logix(default)]
+    verbose: bool,
+    #[logix(default = 8080)]
+    port: u16,
+    #[logix(skip)]
+    cache: Vec<u32>,
+    #[logix(skip, default = String::from("runtime"))]
+    state: String,
+}
+
+#[test]
+fn defaults() {
+    assert_eq!(
+        load::<Config>("Config {\n  name: \"a\"\n}\n").unwrap(),
+        Config {
+            name: "a".into(),
+            verbose: false,
+            port: 8080,
+            cache: vec![],
+            state: "runtime".into(),
+        }
+    );
+    assert_eq!(
+        load::<Config>("Config {\n  name: \"a\"\n  verbose: true\n  port: 80\n}\n").unwrap(),
+        Config {
+            name: "a".into(),
+            verbose: true,
+            port: 80,
+            cache: vec![],
+            state: "runtime".into(),
+        }
+    );
+}
+
+#[test]
+fn skipped_is_unknown() {
+    let e = load::<Config>("Config {\n  name: \"a\"\n  cache: [1]\n}\n").unwrap_err();
+    assert_eq!(
+        e,
+        "Unexpected identifier while parsing `Config`, expected one of `}`, `name`, `verbose`, or `port` in test.logix:3:2"
+    );
+}
+
+#[test]
+fn write_skips() {
+    let value = Config {
+        name: "a".into(),
+        verbose: false,
+        port: 80,
+        cache: vec![1, 2],
+        state: "other".into(),
+    };
+    assert_eq!(
+        LogixWriter::to_file_string(&value).unwrap(),
+        "Config {\n  name: \"a\"\n  verbose: false\n  port: 80\n}\n"
+    );
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+struct OnlySkipped<'a, T: LogixType> {
+    a: u32,
+    #[logix(skip)]
+    _p: PhantomData<&'a T>,
+}
+
+#[test]
+fn generic_only_in_skipped() {
+    assert_eq!(
+        load::<OnlySkipped<String>>("OnlySkipped {\n  a: 1\n}\n").unwrap(),
+        OnlySkipped {
+            a: 1,
+            _p: PhantomData,
+        }
+    );
+}
+
+#[test]
+fn descriptor() {
+    let LogixValueDescriptor::Struct { members } = &Config::descriptor().value else {
+        panic!("Expected a struct");
+    };
+    assert_eq!(
+        members
+            .iter()
+            .map(
+                |LogixMemberDescriptor {
+                     name, optional, ty, ..
+                 }| (*name, *optional, ty.name)
+            )
+            .collect::<Vec<_>>(),
+        vec![
+            ("name", false, "string"),
+            ("verbose", true, "bool"),
+            ("port", true, "u16"),
+        ]
+    );
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+#[logix(rename = "server-config", rename_all = "kebab-case")]
+struct ServerConfig {
+    listen_port: u16,
+    #[logix(rename = "host", alias = "hostname", alias = "address")]
+    host_name: String,
+    log_level: Level,
+    backend: Backend,
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+#[logix(rename_all = "snake_case")]
+enum Level {
+    VeryQuiet,
+    #[logix(rename = "loud", alias = "Noisy")]
+    Verbose,
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+enum Backend {
+    #[logix(rename = "file", rename_all = "camelCase")]
+    File { file_path: String },
+    #[logix(rename_all = "SCREAMING-KEBAB-CASE")]
+    Memory { max_size: u32 },
+}
+
+#[test]
+fn renamed() {
+    let expected = ServerConfig {
+        listen_port: 80,
+        host_name: "localhost".into(),
+        log_level: Level::VeryQuiet,
+        backend: Backend::File {
+            file_path: "/tmp/data".into(),
+        },
+    };
+    let src = concat!(
+        "server-config {\n",
+        "  listen-port: 80\n",
+        "  host: \"localhost\"\n",
+        "  log-level: very_quiet\n",
+        "  backend: file {\n",
+        "    filePath: \"/tmp/data\"\n",
+        "  }\n",
+        "}\n",
+    );
+    assert_eq!(load::<ServerConfig>(src).unwrap(), expected);
+    assert_eq!(LogixWriter::to_file_string(&expected).unwrap(), src);
+    assert_eq!(ServerConfig::descriptor().name, "server-config");
+}
+
+#[test]
+fn aliases() {
+    let src = concat!(
+        "server-config {\n",
+        "  listen-port: 80\n",
+        "  hostname: \"localhost\"\n",
+        "  log-level: Noisy\n",
+        "  backend: Memory {\n",
+        "    MAX-SIZE: 10\n",
+        "  }\n",
+        "}\n",
+    );
+    assert_eq!(
+        load::<ServerConfig>(src).unwrap(),
+        ServerConfig {
+            listen_port: 80,
+            host_name: "localhost".into(),
+            log_level: Level::Verbose,
+            backend: Backend::Memory { max_size: 10 },
+        }
+    );
+
+    let e = load::<ServerConfig>(&src.replace("hostname", "host:\"a\"\n  address")).unwrap_err();
+    assert_eq!(
+        e,
+        "Duplicate struct member `host` while parsing `server-config` in test.logix:4:2"
+    );
+
+    // The original names are not accepted once renamed
+    let e = load::<ServerConfig>(&src.replace("Noisy", "Verbose")).unwrap_err();
+    assert_eq!(
+        e,
+        "Unexpected identifier while parsing `Level`, expected either `very_quiet` or `loud` in test.logix:4:13"
+    );
+}
+
+/// A documented type
+///
+///   With an indented second paragraph
+#[derive(LogixType, PartialEq, Debug)]
+struct Documented {
+    /// The first member
+    first: u32,
+    second: Vec<u32>,
+    /** Block comment */
+    third: DocumentedEnum,
+}
+
+/// An enum
+#[derive(LogixType, PartialEq, Debug)]
+enum DocumentedEnum {
+    /// Nothing to see
+    Unit,
+    Undocumented(u32),
+}
+
+#[test]
+fn docs() {
+    let desc = Documented::descriptor();
+    assert_eq!(
+        desc.doc,
+        "A documented type\n\n  With an indented second paragraph"
+    );
+
+    let LogixValueDescriptor::Struct { members } = &desc.value else {
+        panic!("Expected a struct");
+    };
+    assert_eq!(
+        members.iter().map(|m| (m.name, m.doc)).collect::<Vec<_>>(),
+        vec![
+            ("first", "The first member"),
+            ("second", ""),
+            ("third", "Block comment"),
+        ]
+    );
+
+    let desc = DocumentedEnum::descriptor();
+    assert_eq!(desc.doc, "An enum");
+    let LogixValueDescriptor::Enum { variants } = &desc.value else {
+        panic!("Expected an enum");
+    };
+    assert_eq!(
+        variants.iter().map(|v| (v.name, v.doc)).collect::<Vec<_>>(),
+        vec![("Unit", "Nothing to see"), ("Undocumented", "")]
+    );
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet, VecDeque},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use logix_type::{
+    error::{ParseError, WriteResult},
+    type_trait::{type_descriptor, LogixTypeDescriptor, LogixValueDescriptor, Value},
+    types::Map,
+    LogixParser, LogixType, LogixWriter,
+};
+use logix_vfs::LogixVfs;
+
+#[derive(LogixType)]
+#[allow(dead_code)]
+struct Item {
+    v: u32,
+}
+
+fn value(desc: &'static LogixTypeDescriptor) -> &'static LogixValueDescriptor {
+    &desc.value
+}
+
+#[test]
+fn lists() {
+    assert_eq!(
+        value(Vec::<u32>::descriptor()),
+        &LogixValueDescriptor::List {
+            item: u32::descriptor()
+        }
+    );
+    assert_eq!(
+        value(Vec::<String>::descriptor()),
+        &LogixValueDescriptor::List {
+            item: String::descriptor()
+        }
+    );
+    assert_eq!(VecDeque::<u32>::descriptor(), Vec::<u32>::descriptor());
+    assert_eq!(
+        value(<[Item; 3]>::descriptor()),
+        &LogixValueDescriptor::Array {
+            len: 3,
+            item: Item::descriptor()
+        }
+    );
+    assert_eq!(
+        value(HashSet::<i8>::descriptor()),
+        &LogixValueDescriptor::Set {
+            item: i8::descriptor()
+        }
+    );
+}
+
+/// Generic code doesn't need `T: 'static` to use the descriptors
+fn list_of<T: LogixType>() -> &'static LogixTypeDescriptor {
+    Vec::<Option<(T, T)>>::descriptor()
+}
+
+#[test]
+fn not_static() {
+    assert_eq!(list_of::<u32>(), Vec::<Option<(u32, u32)>>::descriptor());
+    assert_ne!(list_of::<u32>(), list_of::<i32>());
+}
+
+#[test]
+fn maps() {
+    let expected = LogixValueDescriptor::Map {
+        key: String::descriptor(),
+        value: Item::descriptor(),
+    };
+    assert_eq!(value(Map::<Item>::descriptor()), &expected);
+    assert_eq!(value(BTreeMap::<String, Item>::descriptor()), &expected);
+    assert_eq!(Map::<Item>::descriptor().name, "map");
+}
+
+#[test]
+fn empty_defaults() {
+    // Sets and maps can both be left out, and are empty then
+    assert_eq!(Map::<Item>::default_value().map(|m| m.len()), Some(0));
+    assert_eq!(
+        BTreeMap::<String, u8>::default_value(),
+        Some(BTreeMap::new())
+    );
+    assert_eq!(HashSet::<u8>::default_value(), Some(HashSet::new()));
+    assert_eq!(BTreeSet::<u8>::default_value(), Some(BTreeSet::new()));
+}
+
+#[test]
+fn optional() {
+    assert_eq!(
+        value(Option::<Vec<bool>>::descriptor()),
+        &LogixValueDescriptor::Optional {
+            inner: Vec::<bool>::descriptor()
+        }
+    );
+    let LogixValueDescriptor::Optional { inner } = value(Option::<Vec<bool>>::descriptor()) else {
+        unreachable!()
+    };
+    assert_eq!(
+        value(inner),
+        &LogixValueDescriptor::List {
+            item: bool::descriptor()
+        }
+    );
+}
+
+/// The first call to `descriptor` panics
+struct Flaky;
+
+static FLAKY_CALLED: AtomicBool = AtomicBool::new(false);
+
+impl LogixType for Flaky {
+    fn descriptor() -> &'static LogixTypeDescriptor {
+        type_descriptor::<Self>("Flaky", || {
+            assert!(FLAKY_CALLED.swap(true, Ordering::Relaxed), "first call");
+            LogixTypeDescriptor {
+                name: "Flaky",
+                doc: "",
+                value: LogixValueDescriptor::Native,
+            }
+        })
+    }
+
+    fn default_value() -> Option<Self> {
+        None
+    }
+
+    fn logix_parse<FS: LogixVfs>(_: &mut LogixParser<FS>) -> Result<Value<Self>, ParseError> {
+        unimplemented!()
+    }
+
+    fn logix_write(&self, _: &mut LogixWriter) -> WriteResult {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn panic_in_init() {
+    assert!(std::panic::catch_unwind(Flaky::descriptor).is_err());
+    // The type is no longer being described, so this is not a back-reference
+    assert_eq!(value(Flaky::descriptor()), &LogixValueDescriptor::Native);
+}
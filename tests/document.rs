@@ -0,0 +1,252 @@
+use logix_type::{
+    document::{Document, EditError},
+    types::{Map, ShortStr},
+    LogixType,
+};
+
+#[derive(LogixType)]
+struct Font {
+    family: String,
+    size: u32,
+}
+
+const SRC: &str = concat!(
+    "// User settings\n",
+    "Settings {\n",
+    "    editor: Editor {\n",
+    "        theme: \"light\" // picked by the installer\n",
+    "\n",
+    "        /* Nothing below here is used yet */\n",
+    "        tab-width: 4\n",
+    "    }\n",
+    "    plugins: [\"git\", \"lsp\"]\n",
+    "    recent: [\n",
+    "        \"a.logix\",\n",
+    "        \"b.logix\",\n",
+    "    ]\n",
+    "    aliases: {\n",
+    "        ll: \"ls -l\"\n",
+    "    }\n",
+    "    size: Size(80, 24)\n",
+    "}\n",
+);
+
+fn doc() -> Document {
+    let doc = Document::parse(SRC.as_bytes());
+    assert_eq!(doc.to_string(), SRC);
+    doc
+}
+
+fn text(doc: &Document, path: &str) -> String {
+    match doc.get(path).unwrap() {
+        logix_type::cst::Element::Node(node) => node.to_string(),
+        logix_type::cst::Element::Token(token) => token.text.to_string(),
+    }
+}
+
+/// Apply `f` and check that the document became `src` with `from` replaced by `to`
+fn check_edit(f: impl FnOnce(&mut Document), from: &str, to: &str) {
+    let mut doc = doc();
+    f(&mut doc);
+    assert!(SRC.contains(from), "{from:?}");
+    assert_eq!(doc.to_string(), SRC.replacen(from, to, 1));
+    // The result is still a valid document
+    assert_eq!(
+        Document::parse(&doc.to_bytes()).to_string(),
+        doc.to_string()
+    );
+}
+
+#[test]
+fn get() {
+    let doc = doc();
+    assert_eq!(text(&doc, "editor.theme"), "\"light\"");
+    assert_eq!(text(&doc, "editor.tab-width"), "4");
+    assert_eq!(text(&doc, "plugins.1"), "\"lsp\"");
+    assert_eq!(text(&doc, "recent.0"), "\"a.logix\"");
+    assert_eq!(text(&doc, "aliases.ll"), "\"ls -l\"");
+    assert_eq!(text(&doc, "size.1"), "24");
+    assert!(text(&doc, "").starts_with("Settings {"));
+
+    assert_eq!(
+        doc.get("editor.font").unwrap_err(),
+        EditError::NotFound("editor.font".into())
+    );
+    assert_eq!(
+        doc.get("plugins.2").unwrap_err(),
+        EditError::NotFound("plugins.2".into())
+    );
+    assert_eq!(
+        doc.get("editor.theme.x").unwrap_err(),
+        EditError::NotAContainer("editor.theme".into())
+    );
+    assert_eq!(
+        doc.get("editor..theme").unwrap_err(),
+        EditError::InvalidPath("editor..theme".into())
+    );
+}
+
+#[test]
+fn set() {
+    check_edit(
+        |doc| doc.set("editor.theme", &ShortStr::from("dark")).unwrap(),
+        "\"light\" // picked",
+        "\"dark\" // picked",
+    );
+    check_edit(
+        |doc| doc.set("plugins.0", &String::from("fmt")).unwrap(),
+        "[\"git\",",
+        "[\"fmt\",",
+    );
+    check_edit(
+        |doc| doc.set("size.0", &120u32).unwrap(),
+        "Size(80,",
+        "Size(120,",
+    );
+
+    // Multi-line values are indented like the rest of the file
+    check_edit(
+        |doc| {
+            doc.set(
+                "aliases",
+                &Map::<String>::from_iter([("gs".into(), "git status".to_string())]),
+            )
+            .unwrap()
+        },
+        "{\n        ll: \"ls -l\"\n    }",
+        "{\n        gs: \"git status\"\n    }",
+    );
+
+    // A missing member is inserted
+    check_edit(
+        |doc| doc.set("editor.line-numbers", &true).unwrap(),
+        "        tab-width: 4\n",
+        "        tab-width: 4\n        line-numbers: true\n",
+    );
+}
+
+#[test]
+fn insert() {
+    check_edit(
+        |doc| {
+            doc.insert(
+                "editor.font",
+                &Font {
+                    family: "mono".into(),
+                    size: 12,
+                },
+            )
+            .unwrap()
+        },
+        "        tab-width: 4\n",
+        concat!(
+            "        tab-width: 4\n",
+            "        font: Font {\n",
+            "            family: \"mono\"\n",
+            "            size: 12\n",
+            "        }\n",
+        ),
+    );
+    check_edit(
+        |doc| doc.insert("aliases.la", &String::from("ls -a")).unwrap(),
+        "        ll: \"ls -l\"\n",
+        "        ll: \"ls -l\"\n        la: \"ls -a\"\n",
+    );
+
+    // Lists on one line stay on one line
+    check_edit(
+        |doc| doc.insert("plugins.1", &String::from("fmt")).unwrap(),
+        "[\"git\", \"lsp\"]",
+        "[\"git\", \"fmt\", \"lsp\"]",
+    );
+    check_edit(
+        |doc| doc.insert("plugins.2", &String::from("fmt")).unwrap(),
+        "[\"git\", \"lsp\"]",
+        "[\"git\", \"lsp\", \"fmt\"]",
+    );
+
+    // Lists on several lines get a new line, keeping the trailing comma style
+    check_edit(
+        |doc| doc.insert("recent.0", &String::from("new.logix")).unwrap(),
+        "        \"a.logix\",\n",
+        "        \"new.logix\",\n        \"a.logix\",\n",
+    );
+    check_edit(
+        |doc| doc.insert("recent.2", &String::from("c.logix")).unwrap(),
+        "        \"b.logix\",\n",
+        "        \"b.logix\",\n        \"c.logix\",\n",
+    );
+
+    let mut doc = doc();
+    assert_eq!(
+        doc.insert("editor.theme", &1u32).unwrap_err(),
+        EditError::AlreadyExists("editor.theme".into())
+    );
+    assert_eq!(
+        doc.insert("plugins.5", &1u32).unwrap_err(),
+        EditError::NotFound("plugins.5".into())
+    );
+    assert_eq!(
+        doc.insert("size.0", &1u32).unwrap_err(),
+        EditError::NotAContainer("size".into())
+    );
+    assert_eq!(doc.to_string(), SRC);
+}
+
+#[test]
+fn insert_empty() {
+    let mut doc = Document::parse(b"Root {\n\ta: {}\n\tb: []\n\tc: {\n\t}\n}\n");
+    doc.insert("a.x", &1u32).unwrap();
+    doc.insert("b.0", &2u32).unwrap();
+    doc.insert("b.1", &3u32).unwrap();
+    doc.insert("c.y", &4u32).unwrap();
+    assert_eq!(
+        doc.to_string(),
+        "Root {\n\ta: {\n\t\tx: 1\n\t}\n\tb: [2, 3]\n\tc: {\n\t\ty: 4\n\t}\n}\n"
+    );
+}
+
+#[test]
+fn remove() {
+    check_edit(
+        |doc| doc.remove("editor.theme").unwrap(),
+        "        theme: \"light\" // picked by the installer\n",
+        "",
+    );
+    check_edit(
+        |doc| doc.remove("editor.tab-width").unwrap(),
+        "        tab-width: 4\n",
+        "",
+    );
+    check_edit(
+        |doc| doc.remove("plugins.0").unwrap(),
+        "[\"git\", \"lsp\"]",
+        "[\"lsp\"]",
+    );
+    check_edit(
+        |doc| doc.remove("plugins.1").unwrap(),
+        "[\"git\", \"lsp\"]",
+        "[\"git\"]",
+    );
+    check_edit(
+        |doc| doc.remove("recent.1").unwrap(),
+        "        \"b.logix\",\n",
+        "",
+    );
+    check_edit(
+        |doc| doc.remove("size").unwrap(),
+        "    size: Size(80, 24)\n",
+        "",
+    );
+
+    let mut doc = doc();
+    assert_eq!(
+        doc.remove("size.0").unwrap_err(),
+        EditError::NotAContainer("size".into())
+    );
+    assert_eq!(
+        doc.remove("editor.font").unwrap_err(),
+        EditError::NotFound("editor.font".into())
+    );
+    assert_eq!(doc.to_string(), SRC);
+}
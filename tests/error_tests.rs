@@ -2,9 +2,11 @@ use std::{fmt, path::PathBuf};
 
 use logix_type::{
     error::{
-        EscStrError, IncludeError, ParseError, PathError, SourceSpan, TokenError, Wanted, Warn,
+        EscStrError, IncludeError, ParseError, PathError, Report, SourceSpan, TokenError, Wanted,
+        Warn,
     },
     token::{Brace, Delim, StrTag, StrTagSuffix, Token},
+    type_trait::LogixTypeDescriptor,
     types::{Data, ExecutablePath, FullPath, Map, NameOnlyPath, RelPath, ShortStr, ValidPath},
     LogixLoader, LogixType,
 };
@@ -19,6 +21,50 @@ struct GenStruct<T: LogixType + fmt::Debug> {
     bbbb: T,
 }
 
+#[derive(LogixType, Debug)]
+#[allow(dead_code)]
+struct GenList<T: LogixType + fmt::Debug> {
+    items: Vec<T>,
+}
+
+#[test]
+fn generic_descriptors() {
+    use logix_type::type_trait::LogixValueDescriptor;
+
+    let member_types = |desc: &'static LogixTypeDescriptor| {
+        let LogixValueDescriptor::Struct { members } = &desc.value else {
+            panic!("Expected a struct");
+        };
+        members.iter().map(|m| (m.name, m.ty)).collect::<Vec<_>>()
+    };
+
+    // Initialise in both orders, the first one used to win for every instantiation
+    let with_u32 = member_types(GenStruct::<u32>::descriptor());
+    let with_string = member_types(GenStruct::<String>::descriptor());
+    assert_eq!(
+        with_u32,
+        vec![("aaa", u32::descriptor()), ("bbbb", u32::descriptor())]
+    );
+    assert_eq!(
+        with_string,
+        vec![("aaa", u32::descriptor()), ("bbbb", String::descriptor())]
+    );
+    assert!(std::ptr::eq(
+        GenStruct::<u32>::descriptor(),
+        GenStruct::<u32>::descriptor()
+    ));
+    assert_eq!(GenStruct::<Struct>::descriptor().name, "GenStruct");
+
+    assert_eq!(
+        member_types(GenList::<bool>::descriptor()),
+        vec![("items", Vec::<bool>::descriptor())]
+    );
+    assert_eq!(
+        member_types(GenList::<Tuple>::descriptor()),
+        vec![("items", Vec::<Tuple>::descriptor())]
+    );
+}
+
 struct Loader {
     root: tempfile::TempDir,
     loader: LogixLoader<RelFs>,
@@ -48,11 +94,28 @@ impl Loader {
 
         let e = self.loader.load_file::<T>(name).unwrap_err();
 
-        println!("**** DISPLAY START ****\n{e}\n**** DISPLAY END ******\n");
-        println!("*** DEBUG START ******\n{e:?}\n**** DEBUG END ********\n");
+        let report = self.loader.report(&e);
+        println!("**** DISPLAY START ****\n{report}\n**** DISPLAY END ******\n");
+        println!("*** DEBUG START ******\n{report:?}\n**** DEBUG END ********\n");
         e
     }
 
+    /// The error rendered with the source around it
+    fn debval<E>(&self, e: &E) -> String
+    where
+        for<'a> Report<'a, E>: fmt::Debug,
+    {
+        strip_ansi_escapes::strip_str(format!("{:?}", self.loader.report(e)))
+    }
+
+    /// The error rendered as one line
+    fn disval<E>(&self, e: &E) -> String
+    where
+        for<'a> Report<'a, E>: fmt::Display,
+    {
+        strip_ansi_escapes::strip_str(self.loader.report(e).to_string())
+    }
+
     fn parse_struct(&mut self, name: &str) -> ParseError {
         self.parse_file::<Struct>(name)
     }
@@ -71,14 +134,6 @@ struct Struct {
 #[derive(LogixType, PartialEq, Debug)]
 struct Tuple(u32, String);
 
-fn debval(s: &impl fmt::Debug) -> String {
-    strip_ansi_escapes::strip_str(format!("{s:?}"))
-}
-
-fn disval(s: &impl fmt::Display) -> String {
-    strip_ansi_escapes::strip_str(s.to_string())
-}
-
 #[test]
 fn empty_file() {
     let mut l = Loader::init().with_file("test.logix", b"");
@@ -95,7 +150,7 @@ fn empty_file() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected end of file while parsing `Struct`\n",
@@ -105,7 +160,7 @@ fn empty_file() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected end of file while parsing `Struct`, expected `Struct` in test.logix:1:0"
     );
 }
@@ -129,7 +184,7 @@ fn two_types() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected identifier while parsing `Struct`\n",
@@ -143,7 +198,7 @@ fn two_types() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected identifier while parsing `Struct`, expected end of file in test.logix:5:0"
     );
 }
@@ -164,7 +219,7 @@ fn unclosed_curly_brace() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected end of file while parsing `Struct`\n",
@@ -176,7 +231,7 @@ fn unclosed_curly_brace() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected end of file while parsing `Struct`, expected newline in test.logix:1:8"
     );
 }
@@ -197,7 +252,7 @@ fn no_newline() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected `}` while parsing `Struct`\n",
@@ -209,7 +264,7 @@ fn no_newline() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected `}` while parsing `Struct`, expected newline in test.logix:1:8"
     );
 }
@@ -229,7 +284,7 @@ fn no_members() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Missing struct member while parsing `Struct`\n",
@@ -242,7 +297,7 @@ fn no_members() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Missing struct member `aaa` while parsing `Struct` in test.logix:2:0"
     );
 }
@@ -262,7 +317,7 @@ fn one_member_a() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Missing struct member while parsing `Struct`\n",
@@ -275,7 +330,7 @@ fn one_member_a() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Missing struct member `bbbb` while parsing `Struct` in test.logix:3:0"
     );
 }
@@ -295,7 +350,7 @@ fn one_member_b() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Missing struct member while parsing `Struct`\n",
@@ -308,7 +363,7 @@ fn one_member_b() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Missing struct member `aaa` while parsing `Struct` in test.logix:3:0"
     );
 }
@@ -328,7 +383,7 @@ fn duplicate_member() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Duplicate struct member while parsing `Struct`\n",
@@ -342,7 +397,7 @@ fn duplicate_member() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Duplicate struct member `aaa` while parsing `Struct` in test.logix:3:2"
     );
 }
@@ -363,7 +418,7 @@ fn one_member_tuple_want_comma() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected `)` while parsing `Tuple`\n",
@@ -375,7 +430,7 @@ fn one_member_tuple_want_comma() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected `)` while parsing `Tuple`, expected `,` in test.logix:1:8"
     );
 }
@@ -396,7 +451,7 @@ fn one_member_tuple_want_litstr() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected `)` while parsing `string`\n",
@@ -408,7 +463,7 @@ fn one_member_tuple_want_litstr() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected `)` while parsing `string`, expected string in test.logix:1:10"
     );
 }
@@ -427,7 +482,7 @@ fn unknown_character_tilde() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -439,7 +494,7 @@ fn unknown_character_tilde() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected character '~' in test.logix:1:10"
     );
 }
@@ -458,7 +513,7 @@ fn unknown_character_smiley() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -470,7 +525,7 @@ fn unknown_character_smiley() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected character '\u{01f60e}' in test.logix:1:10"
     );
 }
@@ -489,7 +544,7 @@ fn duplicate_map_entry() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Duplicate entry `a` while parsing `Map`\n",
@@ -503,7 +558,7 @@ fn duplicate_map_entry() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Duplicate entry `a` while parsing `Map`, overwrites the previous entry in test.logix:3:2"
     );
 }
@@ -540,7 +595,7 @@ fn missing_members4() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected identifier while parsing `Struct4`\n",
@@ -554,7 +609,7 @@ fn missing_members4() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected identifier while parsing `Struct4`, expected one of `}`, `_a`, `_b`, `_c`, or `_d` in test.logix:2:2"
     );
 }
@@ -585,7 +640,7 @@ fn missing_members1() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected identifier while parsing `Struct1`\n",
@@ -599,7 +654,7 @@ fn missing_members1() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected identifier while parsing `Struct1`, expected either `}` or `_a` in test.logix:2:2"
     );
 }
@@ -625,7 +680,7 @@ fn missing_members0() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected identifier while parsing `Struct0`\n",
@@ -639,7 +694,7 @@ fn missing_members0() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected identifier while parsing `Struct0`, expected `}` in test.logix:2:2"
     );
 }
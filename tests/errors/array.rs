@@ -16,7 +16,7 @@ fn too_small_array() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected `]` while parsing `Array`\n",
@@ -29,7 +29,7 @@ fn too_small_array() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected `]` while parsing `Array`, expected item in test.logix:5:0"
     );
 }
@@ -53,7 +53,7 @@ fn too_big_array() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected number while parsing `Array`\n",
@@ -67,7 +67,7 @@ fn too_big_array() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected number while parsing `Array`, expected `]` in test.logix:4:2"
     );
 }
@@ -88,7 +88,7 @@ fn truncated_array_last_item() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected end of file while parsing `Array`\n",
@@ -101,7 +101,7 @@ fn truncated_array_last_item() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected end of file while parsing `Array`, expected item or end in test.logix:4:3"
     );
 }
@@ -122,7 +122,7 @@ fn truncated_array_penultimate_item() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected end of file while parsing `Array`\n",
@@ -135,7 +135,7 @@ fn truncated_array_penultimate_item() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected end of file while parsing `Array`, expected item or end in test.logix:4:3"
     );
 }
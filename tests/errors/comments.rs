@@ -19,7 +19,7 @@ fn stray_slash() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -32,7 +32,7 @@ fn stray_slash() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected character '/' in test.logix:4:2"
     );
 }
@@ -54,7 +54,7 @@ fn not_terminated() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -67,7 +67,7 @@ fn not_terminated() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected end of file, expected `*/` in test.logix:4:10"
     );
 }
@@ -24,7 +24,7 @@ fn escape_str<T: LogixType + fmt::Debug>(
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         [
             format!("\n"),
             format!("error: Failed to parse escaped string\n"),
@@ -40,7 +40,7 @@ fn escape_str<T: LogixType + fmt::Debug>(
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         format!("Failed to parse string, {err_str} in test.logix:3:{col}")
     );
 }
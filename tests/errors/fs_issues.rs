@@ -1,12 +1,7 @@
 use logix_vfs::LogixVfs;
 
 use super::*;
-use std::{
-    collections::HashMap,
-    io::{Error, ErrorKind},
-    path::Path,
-    sync::Mutex,
-};
+use std::{collections::HashMap, io::Error, path::Path, sync::Mutex};
 
 #[derive(Debug)]
 struct FailFs {
@@ -70,7 +65,7 @@ fn read_error() {
             [(
                 "test.logix",
                 FailFile {
-                    res: vec![Err(Error::new(ErrorKind::Other, "sorry"))],
+                    res: vec![Err(Error::other("sorry"))],
                 },
             )]
             .into_iter()
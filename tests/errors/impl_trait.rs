@@ -16,7 +16,7 @@ fn not_valid_number() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Unexpected string while parsing `u32`\n",
@@ -30,7 +30,7 @@ fn not_valid_number() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:2:7"
     );
 }
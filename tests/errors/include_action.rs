@@ -17,7 +17,7 @@ fn invalid_utf8_one_line() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to include file as `string`\n",
@@ -29,7 +29,7 @@ fn invalid_utf8_one_line() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to include file as `string`, invalid utf-8 sequence in test.txt:1:6"
     );
 }
@@ -51,7 +51,7 @@ fn invalid_utf8_multi_line() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to include file as `string`\n",
@@ -64,7 +64,7 @@ fn invalid_utf8_multi_line() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to include file as `string`, invalid utf-8 sequence in test.txt:3:6"
     );
 }
@@ -86,7 +86,7 @@ fn include_missing_file_as_data() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to include file as `string`\n",
@@ -98,7 +98,26 @@ fn include_missing_file_as_data() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to include file as `string`, Failed to locate \"missing.txt\" in test.logix:1:0"
     );
 }
+
+#[test]
+fn unknown_action() {
+    let mut l = Loader::init().with_file("test.logix", b"@inclde(\"test.txt\")");
+    let e = l.parse_file::<String>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::TokenError {
+            span: l.span("test.logix", 1, 0, 7),
+            error: TokenError::UnknownAction("inclde".into()),
+        }
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Failed to parse input, unknown action `@inclde` in test.logix:1:0"
+    );
+}
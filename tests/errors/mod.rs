@@ -8,7 +8,12 @@ mod escape_str;
 mod fs_issues;
 mod impl_trait;
 mod include_action;
+mod multi_line;
+mod numbers;
+mod primitives;
+mod recovery;
 mod stray_token;
 mod string_lit;
 mod tagged_str;
 mod valid_paths;
+mod warnings;
@@ -0,0 +1,167 @@
+use std::collections::BTreeSet;
+
+use super::*;
+
+#[derive(LogixType, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+#[test]
+fn txt_string() {
+    let src = "Struct {\n  aaa: #txt\"\n    hello\n    world\n  \"#\n  bbbb: \"x\"\n}";
+    let mut l = Loader::init().with_file("test.logix", src.as_bytes());
+    let e = l.parse_struct("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::UnexpectedToken {
+            span: l.span("test.logix", 2, 7, 30),
+            while_parsing: "u32",
+            wanted: Wanted::LitNum("unsigned integer"),
+            got_token: "string",
+        }
+    );
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Unexpected string while parsing `u32`\n",
+            "   ---> test.logix:2:7\n",
+            "    |\n",
+            "  1 |   Struct {\n",
+            "  2 |     aaa: #txt\"\n",
+            "    |  ________^\n",
+            "  3 | |     hello\n",
+            "  4 | |     world\n",
+            "  5 | |   \"#\n",
+            "    | |____^ expected unsigned integer\n",
+            "  6 |     bbbb: \"x\"\n",
+        )
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:2:7"
+    );
+}
+
+#[test]
+fn long_span() {
+    let lines = (1..=10)
+        .map(|i| format!("    line {i}\n"))
+        .collect::<String>();
+    let src = format!("Struct {{\n  aaa: #txt\"\n{lines}  \"#\n  bbbb: \"x\"\n}}");
+    let mut l = Loader::init().with_file("test.logix", src.as_bytes());
+    let e = l.parse_struct("test.logix");
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Unexpected string while parsing `u32`\n",
+            "   ---> test.logix:2:7\n",
+            "    |\n",
+            "  1 |   Struct {\n",
+            "  2 |     aaa: #txt\"\n",
+            "    |  ________^\n",
+            "  3 | |     line 1\n",
+            "  4 | |     line 2\n",
+            "  5 | |     line 3\n",
+            "... |\n",
+            " 12 | |     line 10\n",
+            " 13 | |   \"#\n",
+            "    | |____^ expected unsigned integer\n",
+            " 14 |     bbbb: \"x\"\n",
+        )
+    );
+}
+
+#[test]
+fn esc_string_offset() {
+    let src = "Struct {\n  aaa: 1\n  bbbb: #esc\"first\n    bad \\q\n  \"#\n}";
+    let mut l = Loader::init().with_file("test.logix", src.as_bytes());
+    let e = l.parse_struct("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::StrEscError {
+            span: l.span("test.logix", 4, 8, 2),
+            error: EscStrError::InvalidEscapeChar('q'),
+        }
+    );
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Failed to parse escaped string\n",
+            "   ---> test.logix:4:8\n",
+            "    |\n",
+            "  3 |   bbbb: #esc\"first\n",
+            "  4 |     bad \\q\n",
+            "    |         ^^ got invalid escape character 'q'\n",
+            "  5 |   \"#\n",
+        )
+    );
+}
+
+#[test]
+fn list_and_struct() {
+    let src = "[\n  [1, 2]\n  [\n    1,\n    2\n  ]\n]";
+    let mut l = Loader::init().with_file("test.logix", src.as_bytes());
+    let e = l.parse_file::<BTreeSet<Vec<u32>>>("test.logix");
+    assert_eq!(
+        e,
+        ParseError::Warning(Warn::DuplicateSetEntry {
+            span: l.span("test.logix", 3, 2, 18),
+            type_name: "set",
+        })
+    );
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Duplicate entry while parsing `set`\n",
+            "   ---> test.logix:3:2\n",
+            "    |\n",
+            "  2 |     [1, 2]\n",
+            "  3 |     [\n",
+            "    |  ___^\n",
+            "  4 | |     1,\n",
+            "  5 | |     2\n",
+            "  6 | |   ]\n",
+            "    | |___^ the value is already in the set\n",
+            "  7 |   ]\n",
+        )
+    );
+
+    let src = "[\n  Point {\n    x: 1\n    y: 2\n  }\n  Point {\n    x: 1\n    y: 2\n  }\n]";
+    let mut l = Loader::init().with_file("test.logix", src.as_bytes());
+    let e = l.parse_file::<BTreeSet<Point>>("test.logix");
+    assert_eq!(
+        e,
+        ParseError::Warning(Warn::DuplicateSetEntry {
+            span: l.span("test.logix", 6, 2, 29),
+            type_name: "set",
+        })
+    );
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Duplicate entry while parsing `set`\n",
+            "   ---> test.logix:6:2\n",
+            "    |\n",
+            "  5 |     }\n",
+            "  6 |     Point {\n",
+            "    |  ___^\n",
+            "  7 | |     x: 1\n",
+            "  8 | |     y: 2\n",
+            "  9 | |   }\n",
+            "    | |___^ the value is already in the set\n",
+            " 10 |   ]\n",
+        )
+    );
+}
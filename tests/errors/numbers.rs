@@ -0,0 +1,276 @@
+use logix_type::error::NumError;
+
+use super::*;
+
+fn num_error<T: LogixType + fmt::Debug>(src: &str, col: usize, len: usize, error: NumError) {
+    let mut l = Loader::init().with_file("test.logix", src.as_bytes());
+    let e = l.parse_file::<T>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::NumError {
+            span: l.span("test.logix", 1, col, len),
+            while_parsing: T::descriptor().name,
+            error,
+        }
+    );
+}
+
+fn num_ok<T: LogixType + fmt::Debug + PartialEq>(src: &str, want: T) {
+    let mut l = Loader::init().with_file("test.logix", src.as_bytes());
+    assert_eq!(l.loader.load_file::<T>("test.logix").unwrap(), want);
+}
+
+macro_rules! check_bounds {
+    ($($type:ident),+) => {$({
+        let above = ($type::MAX as i128 + 1).to_string();
+        let below = ($type::MIN as i128 - 1).to_string();
+
+        num_ok::<$type>(&$type::MAX.to_string(), $type::MAX);
+        num_ok::<$type>(&$type::MIN.to_string(), $type::MIN);
+        num_error::<$type>(&above, 0, above.len(), NumError::TooLarge);
+
+        if $type::MIN == 0 {
+            num_error::<$type>(&below, 0, 1, NumError::Negative);
+        } else {
+            num_error::<$type>(&below, 0, below.len(), NumError::TooSmall);
+        }
+    })+};
+}
+
+#[test]
+fn integer_bounds() {
+    check_bounds!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+
+    num_ok::<i128>(&i128::MAX.to_string(), i128::MAX);
+    num_ok::<i128>(&i128::MIN.to_string(), i128::MIN);
+    num_error::<i128>(
+        "170141183460469231731687303715884105728",
+        0,
+        39,
+        NumError::TooLarge,
+    );
+    num_error::<i128>(
+        "-170141183460469231731687303715884105729",
+        0,
+        40,
+        NumError::TooSmall,
+    );
+
+    num_ok::<u128>(&u128::MAX.to_string(), u128::MAX);
+    num_ok::<u128>("0", 0);
+    num_error::<u128>(
+        "340282366920938463463374607431768211456",
+        0,
+        39,
+        NumError::TooLarge,
+    );
+    num_error::<u128>("-1", 0, 1, NumError::Negative);
+}
+
+#[test]
+fn radix_and_separators() {
+    num_ok::<u8>("0xff", 255);
+    num_ok::<u32>("0o755", 0o755);
+    num_ok::<u8>("0b1010", 10);
+    num_ok::<i16>("-0x7fff", -0x7fff);
+    num_ok::<u64>("1_000_000", 1_000_000);
+    num_ok::<u32>("0xdead_beef", 0xdead_beef);
+    num_ok::<i8>("-0b1000_0000", i8::MIN);
+
+    num_error::<u8>("0x100", 0, 5, NumError::TooLarge);
+    num_error::<u8>("0b102", 4, 1, NumError::InvalidDigit('2'));
+    num_error::<u32>("0o758", 4, 1, NumError::InvalidDigit('8'));
+    num_error::<u32>("0o7a", 3, 1, NumError::InvalidDigit('a'));
+    num_error::<u32>("0x_1", 2, 1, NumError::InvalidDigit('_'));
+    num_error::<u32>("0x", 0, 2, NumError::Empty);
+    num_error::<i32>("-0b", 0, 3, NumError::Empty);
+}
+
+#[test]
+fn invalid_octal_digit() {
+    let mut l =
+        Loader::init().with_file("test.logix", b"GenStruct {\n  aaa: 0o7_5_9\n  bbbb: 1\n}");
+    let e = l.parse_file::<GenStruct<u8>>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::NumError {
+            span: l.span("test.logix", 2, 13, 1),
+            while_parsing: "u32",
+            error: NumError::InvalidDigit('9'),
+        }
+    );
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Failed to parse `u32`\n",
+            "   ---> test.logix:2:13\n",
+            "    |\n",
+            "  1 | GenStruct {\n",
+            "  2 |   aaa: 0o7_5_9\n",
+            "    |              ^ invalid digit '9'\n",
+            "  3 |   bbbb: 1\n",
+        )
+    );
+}
+
+#[test]
+fn non_zero() {
+    use std::num::{NonZeroI64, NonZeroU16, NonZeroUsize};
+
+    num_ok::<NonZeroU16>("0xffff", NonZeroU16::new(0xffff).unwrap());
+    num_ok::<NonZeroI64>("-1", NonZeroI64::new(-1).unwrap());
+    num_error::<NonZeroUsize>("0b0_0", 0, 5, NumError::Zero);
+    num_error::<NonZeroUsize>("-1", 0, 1, NumError::Negative);
+
+    let mut l = Loader::init().with_file("test.logix", b"0");
+    let e = l.parse_file::<NonZeroU16>("test.logix");
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Failed to parse `NonZeroU16`\n",
+            "   ---> test.logix:1:0\n",
+            "    |\n",
+            "  1 | 0\n",
+            "    | ^ expected a non-zero number\n",
+        )
+    );
+}
+
+#[test]
+fn too_large_u8() {
+    let mut l = Loader::init().with_file("test.logix", b"GenStruct {\n  aaa: 10\n  bbbb: 300\n}");
+    let e = l.parse_file::<GenStruct<u8>>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::NumError {
+            span: l.span("test.logix", 3, 8, 3),
+            while_parsing: "u8",
+            error: NumError::TooLarge,
+        }
+    );
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Failed to parse `u8`\n",
+            "   ---> test.logix:3:8\n",
+            "    |\n",
+            "  2 |   aaa: 10\n",
+            "  3 |   bbbb: 300\n",
+            "    |         ^^^ the number is too large\n",
+            "  4 | }\n",
+        )
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Failed to parse `u8`, the number is too large in test.logix:3:8"
+    );
+}
+
+#[test]
+fn negative_u32() {
+    let mut l = Loader::init().with_file("test.logix", b"Struct {\n  aaa: -1\n  bbbb: \"\"\n}");
+    let e = l.parse_struct("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::NumError {
+            span: l.span("test.logix", 2, 7, 1),
+            while_parsing: "u32",
+            error: NumError::Negative,
+        }
+    );
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Failed to parse `u32`\n",
+            "   ---> test.logix:2:7\n",
+            "    |\n",
+            "  1 | Struct {\n",
+            "  2 |   aaa: -1\n",
+            "    |        ^ the number is negative, expected an unsigned integer\n",
+            "  3 |   bbbb: \"\"\n",
+        )
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Failed to parse `u32`, the number is negative, expected an unsigned integer in test.logix:2:7"
+    );
+}
+
+#[test]
+fn malformed_digits() {
+    let mut l = Loader::init().with_file("test.logix", b"1.2.3");
+    let e = l.parse_file::<i32>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::NumError {
+            span: l.span("test.logix", 1, 1, 1),
+            while_parsing: "i32",
+            error: NumError::InvalidDigit('.'),
+        }
+    );
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Failed to parse `i32`\n",
+            "   ---> test.logix:1:1\n",
+            "    |\n",
+            "  1 | 1.2.3\n",
+            "    |  ^ invalid digit '.'\n",
+        )
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Failed to parse `i32`, invalid digit '.' in test.logix:1:1"
+    );
+}
+
+#[test]
+fn trailing_letters() {
+    // Only an exponent is part of the number, other letters start the next token
+    num_error::<i64>("-12e5", 3, 1, NumError::InvalidDigit('e'));
+
+    let mut l = Loader::init().with_file("test.logix", b"-12ab");
+    let e = l.parse_file::<i64>("test.logix");
+    assert_eq!(
+        l.disval(&e),
+        "Unexpected identifier while parsing `i64`, expected newline in test.logix:1:3"
+    );
+}
+
+#[test]
+fn only_sign() {
+    let mut l = Loader::init().with_file("test.logix", b"-");
+    let e = l.parse_file::<i8>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::NumError {
+            span: l.span("test.logix", 1, 0, 1),
+            while_parsing: "i8",
+            error: NumError::Empty,
+        }
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Failed to parse `i8`, expected at least one digit in test.logix:1:0"
+    );
+}
@@ -0,0 +1,152 @@
+use logix_type::error::NumError;
+
+use super::*;
+
+#[test]
+fn invalid_bool() {
+    let mut l = Loader::init().with_file("test.logix", b"yes");
+    let e = l.parse_file::<bool>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::UnexpectedToken {
+            span: l.span("test.logix", 1, 0, 3),
+            while_parsing: "bool",
+            got_token: "identifier",
+            wanted: Wanted::Bool,
+        }
+    );
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Unexpected identifier while parsing `bool`\n",
+            "   ---> test.logix:1:0\n",
+            "    |\n",
+            "  1 | yes\n",
+            "    | ^^^ expected either `true` or `false`\n",
+        )
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Unexpected identifier while parsing `bool`, expected either `true` or `false` in test.logix:1:0"
+    );
+}
+
+#[test]
+fn malformed_float() {
+    let mut l = Loader::init().with_file("test.logix", b"[1.5, 1.2.3]");
+    let e = l.parse_file::<Vec<f64>>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::NumError {
+            span: l.span("test.logix", 1, 9, 1),
+            while_parsing: "f64",
+            error: NumError::InvalidDigit('.'),
+        }
+    );
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Failed to parse `f64`\n",
+            "   ---> test.logix:1:9\n",
+            "    |\n",
+            "  1 | [1.5, 1.2.3]\n",
+            "    |          ^ invalid digit '.'\n",
+        )
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Failed to parse `f64`, invalid digit '.' in test.logix:1:9"
+    );
+}
+
+#[test]
+fn float_errors() {
+    let num_error = |src: &str, col, len, error| {
+        let mut l = Loader::init().with_file("test.logix", src.as_bytes());
+        assert_eq!(
+            l.parse_file::<f32>("test.logix"),
+            ParseError::NumError {
+                span: l.span("test.logix", 1, col, len),
+                while_parsing: "f32",
+                error,
+            },
+            "{src}"
+        );
+    };
+    num_error("1e", 0, 2, NumError::Empty);
+    num_error("-", 0, 1, NumError::Empty);
+    num_error("1e5e5", 3, 1, NumError::InvalidDigit('e'));
+    num_error("1_000.5", 1, 1, NumError::InvalidDigit('_'));
+}
+
+#[test]
+fn float_spelled_out_infinity() {
+    let mut l = Loader::init().with_file("test.logix", b"-infinity");
+    let e = l.parse_file::<f32>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::TokenError {
+            span: l.span("test.logix", 1, 1, 1),
+            error: TokenError::UnexpectedChar('i'),
+        }
+    );
+}
+
+#[test]
+fn float_from_string() {
+    let mut l = Loader::init().with_file("test.logix", b"\"0.5\"");
+    let e = l.parse_file::<f32>("test.logix");
+
+    assert_eq!(
+        l.disval(&e),
+        "Unexpected string while parsing `f32`, expected floating point number in test.logix:1:0"
+    );
+}
+
+#[test]
+fn too_long_char() {
+    let mut l = Loader::init().with_file("test.logix", b"\"ab\"");
+    let e = l.parse_file::<char>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::UnexpectedToken {
+            span: l.span("test.logix", 1, 0, 4),
+            while_parsing: "char",
+            got_token: "string",
+            wanted: Wanted::LitChar,
+        }
+    );
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Unexpected string while parsing `char`\n",
+            "   ---> test.logix:1:0\n",
+            "    |\n",
+            "  1 | \"ab\"\n",
+            "    | ^^^^ expected string with a single character\n",
+        )
+    );
+}
+
+#[test]
+fn empty_char() {
+    let mut l = Loader::init().with_file("test.logix", b"\"\"");
+    let e = l.parse_file::<char>("test.logix");
+
+    assert_eq!(
+        l.disval(&e),
+        "Unexpected string while parsing `char`, expected string with a single character in test.logix:1:0"
+    );
+}
@@ -0,0 +1,244 @@
+use logix_type::error::ParseErrors;
+
+use super::*;
+
+#[derive(LogixType, PartialEq, Debug)]
+struct Outer {
+    first: Struct,
+    list: Vec<u32>,
+    map: Map<u32>,
+    last: u32,
+}
+
+fn load_all<T: LogixType + fmt::Debug>(l: &mut Loader, name: &str) -> ParseErrors {
+    let e = l.loader.load_file_all_errors::<T>(name).unwrap_err();
+
+    let report = l.loader.report(&e);
+    println!("**** DISPLAY START ****\n{report}\n**** DISPLAY END ******\n");
+    println!("*** DEBUG START ******\n{report:?}\n**** DEBUG END ********\n");
+    e
+}
+
+#[test]
+fn all_members() {
+    let mut l = Loader::init().with_file("test.logix", b"Struct {\n  aaa: \"x\"\n  bbbb: 10\n}\n");
+    let e = load_all::<Struct>(&mut l, "test.logix");
+
+    assert_eq!(
+        e.into_vec(),
+        vec![
+            ParseError::UnexpectedToken {
+                span: l.span("test.logix", 2, 7, 3),
+                while_parsing: "u32",
+                wanted: Wanted::LitNum("unsigned integer"),
+                got_token: "string",
+            },
+            ParseError::UnexpectedToken {
+                span: l.span("test.logix", 3, 8, 2),
+                while_parsing: "string",
+                wanted: Wanted::LitStr,
+                got_token: "number",
+            },
+        ]
+    );
+
+    // Without recovery only the first error is reported
+    assert_eq!(
+        l.parse_struct("test.logix"),
+        ParseError::UnexpectedToken {
+            span: l.span("test.logix", 2, 7, 3),
+            while_parsing: "u32",
+            wanted: Wanted::LitNum("unsigned integer"),
+            got_token: "string",
+        },
+    );
+}
+
+#[test]
+fn unknown_and_missing() {
+    let mut l = Loader::init().with_file(
+        "test.logix",
+        b"Struct {\n  ccc: 1\n  aaa: 1 2\n  aaa: 3\n}\n",
+    );
+    let e = load_all::<Struct>(&mut l, "test.logix");
+
+    assert_eq!(
+        e.into_vec(),
+        vec![
+            ParseError::UnexpectedToken {
+                span: l.span("test.logix", 2, 2, 3),
+                while_parsing: "Struct",
+                wanted: Wanted::Tokens(&[
+                    Token::Brace {
+                        start: false,
+                        brace: Brace::Curly
+                    },
+                    Token::Ident("aaa"),
+                    Token::Ident("bbbb"),
+                ]),
+                got_token: "identifier",
+            },
+            ParseError::UnexpectedToken {
+                span: l.span("test.logix", 3, 9, 1),
+                while_parsing: "Struct",
+                wanted: Wanted::Token(Token::Newline(false)),
+                got_token: "number",
+            },
+            ParseError::DuplicateStructMember {
+                span: l.span("test.logix", 4, 2, 3),
+                type_name: "Struct",
+                member: "aaa",
+            },
+            ParseError::MissingStructMember {
+                span: l.span("test.logix", 5, 0, 1),
+                type_name: "Struct",
+                member: "bbbb",
+            },
+        ]
+    );
+}
+
+#[test]
+fn nested() {
+    let mut l = Loader::init().with_file(
+        "test.logix",
+        concat!(
+            "Outer {\n",
+            "  first: Struct {\n",
+            "    aaa: -1\n",
+            "    bbbb: \"ok\"\n",
+            "  }\n",
+            "  list: [\n",
+            "    1\n",
+            "    \"two\"\n",
+            "    3\n",
+            "  ]\n",
+            "  map: {\n",
+            "    a: 1\n",
+            "    b: x\n",
+            "    a: 2\n",
+            "  }\n",
+            "  last: 300000000000\n",
+            "}\n",
+        )
+        .as_bytes(),
+    );
+    let e = load_all::<Outer>(&mut l, "test.logix");
+
+    assert_eq!(
+        e.iter().map(|e| l.disval(e)).collect::<Vec<_>>(),
+        vec![
+            "Failed to parse `u32`, the number is negative, expected an unsigned integer in test.logix:3:9",
+            "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:8:4",
+            "Unexpected identifier while parsing `u32`, expected unsigned integer in test.logix:13:7",
+            "Duplicate entry `a` while parsing `Map`, overwrites the previous entry in test.logix:14:4",
+            "Failed to parse `u32`, the number is too large in test.logix:16:8",
+        ]
+    );
+}
+
+#[test]
+fn unexpected_eof() {
+    let mut l = Loader::init().with_file("test.logix", b"Struct {\n  aaa: \"x\"\n  bbbb:");
+    let e = load_all::<Struct>(&mut l, "test.logix");
+
+    assert_eq!(
+        e.iter().map(|e| l.disval(e)).collect::<Vec<_>>(),
+        vec![
+            "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:2:7",
+            "Unexpected end of file while parsing `string`, expected string in test.logix:3:7",
+        ]
+    );
+}
+
+#[test]
+fn rendering() {
+    let mut l = Loader::init().with_file("test.logix", b"Struct {\n  aaa: \"x\"\n  bbbb: 10\n}\n");
+    let e = load_all::<Struct>(&mut l, "test.logix");
+
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Unexpected string while parsing `u32`\n",
+            "   ---> test.logix:2:7\n",
+            "    |\n",
+            "  1 | Struct {\n",
+            "  2 |   aaa: \"x\"\n",
+            "    |        ^^^ expected unsigned integer\n",
+            "  3 |   bbbb: 10\n",
+            "\n",
+            "error: Unexpected number while parsing `string`\n",
+            "   ---> test.logix:3:8\n",
+            "    |\n",
+            "  2 |   aaa: \"x\"\n",
+            "  3 |   bbbb: 10\n",
+            "    |         ^^ expected string\n",
+            "  4 | }\n",
+        )
+    );
+    assert_eq!(
+        l.disval(&e),
+        concat!(
+            "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:2:7\n",
+            "Unexpected number while parsing `string`, expected string in test.logix:3:8",
+        )
+    );
+}
+
+#[test]
+fn no_errors() {
+    let mut l = Loader::init().with_file("test.logix", b"Struct {\n  aaa: 1\n  bbbb: \"x\"\n}\n");
+    assert_eq!(
+        l.loader
+            .load_file_all_errors::<Struct>("test.logix")
+            .unwrap(),
+        Struct {
+            aaa: 1,
+            bbbb: "x".into(),
+        }
+    );
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+struct Pair(u32, u32);
+
+#[derive(LogixType, PartialEq, Debug)]
+struct Items {
+    list: Vec<u32>,
+    tuple: (u32, String, u32),
+    pair: Pair,
+    last: u32,
+}
+
+#[test]
+fn list_and_tuple_items() {
+    let mut l = Loader::init().with_file(
+        "test.logix",
+        concat!(
+            "Items {\n",
+            "  list: [1, \"two\", 3, x\n",
+            "    [4], 5]\n",
+            "  tuple: (\"a\", \"b\", \"c\")\n",
+            "  pair: Pair(-1 2, x)\n",
+            "  last: \"x\"\n",
+            "}\n",
+        )
+        .as_bytes(),
+    );
+    let e = load_all::<Items>(&mut l, "test.logix");
+
+    assert_eq!(
+        e.iter().map(|e| l.disval(e)).collect::<Vec<_>>(),
+        vec![
+            "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:2:12",
+            "Unexpected identifier while parsing `u32`, expected unsigned integer in test.logix:2:22",
+            "Unexpected `[` while parsing `u32`, expected unsigned integer in test.logix:3:4",
+            "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:4:10",
+            "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:4:20",
+            "Failed to parse `u32`, the number is negative, expected an unsigned integer in test.logix:5:13",
+            "Unexpected identifier while parsing `u32`, expected unsigned integer in test.logix:5:19",
+            "Unexpected string while parsing `u32`, expected unsigned integer in test.logix:6:8",
+        ]
+    );
+}
@@ -30,7 +30,7 @@ fn stray_token_after_struct(token: &str, token_str: &'static str) {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         [
             format!("\n"),
             format!("error: Unexpected {token_str} while parsing `Struct`\n"),
@@ -45,7 +45,7 @@ fn stray_token_after_struct(token: &str, token_str: &'static str) {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         format!(
             "Unexpected {token_str} while parsing `Struct`, expected end of file in test.logix:6:0"
         )
@@ -81,7 +81,7 @@ fn stray_token_in_map(
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         [
             format!("\n"),
             format!("error: Unexpected {token_str} while parsing `map`\n"),
@@ -100,7 +100,7 @@ fn stray_token_in_map(
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         format!(
             "Unexpected {token_str} while parsing `map`, expected {wanted_str} in test.logix:3:{prefix_len}"
         )
@@ -149,3 +149,22 @@ fn stray_number() {
     stray_token("-1337", "number");
     stray_token("-13_37", "number");
 }
+
+#[test]
+fn dash_before_key() {
+    let mut l = Loader::init().with_file("test.logix", b"{\n  -foo: 1\n}\n");
+    let e = l.parse_file::<Map<i32>>("test.logix");
+
+    assert_eq!(
+        e,
+        ParseError::TokenError {
+            span: l.span("test.logix", 2, 3, 1),
+            error: TokenError::UnexpectedChar('f'),
+        }
+    );
+
+    assert_eq!(
+        l.disval(&e),
+        "Failed to parse input, unexpected character 'f' in test.logix:2:3"
+    );
+}
@@ -16,7 +16,7 @@ fn invalid_utf8_basic<T: LogixType + fmt::Debug>() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -30,7 +30,7 @@ fn invalid_utf8_basic<T: LogixType + fmt::Debug>() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, invalid utf-8 sequence in test.logix:3:11"
     );
 }
@@ -60,28 +60,28 @@ fn invalid_utf8_txt<T: LogixType + fmt::Debug>() {
     assert_eq!(
         e,
         ParseError::TokenError {
-            span: l.span("test.logix", 3, 16, 1),
+            span: l.span("test.logix", 3, 15, 1),
             error: TokenError::LitStrNotUtf8,
         }
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
-            "   ---> test.logix:3:16\n",
+            "   ---> test.logix:3:15\n",
             "    |\n",
             "  2 |   aaa: 20\n",
             "  3 |   bbbb: #txt\"aa\u{fffd}\"#\n",
-            "    |                 ^ invalid utf-8 sequence\n",
+            "    |                ^ invalid utf-8 sequence\n",
             "  4 | }\n",
         )
     );
 
     assert_eq!(
-        disval(&e),
-        "Failed to parse input, invalid utf-8 sequence in test.logix:3:16"
+        l.disval(&e),
+        "Failed to parse input, invalid utf-8 sequence in test.logix:3:15"
     );
 }
 
@@ -117,7 +117,7 @@ fn unknown_tag() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -131,7 +131,7 @@ fn unknown_tag() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unknown string tag `invalid` in test.logix:3:8"
     );
 }
@@ -150,7 +150,7 @@ fn unterminated_eol() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -164,7 +164,7 @@ fn unterminated_eol() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected end of the string, expected `\"` in test.logix:3:11"
     );
 }
@@ -183,7 +183,7 @@ fn unterminated_eof() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -196,7 +196,7 @@ fn unterminated_eof() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected end of the string, expected `\"` in test.logix:3:11"
     );
 }
@@ -215,7 +215,7 @@ fn not_a_string() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -229,7 +229,7 @@ fn not_a_string() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected character '#' in test.logix:3:8"
     );
 }
@@ -248,7 +248,7 @@ fn invalid_tag() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -262,7 +262,7 @@ fn invalid_tag() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected character '#' in test.logix:3:8"
     );
 }
@@ -281,7 +281,7 @@ fn unterminated_tag() {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         concat!(
             "\n",
             "error: Failed to parse input\n",
@@ -294,7 +294,7 @@ fn unterminated_tag() {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         "Failed to parse input, unexpected character '#' in test.logix:3:8"
     );
 }
@@ -19,7 +19,7 @@ fn unterminated_eof_tagged(tag: StrTag, tag_str: &str) {
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         [
             format!("\n"),
             format!("error: Failed to parse input\n"),
@@ -38,7 +38,7 @@ fn unterminated_eof_tagged(tag: StrTag, tag_str: &str) {
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         format!("Failed to parse input, unexpected end of `#{tag_str}` string, expected `\"#` in test.logix:3:15"),
     );
 }
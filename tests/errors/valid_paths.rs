@@ -43,7 +43,7 @@ fn path_test<'a, T: PathLike<'a>>(
     );
 
     assert_eq!(
-        debval(&e),
+        l.debval(&e),
         [
             format!("\n"),
             format!("error: Failed to parse path\n"),
@@ -59,7 +59,7 @@ fn path_test<'a, T: PathLike<'a>>(
     );
 
     assert_eq!(
-        disval(&e),
+        l.disval(&e),
         format!("Failed to parse path, {err_str} in test.logix:3:{col}")
     );
 
@@ -0,0 +1,142 @@
+use logix_type::error::{WarnKind, WarningPolicy};
+
+use super::*;
+
+fn dup_map_loader() -> Loader {
+    Loader::init().with_file("test.logix", b"{\n  a: 1\n  b: 2\n  a: 3\n  b: 4\n}")
+}
+
+#[test]
+fn deny_by_default() {
+    let mut l = dup_map_loader();
+    assert_eq!(
+        l.loader.warning_policy(WarnKind::DuplicateMapEntry),
+        WarningPolicy::Deny
+    );
+
+    let e = l.parse_file::<Map<u32>>("test.logix");
+    assert_eq!(
+        e,
+        ParseError::Warning(Warn::DuplicateMapEntry {
+            span: l.span("test.logix", 4, 2, 1),
+            key: ShortStr::from("a"),
+        })
+    );
+}
+
+#[test]
+fn allow_warnings() {
+    let mut l = dup_map_loader();
+    l.loader.set_default_warning_policy(WarningPolicy::Allow);
+
+    let (map, warnings) = l
+        .loader
+        .load_file_with_warnings::<Map<u32>>("test.logix")
+        .unwrap();
+    assert_eq!(
+        map,
+        Map::from([(ShortStr::from("a"), 3), (ShortStr::from("b"), 4)])
+    );
+    assert_eq!(warnings, vec![]);
+}
+
+#[test]
+fn collect_warnings() {
+    let mut l = dup_map_loader();
+    l.loader
+        .set_warning_policy(WarnKind::DuplicateMapEntry, WarningPolicy::Collect);
+
+    let (map, warnings) = l
+        .loader
+        .load_file_with_warnings::<Map<u32>>("test.logix")
+        .unwrap();
+    assert_eq!(
+        map,
+        Map::from([(ShortStr::from("a"), 3), (ShortStr::from("b"), 4)])
+    );
+    assert_eq!(
+        warnings,
+        vec![
+            Warn::DuplicateMapEntry {
+                span: l.span("test.logix", 4, 2, 1),
+                key: ShortStr::from("a"),
+            },
+            Warn::DuplicateMapEntry {
+                span: l.span("test.logix", 5, 2, 1),
+                key: ShortStr::from("b"),
+            },
+        ]
+    );
+    assert_eq!(
+        warnings.iter().map(|e| l.disval(e)).collect::<Vec<_>>(),
+        vec![
+            "Duplicate entry `a` while parsing `Map`, overwrites the previous entry in test.logix:4:2",
+            "Duplicate entry `b` while parsing `Map`, overwrites the previous entry in test.logix:5:2",
+        ]
+    );
+
+    // Loading again should only return the warnings from the new load
+    let (_, warnings) = l
+        .loader
+        .load_file_with_warnings::<Map<u32>>("test.logix")
+        .unwrap();
+    assert_eq!(warnings.len(), 2);
+}
+
+#[test]
+fn per_kind_overrides_default() {
+    let mut l = dup_map_loader();
+    l.loader.set_default_warning_policy(WarningPolicy::Collect);
+    l.loader
+        .set_warning_policy(WarnKind::DuplicateMapEntry, WarningPolicy::Deny);
+
+    let e = l.parse_file::<Map<u32>>("test.logix");
+    assert_eq!(
+        l.disval(&e),
+        "Duplicate entry `a` while parsing `Map`, overwrites the previous entry in test.logix:4:2"
+    );
+
+    // Setting the default policy resets the per kind policies
+    l.loader.set_default_warning_policy(WarningPolicy::Allow);
+    assert_eq!(
+        l.loader.load_file::<Map<u32>>("test.logix").unwrap().len(),
+        2
+    );
+}
+
+#[test]
+fn duplicate_set_entry() {
+    let mut l = Loader::init().with_file("test.logix", b"[1, 2, 1]");
+
+    let e = l.parse_file::<std::collections::BTreeSet<u32>>("test.logix");
+    assert_eq!(
+        e,
+        ParseError::Warning(Warn::DuplicateSetEntry {
+            span: l.span("test.logix", 1, 7, 1),
+            type_name: "set",
+        })
+    );
+    assert_eq!(
+        l.debval(&e),
+        concat!(
+            "\n",
+            "error: Duplicate entry while parsing `set`\n",
+            "   ---> test.logix:1:7\n",
+            "    |\n",
+            "  1 | [1, 2, 1]\n",
+            "    |        ^ the value is already in the set\n",
+        )
+    );
+
+    l.loader
+        .set_warning_policy(WarnKind::DuplicateSetEntry, WarningPolicy::Collect);
+    let (set, warnings) = l
+        .loader
+        .load_file_with_warnings::<std::collections::HashSet<u32>>("test.logix")
+        .unwrap();
+    assert_eq!(set, [1, 2].into());
+    assert_eq!(
+        warnings.iter().map(|e| l.disval(e)).collect::<Vec<_>>(),
+        vec!["Duplicate entry while parsing `set`, the value is already in the set in test.logix:1:7"]
+    );
+}
@@ -0,0 +1,123 @@
+use logix_type::{
+    error::TokenError,
+    format::{format, FormatError},
+    types::Map,
+    LogixType, LogixWriter,
+};
+
+use common::load;
+
+mod common;
+
+#[derive(LogixType, PartialEq, Debug)]
+struct Config {
+    name: String,
+    text: String,
+    sizes: Vec<Size>,
+    extra: Map<u32>,
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+struct Size(u32, u32);
+
+const MESSY: &str = concat!(
+    "\n",
+    "// The config\n",
+    "Config{\n",
+    "\n",
+    "    name :\"test\"   // trailing comment\n",
+    "\t\t\ttext: #txt\"\n",
+    "\t\t\t\tfirst line\n",
+    "\n",
+    "\t\t\t\tsecond line\n",
+    "\t\t\t\"#\n",
+    "\n",
+    "\n",
+    "\n",
+    "  sizes :[Size( 1,2 ) , Size(3 ,4),\n",
+    "      Size(5, 6)\n",
+    "        ]\n",
+    "   /* a block\n",
+    "      comment */\n",
+    "extra: {\n",
+    "a: 1\n",
+    "\n",
+    "}\n",
+    "}   \n",
+    "\n",
+    "\n",
+);
+
+const CANONICAL: &str = concat!(
+    "// The config\n",
+    "Config {\n",
+    "  name: \"test\" // trailing comment\n",
+    "  text: #txt\"\n",
+    "    first line\n",
+    "\n",
+    "    second line\n",
+    "    \"#\n",
+    "\n",
+    "  sizes: [Size(1, 2), Size(3, 4),\n",
+    "    Size(5, 6)\n",
+    "  ]\n",
+    "  /* a block\n",
+    "      comment */\n",
+    "  extra: {\n",
+    "    a: 1\n",
+    "  }\n",
+    "}\n",
+);
+
+#[test]
+fn canonical() {
+    assert_eq!(format(MESSY.as_bytes()).unwrap(), CANONICAL);
+    assert_eq!(format(CANONICAL.as_bytes()).unwrap(), CANONICAL);
+    assert_eq!(
+        load::<Config>(MESSY).unwrap(),
+        load::<Config>(CANONICAL).unwrap()
+    );
+}
+
+#[test]
+fn writer_output_is_canonical() {
+    let value = Config {
+        name: "test".into(),
+        text: "first line\nsecond line".into(),
+        sizes: vec![Size(1, 2), Size(3, 4)],
+        extra: Map::from_iter([("a".into(), 1), ("b".into(), 2)]),
+    };
+    let src = LogixWriter::to_file_string(&value).unwrap();
+    assert_eq!(format(src.as_bytes()).unwrap(), src);
+}
+
+#[test]
+fn nested_braces_on_one_line() {
+    let src = "List {\n  items: [Size(\n1, 2\n), Item {\nx: 1\n}]\n}\n";
+    assert_eq!(
+        format(src.as_bytes()).unwrap(),
+        "List {\n  items: [Size(\n    1, 2\n  ), Item {\n    x: 1\n  }]\n}\n"
+    );
+}
+
+#[test]
+fn empty() {
+    assert_eq!(format(b"").unwrap(), "");
+    assert_eq!(format(b"\n\n  \n").unwrap(), "");
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(
+        format(b"Config {\n  name: \"test\n}\n").unwrap_err(),
+        FormatError {
+            line: 2,
+            col: 13,
+            error: TokenError::MissingStringTerminator,
+        }
+    );
+    assert_eq!(
+        format(b"a: 1\r\n").unwrap_err().to_string(),
+        "unexpected character '\\r' at 1:4"
+    );
+}
@@ -0,0 +1,172 @@
+use logix_type::{
+    error::TokenError,
+    token::{Action, Brace, Delim, LexSpan, LexemeKind, Lexer, Literal, StrLit, StrTag, Token},
+};
+
+/// Lex `src` and check that the lexemes cover all of it
+fn lex(src: &[u8]) -> Vec<(LexemeKind<'_>, &str, usize, usize)> {
+    let mut pos = 0;
+    let ret = Lexer::new(src)
+        .map(|lexeme| {
+            assert_eq!(lexeme.span.start, pos, "{lexeme:?}");
+            pos = lexeme.span.end;
+            let text = std::str::from_utf8(&src[lexeme.span.range()]).unwrap_or("<invalid>");
+            (lexeme.kind, text, lexeme.span.line, lexeme.span.col)
+        })
+        .collect();
+    assert_eq!(pos, src.len());
+    ret
+}
+
+fn tok(token: Token) -> LexemeKind {
+    LexemeKind::Token(token)
+}
+
+const WS: LexemeKind = LexemeKind::Whitespace;
+const NL: LexemeKind = LexemeKind::Token(Token::Newline(false));
+const EOF: LexemeKind = LexemeKind::Token(Token::Newline(true));
+
+#[test]
+fn trivia() {
+    let src = b"Node { // first\n\n  a: \t\"x\"\n  /* multi\n  line */ b: 1,\n}";
+    assert_eq!(
+        lex(src),
+        vec![
+            (tok(Token::Ident("Node")), "Node", 1, 0),
+            (WS, " ", 1, 4),
+            (
+                tok(Token::Brace {
+                    start: true,
+                    brace: Brace::Curly
+                }),
+                "{",
+                1,
+                5
+            ),
+            (WS, " ", 1, 6),
+            (tok(Token::Comment("first")), "// first", 1, 7),
+            (NL, "\n", 1, 15),
+            (NL, "\n", 2, 0),
+            (WS, "  ", 3, 0),
+            (tok(Token::Ident("a")), "a", 3, 2),
+            (tok(Token::Delim(Delim::Colon)), ":", 3, 3),
+            (WS, " \t", 3, 4),
+            (
+                tok(Token::Literal(Literal::Str(StrLit::new(StrTag::Raw, "x")))),
+                "\"x\"",
+                3,
+                6
+            ),
+            (NL, "\n", 3, 9),
+            (WS, "  ", 4, 0),
+            (
+                tok(Token::Comment("multi\n  line")),
+                "/* multi\n  line */",
+                4,
+                2
+            ),
+            (WS, " ", 5, 9),
+            (tok(Token::Ident("b")), "b", 5, 10),
+            (tok(Token::Delim(Delim::Colon)), ":", 5, 11),
+            (WS, " ", 5, 12),
+            (tok(Token::Literal(Literal::Num("1"))), "1", 5, 13),
+            (tok(Token::Delim(Delim::Comma)), ",", 5, 14),
+            (NL, "\n", 5, 15),
+            (
+                tok(Token::Brace {
+                    start: false,
+                    brace: Brace::Curly
+                }),
+                "}",
+                6,
+                0
+            ),
+            (EOF, "", 6, 1),
+        ]
+    );
+}
+
+#[test]
+fn tagged_strings() {
+    let src = b"a: #txt\"\n  x\n\"#\n@include(\"b\")";
+    let lexemes = lex(src);
+    assert_eq!(lexemes[3].2, 1);
+    assert_eq!(lexemes[3].1, "#txt\"\n  x\n\"#");
+    assert_eq!(lexemes[4], (NL, "\n", 3, 2));
+    assert_eq!(
+        lexemes[5],
+        (tok(Token::Action(Action::Include)), "@include", 4, 0)
+    );
+    assert_eq!(lexemes.last().unwrap(), &(EOF, "", 4, 13));
+}
+
+#[test]
+fn numbers() {
+    let num = |s| tok(Token::Literal(Literal::Num(s)));
+    assert_eq!(
+        lex(b"12abc -1.5e+3 0xff_0g -inf nan"),
+        vec![
+            (num("12"), "12", 1, 0),
+            (tok(Token::Ident("abc")), "abc", 1, 2),
+            (WS, " ", 1, 5),
+            (num("-1.5e+3"), "-1.5e+3", 1, 6),
+            (WS, " ", 1, 13),
+            (num("0xff_0"), "0xff_0", 1, 14),
+            (tok(Token::Ident("g")), "g", 1, 20),
+            (WS, " ", 1, 21),
+            (tok(Token::Ident("-inf")), "-inf", 1, 22),
+            (WS, " ", 1, 26),
+            (tok(Token::Ident("nan")), "nan", 1, 27),
+            (EOF, "", 1, 30),
+        ]
+    );
+}
+
+#[test]
+fn errors() {
+    let src = b"a: \"x\n$ b @what @ \"\x8e\" // \x8e\nc /* open";
+    let lexemes = lex(src);
+    let errors = lexemes
+        .iter()
+        .filter_map(|(kind, text, _, _)| match kind {
+            LexemeKind::Error { error, at } => Some((error.clone(), *text, *at)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let at = |start, end, line, col| LexSpan {
+        start,
+        end,
+        line,
+        col,
+    };
+    assert_eq!(
+        errors,
+        vec![
+            (TokenError::MissingStringTerminator, "\"x", at(5, 6, 1, 5)),
+            (TokenError::UnexpectedChar('$'), "$", at(6, 7, 2, 0)),
+            (
+                TokenError::UnknownAction("what".into()),
+                "@what",
+                at(10, 15, 2, 4)
+            ),
+            (TokenError::UnexpectedChar('@'), "@", at(16, 17, 2, 10)),
+            (TokenError::LitStrNotUtf8, "<invalid>", at(19, 20, 2, 13)),
+            (TokenError::LitStrNotUtf8, "<invalid>", at(22, 26, 2, 16)),
+            (
+                TokenError::MissingCommentTerminator,
+                "/* open",
+                at(36, 36, 3, 9)
+            ),
+        ]
+    );
+
+    // Lexing continues after each error
+    assert!(lexemes.contains(&(tok(Token::Ident("b")), "b", 2, 2)));
+    assert!(lexemes.contains(&(tok(Token::Ident("c")), "c", 3, 0)));
+    assert_eq!(lexemes.last().unwrap(), &(EOF, "", 3, 9));
+}
+
+#[test]
+fn empty() {
+    assert_eq!(lex(b""), vec![(EOF, "", 1, 0)]);
+}
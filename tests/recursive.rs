@@ -0,0 +1,232 @@
+use logix_type::{
+    error::ParseError,
+    schema,
+    type_trait::{LogixTypeDescriptor, LogixValueDescriptor},
+    LogixType, LogixWriter,
+};
+
+use common::TestFile;
+
+mod common;
+
+/// A node in a menu tree
+#[derive(LogixType, PartialEq, Debug)]
+struct Node {
+    name: String,
+    children: Vec<Node>,
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+struct Menu {
+    title: String,
+    entries: Vec<Entry>,
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+enum Entry {
+    Action(String),
+    Submenu(Menu),
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+struct Chain {
+    value: u32,
+    next: Option<Box<Chain>>,
+}
+
+#[derive(LogixType, PartialEq, Debug)]
+#[logix(rename = "tree-node")]
+struct TreeNode {
+    children: Vec<TreeNode>,
+}
+
+fn node(name: &str, children: Vec<Node>) -> Node {
+    Node {
+        name: name.into(),
+        children,
+    }
+}
+
+fn round_trip<T: LogixType + PartialEq + std::fmt::Debug>(value: T) {
+    let src = LogixWriter::to_file_string(&value).unwrap();
+    assert_eq!(TestFile::new(&src).load::<T>().unwrap(), value, "{src}");
+}
+
+fn struct_member(desc: &'static LogixTypeDescriptor, name: &str) -> &'static LogixTypeDescriptor {
+    let LogixValueDescriptor::Struct { members } = &desc.value else {
+        panic!("Expected a struct");
+    };
+    members.iter().find(|m| m.name == name).unwrap().ty
+}
+
+#[test]
+fn tree() {
+    let src = concat!(
+        "Node {\n",
+        "  name: \"root\"\n",
+        "  children: [\n",
+        "    Node {\n",
+        "      name: \"a\"\n",
+        "      children: []\n",
+        "    }\n",
+        "    Node {\n",
+        "      name: \"b\"\n",
+        "      children: [Node {\n",
+        "        name: \"c\"\n",
+        "        children: []\n",
+        "      }]\n",
+        "    }\n",
+        "  ]\n",
+        "}\n",
+    );
+    let expected = node(
+        "root",
+        vec![node("a", vec![]), node("b", vec![node("c", vec![])])],
+    );
+    assert_eq!(TestFile::new(src).load::<Node>().unwrap(), expected);
+    round_trip(expected);
+}
+
+#[test]
+fn mutual() {
+    let value = Menu {
+        title: "File".into(),
+        entries: vec![
+            Entry::Action("open".into()),
+            Entry::Submenu(Menu {
+                title: "Recent".into(),
+                entries: vec![Entry::Action("clear".into())],
+            }),
+        ],
+    };
+    round_trip(value);
+    round_trip(Chain {
+        value: 1,
+        next: Some(Box::new(Chain {
+            value: 2,
+            next: None,
+        })),
+    });
+}
+
+#[test]
+fn descriptors() {
+    let children = struct_member(Node::descriptor(), "children");
+    assert_eq!(children, Vec::<Node>::descriptor());
+    let LogixValueDescriptor::List { item } = children.value else {
+        panic!("Expected a list");
+    };
+    assert!(std::ptr::eq(item.resolve(), Node::descriptor()));
+
+    // Whichever type is described first holds the back-reference
+    let entries = struct_member(Menu::descriptor(), "entries");
+    let LogixValueDescriptor::List { item } = entries.value else {
+        panic!("Expected a list");
+    };
+    assert!(std::ptr::eq(item.resolve(), Entry::descriptor()));
+    let LogixValueDescriptor::Enum { variants } = &Entry::descriptor().value else {
+        panic!("Expected an enum");
+    };
+    let LogixValueDescriptor::Tuple { members } = &variants[1].value else {
+        panic!("Expected a tuple");
+    };
+    assert!(std::ptr::eq(members[0].resolve(), Menu::descriptor()));
+
+    let next = struct_member(Chain::descriptor(), "next");
+    let LogixValueDescriptor::Optional { inner } = next.value else {
+        panic!("Expected an optional value");
+    };
+    assert!(std::ptr::eq(inner.resolve(), Chain::descriptor()));
+}
+
+#[test]
+fn renamed() {
+    let children = struct_member(TreeNode::descriptor(), "children");
+    let LogixValueDescriptor::List { item } = children.value else {
+        panic!("Expected a list");
+    };
+    assert!(matches!(item.value, LogixValueDescriptor::BackRef { .. }));
+    assert_eq!(item.name, "tree-node");
+
+    round_trip(TreeNode {
+        children: vec![TreeNode { children: vec![] }],
+    });
+}
+
+#[test]
+fn schema() {
+    assert_eq!(
+        schema::to_markdown(Node::descriptor()),
+        concat!(
+            "# `Node`\n",
+            "\n",
+            "A node in a menu tree\n",
+            "\n",
+            "| Member | Type | Required | Description |\n",
+            "|--------|------|----------|-------------|\n",
+            "| `name` | `string` | yes |  |\n",
+            "| `children` | list of [`Node`](#node) | yes |  |\n",
+            "\n",
+        )
+    );
+
+    let json = schema::to_json_schema(Menu::descriptor());
+    assert!(json.contains("\"$ref\": \"#/$defs/Entry\""), "{json}");
+    assert!(json.contains("\"$ref\": \"#/$defs/Menu\""), "{json}");
+}
+
+#[test]
+fn max_depth() {
+    let src = concat!(
+        "Node {\n",
+        "  name: \"a\"\n",
+        "  children: [Node {\n",
+        "    name: \"b\"\n",
+        "    children: [Node {\n",
+        "      name: \"c\"\n",
+        "      children: []\n",
+        "    }]\n",
+        "  }]\n",
+        "}\n",
+    );
+    let mut l = TestFile::new(src);
+    l.loader.set_max_depth(4);
+    let e = l.load::<Node>().unwrap_err();
+    assert_eq!(
+        e,
+        ParseError::NestingTooDeep {
+            span: l.span(5, 20, 1),
+            max_depth: 4,
+        }
+    );
+    assert_eq!(
+        l.report(&e),
+        "Nesting is deeper than the limit of 4 levels in test.logix:5:20"
+    );
+
+    l.loader.set_max_depth(6);
+    assert_eq!(
+        l.load::<Node>().unwrap(),
+        node("a", vec![node("b", vec![node("c", vec![])])])
+    );
+}
+
+#[test]
+fn deep_input() {
+    // Far deeper than the default limit, this must fail cleanly instead of overflowing the stack
+    let levels = 10_000;
+    let mut src = String::new();
+    for _ in 0..levels {
+        src += "Node {\n  name: \"x\"\n  children: [";
+    }
+    for _ in 0..levels {
+        src += "]\n}";
+    }
+    src.push('\n');
+
+    let e = TestFile::new(&src).load::<Node>().unwrap_err();
+    assert!(
+        matches!(e, ParseError::NestingTooDeep { max_depth: 128, .. }),
+        "{e}"
+    );
+}
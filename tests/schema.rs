@@ -0,0 +1,196 @@
+use logix_type::{schema, LogixType};
+
+/// The root of the config
+#[derive(LogixType)]
+#[allow(dead_code)]
+struct Config {
+    /// Name of the config
+    name: String,
+    #[logix(default)]
+    port: u16,
+    mode: Mode,
+    /// Used twice, but only described once
+    first: Point,
+    second: Point,
+}
+
+/// How to run
+#[derive(LogixType)]
+#[allow(dead_code)]
+enum Mode {
+    /// Run once
+    Once,
+    Repeat {
+        count: u32,
+    },
+    Delay(Point),
+}
+
+#[derive(LogixType)]
+#[allow(dead_code)]
+struct Point(i32, i32);
+
+#[test]
+fn markdown() {
+    assert_eq!(
+        schema::to_markdown(Config::descriptor()),
+        concat!(
+            "# `Config`\n",
+            "\n",
+            "The root of the config\n",
+            "\n",
+            "| Member | Type | Required | Description |\n",
+            "|--------|------|----------|-------------|\n",
+            "| `name` | `string` | yes | Name of the config |\n",
+            "| `port` | `u16` | no |  |\n",
+            "| `mode` | [`Mode`](#mode) | yes |  |\n",
+            "| `first` | [`Point`](#point) | yes | Used twice, but only described once |\n",
+            "| `second` | [`Point`](#point) | yes |  |\n",
+            "\n",
+            "\n",
+            "## `Mode`\n",
+            "\n",
+            "How to run\n",
+            "\n",
+            "One of the variants:\n",
+            "\n",
+            "### `Once`\n",
+            "\n",
+            "Run once\n",
+            "\n",
+            "A struct without members\n",
+            "\n",
+            "### `Repeat`\n",
+            "\n",
+            "| Member | Type | Required | Description |\n",
+            "|--------|------|----------|-------------|\n",
+            "| `count` | `u32` | yes |  |\n",
+            "\n",
+            "### `Delay`\n",
+            "\n",
+            "A tuple with the members:\n",
+            "\n",
+            "1. [`Point`](#point)\n",
+            "\n",
+            "\n",
+            "## `Point`\n",
+            "\n",
+            "A tuple with the members:\n",
+            "\n",
+            "1. `i32`\n",
+            "2. `i32`\n",
+            "\n",
+        )
+    );
+}
+
+#[test]
+fn json_schema() {
+    assert_eq!(
+        schema::to_json_schema(Point::descriptor()),
+        concat!(
+            "{\n",
+            "  \"$schema\": \"https://json-schema.org/draft/2020-12/schema\",\n",
+            "  \"$ref\": \"#/$defs/Point\",\n",
+            "  \"$defs\": {\n",
+            "    \"Point\": {\n",
+            "      \"type\": \"array\",\n",
+            "      \"prefixItems\": [\n",
+            "        {\n",
+            "          \"type\": \"integer\"\n",
+            "        },\n",
+            "        {\n",
+            "          \"type\": \"integer\"\n",
+            "        }\n",
+            "      ],\n",
+            "      \"items\": false\n",
+            "    }\n",
+            "  }\n",
+            "}\n",
+        )
+    );
+
+    let schema = schema::to_json_schema(Config::descriptor());
+    for expected in [
+        "\"$ref\": \"#/$defs/Config\"",
+        "\"required\": [\n        \"name\",\n        \"mode\",\n        \"first\",\n        \"second\"\n      ]",
+        "\"const\": \"Once\",\n          \"description\": \"Run once\"",
+        "\"description\": \"Used twice, but only described once\"",
+        "\"description\": \"The root of the config\"",
+    ] {
+        assert!(schema.contains(expected), "{expected:?} not found");
+    }
+    assert_eq!(schema.matches("\"Point\": {").count(), 1);
+}
+
+#[test]
+fn native_root() {
+    assert_eq!(
+        schema::to_markdown(u32::descriptor()),
+        "# `u32`
+
+"
+    );
+    assert_eq!(
+        schema::to_markdown(std::path::PathBuf::descriptor()),
+        "# `path`
+
+a valid path
+"
+    );
+    assert_eq!(
+        schema::to_json_schema(bool::descriptor()),
+        concat!(
+            "{\n",
+            "  \"$schema\": \"https://json-schema.org/draft/2020-12/schema\",\n",
+            "  \"type\": \"boolean\"\n",
+            "}\n",
+        )
+    );
+}
+
+#[derive(LogixType)]
+#[allow(dead_code)]
+struct Collections {
+    tags: Vec<String>,
+    points: [Point; 2],
+    named: logix_type::types::Map<Point>,
+    limit: Option<u32>,
+}
+
+#[test]
+fn collections() {
+    assert_eq!(
+        schema::to_markdown(Collections::descriptor()),
+        concat!(
+            "# `Collections`\n",
+            "\n",
+            "| Member | Type | Required | Description |\n",
+            "|--------|------|----------|-------------|\n",
+            "| `tags` | list of `string` | yes |  |\n",
+            "| `points` | array of 2 [`Point`](#point) | yes |  |\n",
+            "| `named` | map of [`Point`](#point) | no |  |\n",
+            "| `limit` | optional `u32` | no |  |\n",
+            "\n",
+            "\n",
+            "## `Point`\n",
+            "\n",
+            "A tuple with the members:\n",
+            "\n",
+            "1. `i32`\n",
+            "2. `i32`\n",
+            "\n",
+        )
+    );
+
+    let schema = schema::to_json_schema(Collections::descriptor());
+    for expected in [
+        "\"tags\": {\n          \"type\": \"array\",\n          \"items\": {\n            \"type\": \"string\",",
+        "\"minItems\": 2,\n          \"maxItems\": 2,",
+        "\"named\": {\n          \"type\": \"object\",\n          \"additionalProperties\": {\n            \"$ref\": \"#/$defs/Point\"\n          },",
+        "\"limit\": {\n          \"type\": \"integer\"\n        }",
+        "\"required\": [\n        \"tags\",\n        \"points\"\n      ]",
+    ] {
+        assert!(schema.contains(expected), "{expected:?} not found");
+    }
+}
@@ -0,0 +1,356 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use logix_type::{
+    error::{ParseError, SerializeError, Wanted},
+    LogixWriter,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use common::TestFile;
+
+mod common;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Config {
+    name: String,
+    port: u16,
+    tags: Vec<String>,
+    theme: Theme,
+    font: Option<Font>,
+    #[serde(default)]
+    env: BTreeMap<String, i32>,
+    pos: (f32, f32),
+    id: Id,
+    mode: Mode,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Theme {
+    Dark,
+    Custom(String),
+    Rgb(u8, u8, u8),
+    Named { name: String, base: Box<Theme> },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Font {
+    family: String,
+    size: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Id(u32);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Mode;
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(try_from = "String")]
+struct Upper(String);
+
+impl TryFrom<String> for Upper {
+    type Error = &'static str;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.chars().all(|c| c.is_ascii_uppercase()) {
+            Ok(Self(value))
+        } else {
+            Err("expected upper case letters")
+        }
+    }
+}
+
+fn load<T: DeserializeOwned>(src: &str) -> (TestFile, Result<T, ParseError>) {
+    let mut file = TestFile::new(src);
+    let ret = file.loader.deserialize_file("test.logix");
+    (file, ret)
+}
+
+#[test]
+fn all_kinds() {
+    let (_, ret) = load::<Config>(concat!(
+        "Config {\n",
+        "  // Comments and blank lines are allowed\n",
+        "  name: #raw\"hello\"#\n",
+        "\n",
+        "  port: 8080\n",
+        "  tags: [\"a\", \"b\"\n",
+        "    \"c\"]\n",
+        "  theme: Named {\n",
+        "    name: \"night\"\n",
+        "    base: Rgb(0, 0x10, 255)\n",
+        "  }\n",
+        "  font: Font {\n",
+        "    family: \"mono\"\n",
+        "    size: 12\n",
+        "  }\n",
+        "  env: {\n",
+        "    a: -1\n",
+        "    b: 2\n",
+        "  }\n",
+        "  pos: (1.5, -2)\n",
+        "  id: Id(7)\n",
+        "  mode: Mode\n",
+        "}\n",
+    ));
+    assert_eq!(
+        ret.unwrap(),
+        Config {
+            name: "hello".into(),
+            port: 8080,
+            tags: vec!["a".into(), "b".into(), "c".into()],
+            theme: Theme::Named {
+                name: "night".into(),
+                base: Box::new(Theme::Rgb(0, 16, 255)),
+            },
+            font: Some(Font {
+                family: "mono".into(),
+                size: 12,
+            }),
+            env: [("a".into(), -1), ("b".into(), 2)].into(),
+            pos: (1.5, -2.0),
+            id: Id(7),
+            mode: Mode,
+        }
+    );
+
+    // Options and defaults can be left out
+    let (_, ret) = load::<Config>(concat!(
+        "Config {\n",
+        "  name: \"x\"\n",
+        "  port: 1\n",
+        "  tags: []\n",
+        "  theme: Custom(\"y\")\n",
+        "  pos: (0, 0)\n",
+        "  id: Id(0)\n",
+        "  mode: Mode\n",
+        "}\n",
+    ));
+    let ret = ret.unwrap();
+    assert_eq!((ret.theme, ret.font), (Theme::Custom("y".into()), None));
+    assert!(ret.env.is_empty());
+
+    let (_, ret) = load::<Vec<Theme>>("[Dark, Rgb(1, 2, 3)]");
+    assert_eq!(ret.unwrap(), [Theme::Dark, Theme::Rgb(1, 2, 3)]);
+}
+
+#[test]
+fn ignored_members() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Small {
+        b: u32,
+    }
+
+    let (_, ret) = load::<Small>(concat!(
+        "Small {\n",
+        "  a: Font {\n",
+        "    list: [1, -2, 3.5, \"x\", (true, Dark)]\n",
+        "    map: {\n",
+        "      c: Rgb(1, 2, 3)\n",
+        "    }\n",
+        "  }\n",
+        "  b: 5\n",
+        "}\n",
+    ));
+    assert_eq!(ret.unwrap(), Small { b: 5 });
+}
+
+#[test]
+fn errors() {
+    let custom = |file: &TestFile, line, col, len, message: &str| ParseError::Custom {
+        span: file.span(line, col, len),
+        message: message.into(),
+    };
+
+    // Errors from the parser are unchanged
+    let (_, ret) = load::<Font>("Font {\n  family: 10\n  size: 12\n}\n");
+    assert!(matches!(
+        ret.unwrap_err(),
+        ParseError::UnexpectedToken {
+            while_parsing: "string",
+            wanted: Wanted::LitStr,
+            ..
+        }
+    ));
+
+    // Errors from serde are located at the value
+    let (l, ret) = load::<Vec<Theme>>("[Dark, Light]");
+    assert_eq!(
+        ret.unwrap_err(),
+        custom(
+            &l,
+            1,
+            7,
+            5,
+            "unknown variant `Light`, expected one of `Dark`, `Custom`, `Rgb`, `Named`"
+        )
+    );
+
+    let (l, ret) = load::<Font>("Font {\n  family: \"a\"\n  style: \"b\"\n}\n");
+    assert_eq!(
+        ret.unwrap_err(),
+        custom(
+            &l,
+            3,
+            2,
+            5,
+            "unknown field `style`, expected `family` or `size`"
+        )
+    );
+
+    let (l, ret) = load::<Font>("Font {\n  family: \"a\"\n}\n");
+    let e = ret.unwrap_err();
+    assert_eq!(e, custom(&l, 3, 0, 1, "missing field `size`"));
+    assert_eq!(
+        l.report(&e),
+        "Failed to deserialize, missing field `size` in test.logix:3:0"
+    );
+
+    let (l, ret) = load::<Vec<Upper>>("[\"AB\", \"cd\"]");
+    assert_eq!(
+        ret.unwrap_err(),
+        custom(&l, 1, 7, 4, "expected upper case letters")
+    );
+
+    let (l, ret) = load::<(u32, u32)>("(1)");
+    assert_eq!(
+        ret.unwrap_err(),
+        custom(&l, 1, 0, 1, "invalid length 1, expected a tuple of size 2")
+    );
+
+    // The whole file must be used
+    let (_, ret) = load::<Id>("Id(1) Id(2)");
+    assert!(matches!(
+        ret.unwrap_err(),
+        ParseError::UnexpectedToken {
+            while_parsing: "Id",
+            got_token: "identifier",
+            ..
+        }
+    ));
+}
+
+/// Writes `value`, checks the text and reads it back
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(value: T, src: &str) {
+    let text = LogixWriter::serialize_to_file_string(&value).unwrap();
+    assert_eq!(text, src);
+    assert_eq!(load::<T>(&text).1.unwrap(), value);
+}
+
+#[test]
+fn serialize() {
+    round_trip(
+        Config {
+            name: "hello".into(),
+            port: 8080,
+            tags: vec!["a".into(), "b".into()],
+            theme: Theme::Named {
+                name: "night".into(),
+                base: Box::new(Theme::Rgb(0, 16, 255)),
+            },
+            font: Some(Font {
+                family: "mono".into(),
+                size: 12,
+            }),
+            env: [("a".into(), -1), ("b".into(), 2)].into(),
+            pos: (1.5, -2.0),
+            id: Id(7),
+            mode: Mode,
+        },
+        concat!(
+            "Config {\n",
+            "  name: \"hello\"\n",
+            "  port: 8080\n",
+            "  tags: [\"a\", \"b\"]\n",
+            "  theme: Named {\n",
+            "    name: \"night\"\n",
+            "    base: Rgb(0, 16, 255)\n",
+            "  }\n",
+            "  font: Font {\n",
+            "    family: \"mono\"\n",
+            "    size: 12\n",
+            "  }\n",
+            "  env: {\n",
+            "    a: -1\n",
+            "    b: 2\n",
+            "  }\n",
+            "  pos: (1.5, -2.0)\n",
+            "  id: Id(7)\n",
+            "  mode: Mode\n",
+            "}\n",
+        ),
+    );
+
+    // Lists with multi-line items get one item per line, strings keep their value
+    round_trip(
+        vec![
+            Theme::Dark,
+            Theme::Named {
+                name: "a \"b\"".into(),
+                base: Box::new(Theme::Custom("x\ty".into())),
+            },
+        ],
+        concat!(
+            "[\n",
+            "  Dark\n",
+            "  Named {\n",
+            "    name: #raw\"a \"b\"\"#\n",
+            "    base: Custom(\"x\\ty\")\n",
+            "  }\n",
+            "]\n",
+        ),
+    );
+    round_trip(
+        Font {
+            family: "first\nsecond".into(),
+            size: 1,
+        },
+        concat!(
+            "Font {\n",
+            "  family: #txt\"\n",
+            "    first\n",
+            "\n",
+            "    second\n",
+            "    \"#\n",
+            "  size: 1\n",
+            "}\n",
+        ),
+    );
+
+    // `None` members are left out
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Opt {
+        a: Option<u32>,
+        b: Option<u32>,
+    }
+    round_trip(
+        Opt {
+            a: None,
+            b: Some(1),
+        },
+        "Opt {\n  b: 1\n}\n",
+    );
+}
+
+#[test]
+fn serialize_errors() {
+    assert_eq!(
+        LogixWriter::serialize_to_file_string(&None::<u32>),
+        Err(SerializeError::None)
+    );
+    assert_eq!(
+        LogixWriter::serialize_to_file_string(&[Some(1), None]),
+        Err(SerializeError::None)
+    );
+    assert_eq!(
+        LogixWriter::serialize_to_file_string(&BTreeMap::from([("a b", 1)])),
+        Err(SerializeError::InvalidIdent("a b".into()))
+    );
+    assert_eq!(
+        LogixWriter::serialize_to_file_string(&BTreeMap::from([(1, 1)])),
+        Err(SerializeError::InvalidIdent("1".into()))
+    );
+}